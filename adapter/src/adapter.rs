@@ -1,7 +1,9 @@
 use std::pin::Pin;
 
+use chrono::Utc;
 use futures::{Future, FutureExt};
 
+use domain::util::normalize_address;
 use domain::validator::message::State;
 use domain::{Asset, BigNum, Channel};
 
@@ -25,11 +27,37 @@ impl AsRef<[u8]> for BalanceRoot {
 
 pub struct SignableStateRoot<T: fmt::Display>(pub T);
 
+/// The eras, which a token can be away from the current one, to allow for clock differences
+pub const MAX_ERA_DIFFERENCE: i64 = 1;
+
+/// The era of the current time, i.e. the minutes since the Unix epoch
+pub fn current_era() -> i64 {
+    Utc::now().timestamp() / 60
+}
+
+/// The authenticated identity (`uid`) of an auth. token and the `era` it was issued in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub uid: String,
+    pub era: i64,
+}
+
+impl Session {
+    /// Whether the era of the Session is at most `MAX_ERA_DIFFERENCE` away from the current one
+    pub fn is_valid_in(&self, current_era: i64) -> bool {
+        (current_era - self.era).abs() <= MAX_ERA_DIFFERENCE
+    }
+}
+
 pub type AdapterFuture<T> = Pin<Box<dyn Future<Output = Result<T, AdapterError>> + Send>>;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum AdapterError {
     Authentication(String),
+    /// The adapter could not be set up, e.g. the keystore could not be loaded
+    Configuration(String),
+    /// The state root could not be signed
+    Signature(String),
 }
 
 impl Error for AdapterError {}
@@ -38,6 +66,8 @@ impl fmt::Display for AdapterError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AdapterError::Authentication(error) => write!(f, "Authentication error: {}", error),
+            AdapterError::Configuration(error) => write!(f, "Configuration error: {}", error),
+            AdapterError::Signature(error) => write!(f, "Signature error: {}", error),
         }
     }
 }
@@ -68,6 +98,9 @@ pub trait Adapter: SanityChecker {
     /// Gets authentication for specific validator
     fn get_auth(&self, validator: &str) -> AdapterFuture<String>;
 
+    /// Verifies an authentication token and returns the Session of the identity it belongs to
+    fn verify_auth(&self, token: &str) -> AdapterFuture<Session>;
+
     fn signable_state_root(
         channel_id: ChannelId,
         balance_root: BalanceRoot,
@@ -84,6 +117,8 @@ pub struct Config {
     pub minimal_fee: BigNum,
 }
 
+/// The identity & the whitelisted validators and creators are normalized (see `normalize_address`),
+/// so they can be compared as they are to the normalized addresses of the channels
 pub struct ConfigBuilder {
     identity: String,
     validators_whitelist: Vec<String>,
@@ -96,7 +131,7 @@ pub struct ConfigBuilder {
 impl ConfigBuilder {
     pub fn new(identity: &str) -> Self {
         Self {
-            identity: identity.to_string(),
+            identity: normalize_address(identity),
             validators_whitelist: Vec::new(),
             creators_whitelist: Vec::new(),
             assets_whitelist: Vec::new(),
@@ -106,12 +141,18 @@ impl ConfigBuilder {
     }
 
     pub fn set_validators_whitelist(mut self, validators: &[&str]) -> Self {
-        self.validators_whitelist = validators.iter().map(|slice| slice.to_string()).collect();
+        self.validators_whitelist = validators
+            .iter()
+            .map(|slice| normalize_address(slice))
+            .collect();
         self
     }
 
     pub fn set_creators_whitelist(mut self, creators: &[&str]) -> Self {
-        self.creators_whitelist = creators.iter().map(|slice| slice.to_string()).collect();
+        self.creators_whitelist = creators
+            .iter()
+            .map(|slice| normalize_address(slice))
+            .collect();
         self
     }
 
@@ -1,5 +1,6 @@
 use crate::adapter::{
-    Adapter, AdapterError, AdapterFuture, BalanceRoot, ChannelId, Config, SignableStateRoot,
+    current_era, Adapter, AdapterError, AdapterFuture, BalanceRoot, ChannelId, Config, Session,
+    SignableStateRoot,
 };
 use crate::sanity::SanityChecker;
 
@@ -145,7 +146,9 @@ impl<'a> Adapter for DummyAdapter<'a> {
         ok(is_same).boxed()
     }
 
-    /// Finds the auth. token in the HashMap of DummyParticipants if exists
+    /// Finds the auth. token of our identity in the HashMap of DummyParticipants,
+    /// if the `validator` is one of them as well. Like the Ethereum tokens, which are issued by
+    /// our identity for the `validator`, the token authenticates us to the Sentry of the `validator`
     ///
     /// Example:
     ///
@@ -173,14 +176,63 @@ impl<'a> Adapter for DummyAdapter<'a> {
     /// # });
     /// ```
     fn get_auth(&self, validator: &str) -> AdapterFuture<String> {
+        let participant = |identity: &str| {
+            self.participants
+                .values()
+                .find(|participant| participant.identity == identity)
+        };
+        let future = match (participant(validator), participant(&self.config.identity)) {
+            (Some(_), Some(ours)) => ok(ours.token.to_string()),
+            _ => err(AdapterError::Authentication(
+                "Identity not found".to_string(),
+            )),
+        };
+
+        future.boxed()
+    }
+
+    /// Finds the identity of the DummyParticipant with the given auth. token,
+    /// the dummy tokens never expire, so their Session is always of the current era
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # futures::executor::block_on(async {
+    /// use std::collections::HashMap;
+    /// use adapter::dummy::{DummyParticipant, DummyAdapter};
+    /// use adapter::{current_era, ConfigBuilder, Adapter};
+    ///
+    /// let mut participants = HashMap::new();
+    /// participants.insert(
+    ///     "identity_key",
+    ///     DummyParticipant {
+    ///         identity: "identity".to_string(),
+    ///         token: "token".to_string(),
+    ///     },
+    /// );
+    ///
+    /// let adapter = DummyAdapter {
+    ///     config: ConfigBuilder::new("identity").build(),
+    ///     participants,
+    /// };
+    ///
+    /// let session = await!(adapter.verify_auth("token")).expect("The token should be verified");
+    /// assert_eq!("identity", session.uid);
+    /// assert!(session.is_valid_in(current_era()));
+    /// # });
+    /// ```
+    fn verify_auth(&self, token: &str) -> AdapterFuture<Session> {
         let participant = self
             .participants
-            .iter()
-            .find(|&(_, participant)| participant.identity == validator);
+            .values()
+            .find(|participant| participant.token == token);
         let future = match participant {
-            Some((_, participant)) => ok(participant.token.to_string()),
+            Some(participant) => ok(Session {
+                uid: participant.identity.to_string(),
+                era: current_era(),
+            }),
             None => err(AdapterError::Authentication(
-                "Identity not found".to_string(),
+                "Token not found".to_string(),
             )),
         };
 
@@ -268,4 +320,48 @@ mod test {
             );
         });
     }
+
+    #[test]
+    fn get_auth_issues_the_token_of_our_identity_for_the_validator() {
+        futures::executor::block_on(async {
+            let mut participants = HashMap::new();
+            for identity in &["leader", "follower"] {
+                participants.insert(
+                    *identity,
+                    DummyParticipant {
+                        identity: identity.to_string(),
+                        token: format!("{} token", identity),
+                    },
+                );
+            }
+            let adapter = DummyAdapter {
+                config: ConfigBuilder::new("leader").build(),
+                participants,
+            };
+
+            assert_eq!(
+                Ok("leader token".to_string()),
+                await!(adapter.get_auth("follower"))
+            );
+            assert_eq!(
+                Ok("leader token".to_string()),
+                await!(adapter.get_auth("leader"))
+            );
+        });
+    }
+
+    #[test]
+    fn verify_auth_with_unknown_token() {
+        futures::executor::block_on(async {
+            let adapter = DummyAdapter {
+                config: ConfigBuilder::new("identity").build(),
+                participants: HashMap::new(),
+            };
+
+            assert_eq!(
+                Err(AdapterError::Authentication("Token not found".to_string())),
+                await!(adapter.verify_auth("token"))
+            );
+        });
+    }
 }
@@ -0,0 +1,522 @@
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use std::str::FromStr;
+
+use ethsign::{KeyFile, Protected, SecretKey};
+use futures::future::{err, ok, FutureExt};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+use tiny_keccak::keccak256;
+
+use domain::validator::message::State;
+
+use crate::adapter::{
+    current_era, Adapter, AdapterError, AdapterFuture, BalanceRoot, ChannelId, Config,
+    ConfigBuilder, Session, SignableStateRoot,
+};
+use crate::sanity::SanityChecker;
+
+pub mod ewt;
+
+/// The `v`, `r` & `s` of a secp256k1 signature, where `v` is `27` or `28` as `eth_sign` returns it.
+/// It's (de)serialized as the `0x` prefixed hex of `r`, `s` & `v`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthereumSignature {
+    pub v: u8,
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+impl EthereumSignature {
+    /// The 65 bytes of `r`, `s` & `v`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(65);
+        bytes.extend_from_slice(&self.r);
+        bytes.extend_from_slice(&self.s);
+        bytes.push(self.v);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AdapterError> {
+        if bytes.len() != 65 {
+            return Err(AdapterError::Signature(
+                "Expected a signature of 65 bytes".to_string(),
+            ));
+        }
+
+        let mut r = [0; 32];
+        r.copy_from_slice(&bytes[..32]);
+        let mut s = [0; 32];
+        s.copy_from_slice(&bytes[32..64]);
+
+        Ok(Self { v: bytes[64], r, s })
+    }
+}
+
+impl fmt::Display for EthereumSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl FromStr for EthereumSignature {
+    type Err = AdapterError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(&decode_hex(value, 65)?)
+    }
+}
+
+/// The keccak256 hash of the Channel id & the balance root.
+/// It's (de)serialized as a `0x` prefixed hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthereumStateRoot(pub [u8; 32]);
+
+impl fmt::Display for EthereumStateRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.0))
+    }
+}
+
+impl FromStr for EthereumStateRoot {
+    type Err = AdapterError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut state_root = [0; 32];
+        state_root.copy_from_slice(&decode_hex(value, 32)?);
+
+        Ok(Self(state_root))
+    }
+}
+
+impl AsRef<[u8]> for EthereumStateRoot {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+macro_rules! impl_hex_serde {
+    ($type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+
+                value.parse().map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_hex_serde!(EthereumSignature);
+impl_hex_serde!(EthereumStateRoot);
+
+#[derive(Clone)]
+pub struct EthereumState {}
+impl State for EthereumState {
+    type Signature = EthereumSignature;
+    type StateRoot = EthereumStateRoot;
+}
+
+pub struct EthereumAdapter {
+    /// The `identity` is the `0x` prefixed, lowercase hex address of the key
+    pub config: Config,
+    secret: SecretKey,
+}
+
+impl EthereumAdapter {
+    pub fn new(secret: SecretKey) -> Self {
+        let identity = format!("0x{}", hex::encode(secret.public().address()));
+
+        Self {
+            config: ConfigBuilder::new(&identity).build(),
+            secret,
+        }
+    }
+
+    /// Decrypts the secret key of a JSON keystore (the Web3 Secret Storage format) with the password
+    pub fn from_keystore(keystore: &Path, password: &str) -> Result<Self, AdapterError> {
+        let file = File::open(keystore).map_err(|error| {
+            AdapterError::Configuration(format!("Opening the keystore failed: {}", error))
+        })?;
+        let key_file: KeyFile = serde_json::from_reader(file).map_err(|error| {
+            AdapterError::Configuration(format!("Invalid keystore JSON: {}", error))
+        })?;
+
+        let password: Protected = password.into();
+        let secret = key_file.to_secret_key(&password).map_err(|error| {
+            AdapterError::Configuration(format!("Decrypting the keystore failed: {:?}", error))
+        })?;
+
+        Ok(Self::new(secret))
+    }
+
+    /// The Session of the Ethereum Web Token, if it's valid in the current era
+    fn session(&self, token: &str, current_era: i64) -> Result<Session, AdapterError> {
+        let payload = ewt::verify(token)?;
+
+        if !payload.id.eq_ignore_ascii_case(&self.config.identity) {
+            return Err(AdapterError::Authentication(
+                "The token is not intended for this validator".to_string(),
+            ));
+        }
+
+        // the privileges of the address on an identity contract can't be checked yet,
+        // so acting on behalf of one is rejected rather than trusted
+        if payload.identity.is_some() {
+            return Err(AdapterError::Authentication(
+                "Tokens on behalf of an identity are not supported".to_string(),
+            ));
+        }
+
+        // the verified `address` is the recovered (lowercase) signer of the token
+        let session = Session {
+            uid: payload.address,
+            era: payload.era,
+        };
+        if !session.is_valid_in(current_era) {
+            return Err(AdapterError::Authentication(
+                "The token era has expired".to_string(),
+            ));
+        }
+
+        Ok(session)
+    }
+}
+
+impl SanityChecker for EthereumAdapter {}
+
+impl Adapter for EthereumAdapter {
+    type State = EthereumState;
+
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Signs the state root as `eth_sign` does, i.e. prefixed with the Ethereum signed message header
+    fn sign(
+        &self,
+        state_root: &<Self::State as State>::StateRoot,
+    ) -> AdapterFuture<<Self::State as State>::Signature> {
+        let future = match eth_sign(&self.secret, state_root.as_ref()) {
+            Ok(signature) => ok(signature),
+            Err(error) => err(error),
+        };
+
+        future.boxed()
+    }
+
+    /// Recovers the address of the `eth_sign` signature and compares it to the signer address.
+    /// A malformed signer or signature is never verified.
+    fn verify(
+        &self,
+        signer: &str,
+        state_root: &<Self::State as State>::StateRoot,
+        signature: &<Self::State as State>::Signature,
+    ) -> AdapterFuture<bool> {
+        let is_verified = match recover_address(state_root.as_ref(), signature) {
+            Some(address) => decode_hex(signer, 20)
+                .map(|signer| signer.as_slice() == address)
+                .unwrap_or(false),
+            None => false,
+        };
+
+        ok(is_verified).boxed()
+    }
+
+    /// Issues an Ethereum Web Token for the validator in the current era
+    fn get_auth(&self, validator: &str) -> AdapterFuture<String> {
+        let payload = ewt::Payload {
+            id: validator.to_string(),
+            era: current_era(),
+            address: self.config.identity.clone(),
+            identity: None,
+        };
+
+        let future = match ewt::sign(&self.secret, &payload) {
+            Ok(token) => ok(token),
+            Err(error) => err(error),
+        };
+
+        future.boxed()
+    }
+
+    /// Verifies an Ethereum Web Token, which is intended for this adapter identity
+    /// and has been issued in the current era or in the one before or after it
+    fn verify_auth(&self, token: &str) -> AdapterFuture<Session> {
+        let future = match self.session(token, current_era()) {
+            Ok(session) => ok(session),
+            Err(error) => err(error),
+        };
+
+        future.boxed()
+    }
+
+    /// The keccak256 hash of the Channel id, followed by the balance root,
+    /// as the AdEx protocol contracts hash them
+    fn signable_state_root(
+        channel_id: ChannelId,
+        balance_root: BalanceRoot,
+    ) -> SignableStateRoot<<Self::State as State>::StateRoot> {
+        let mut encoded = [0; 64];
+        encoded[..32].copy_from_slice(channel_id.as_ref());
+        encoded[32..].copy_from_slice(balance_root.as_ref());
+
+        SignableStateRoot(EthereumStateRoot(keccak256(&encoded)))
+    }
+}
+
+/// The hash that `eth_sign` signs: the keccak256 of the Ethereum signed message header & the message
+fn eth_sign_hash(message: &[u8]) -> [u8; 32] {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+
+    keccak256(&prefixed)
+}
+
+fn eth_sign(secret: &SecretKey, message: &[u8]) -> Result<EthereumSignature, AdapterError> {
+    let signature = secret
+        .sign(&eth_sign_hash(message))
+        .map_err(|error| AdapterError::Signature(format!("{:?}", error)))?;
+
+    Ok(EthereumSignature {
+        v: signature.v + 27,
+        r: signature.r,
+        s: signature.s,
+    })
+}
+
+fn recover_address(message: &[u8], signature: &EthereumSignature) -> Option<[u8; 20]> {
+    if signature.v < 27 {
+        return None;
+    }
+
+    let signature = ethsign::Signature {
+        v: signature.v - 27,
+        r: signature.r,
+        s: signature.s,
+    };
+    let public = signature.recover(&eth_sign_hash(message)).ok()?;
+
+    Some(*public.address())
+}
+
+/// Decodes the hex, with or without a `0x` prefix, into exactly `length` bytes
+fn decode_hex(value: &str, length: usize) -> Result<Vec<u8>, AdapterError> {
+    let value = if value.starts_with("0x") {
+        &value[2..]
+    } else {
+        value
+    };
+
+    match hex::decode(value) {
+        Ok(ref bytes) if bytes.len() == length => Ok(bytes.clone()),
+        _ => Err(AdapterError::Signature(format!(
+            "Expected a hex of {} bytes",
+            length
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// The private key of the `web3.eth.accounts.sign()` documentation
+    const SECRET: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const ADDRESS: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+
+    fn adapter(secret: &str) -> EthereumAdapter {
+        let secret = hex::decode(secret).expect("Invalid secret hex");
+
+        EthereumAdapter::new(SecretKey::from_raw(&secret).expect("Invalid secret"))
+    }
+
+    fn keystore() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/resources/keystore.json")
+    }
+
+    fn hex_address(address: &str) -> [u8; 20] {
+        let mut bytes = [0; 20];
+        bytes.copy_from_slice(&decode_hex(address, 20).unwrap());
+
+        bytes
+    }
+
+    #[test]
+    fn signs_as_eth_sign() {
+        let secret = SecretKey::from_raw(&hex::decode(SECRET).unwrap()).expect("Invalid secret");
+
+        let signature = eth_sign(&secret, b"Some data").expect("Signing failed");
+
+        let expected = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+        assert_eq!(expected, signature.to_string());
+        assert_eq!(
+            Some(hex_address(ADDRESS)),
+            recover_address(b"Some data", &signature)
+        );
+    }
+
+    #[test]
+    fn the_identity_is_the_address_of_the_key() {
+        assert_eq!(ADDRESS, adapter(SECRET).config.identity);
+    }
+
+    #[test]
+    fn signs_the_state_root_and_verifies_the_signer() {
+        futures::executor::block_on(async {
+            let adapter = adapter(SECRET);
+            let state_root =
+                EthereumAdapter::signable_state_root(ChannelId([1; 32]), BalanceRoot([2; 32])).0;
+
+            let signature = await!(adapter.sign(&state_root)).expect("Signing failed");
+
+            let checksummed = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+            let signer = adapter.verify(ADDRESS, &state_root, &signature);
+            assert_eq!(Ok(true), await!(signer));
+            let checksummed_signer = adapter.verify(checksummed, &state_root, &signature);
+            assert_eq!(Ok(true), await!(checksummed_signer));
+
+            let other_state_root = EthereumStateRoot([3; 32]);
+            let other_signer = adapter.verify(ADDRESS, &other_state_root, &signature);
+            assert_eq!(Ok(false), await!(other_signer));
+            let invalid_signer = adapter.verify("leader", &state_root, &signature);
+            assert_eq!(Ok(false), await!(invalid_signer));
+        })
+    }
+
+    #[test]
+    fn does_not_verify_the_signature_of_another_key() {
+        futures::executor::block_on(async {
+            let other = adapter("0101010101010101010101010101010101010101010101010101010101010101");
+            let state_root = EthereumStateRoot([1; 32]);
+
+            let signature = await!(other.sign(&state_root)).expect("Signing failed");
+
+            let is_verified = await!(adapter(SECRET).verify(ADDRESS, &state_root, &signature));
+            assert_eq!(Ok(false), is_verified);
+        })
+    }
+
+    #[test]
+    fn serializes_the_signature_and_the_state_root_as_hex() {
+        let state_root = EthereumStateRoot([0xab; 32]);
+        let json = serde_json::to_string(&state_root).expect("Serialization failed");
+
+        assert_eq!(format!("\"0x{}\"", "ab".repeat(32)), json);
+        let deserialized: EthereumStateRoot =
+            serde_json::from_str(&json).expect("Deserialization failed");
+        assert_eq!(state_root, deserialized);
+
+        let signature = EthereumSignature {
+            v: 27,
+            r: [1; 32],
+            s: [2; 32],
+        };
+        let json = serde_json::to_string(&signature).expect("Serialization failed");
+        let deserialized: EthereumSignature =
+            serde_json::from_str(&json).expect("Deserialization failed");
+        assert_eq!(signature, deserialized);
+
+        assert!(serde_json::from_str::<EthereumSignature>("\"0x1b\"").is_err());
+    }
+
+    #[test]
+    fn verifies_the_auth_token_of_another_validator() {
+        futures::executor::block_on(async {
+            let other = adapter("0101010101010101010101010101010101010101010101010101010101010101");
+            let adapter = adapter(SECRET);
+
+            let token = await!(other.get_auth(ADDRESS)).expect("Issuing the token failed");
+
+            let session = await!(adapter.verify_auth(&token)).expect("The token should be valid");
+            assert_eq!(other.config.identity, session.uid);
+
+            let not_for_us = await!(other.get_auth("0xce07cbb7e054514d590a0262c93070d838bfba2e"))
+                .expect("Issuing the token failed");
+            match await!(adapter.verify_auth(&not_for_us)) {
+                Err(AdapterError::Authentication(_)) => {}
+                result => panic!("Expected an Authentication error, got {:?}", result),
+            }
+        })
+    }
+
+    #[test]
+    fn the_session_uid_is_the_lowercase_signer_of_the_auth_token() {
+        let other = adapter("0101010101010101010101010101010101010101010101010101010101010101");
+        let adapter = adapter(SECRET);
+        let payload = ewt::Payload {
+            id: ADDRESS.to_string(),
+            era: 26_000_000,
+            address: other.config.identity.to_uppercase().replacen("0X", "0x", 1),
+            identity: None,
+        };
+        let token = ewt::sign(&other.secret, &payload).expect("Issuing the token failed");
+
+        let session = adapter
+            .session(&token, 26_000_000)
+            .expect("The token should be valid");
+        assert_eq!(other.config.identity, session.uid);
+    }
+
+    #[test]
+    fn rejects_the_auth_token_on_behalf_of_an_identity() {
+        let other = adapter("0101010101010101010101010101010101010101010101010101010101010101");
+        let adapter = adapter(SECRET);
+        let payload = ewt::Payload {
+            id: ADDRESS.to_string(),
+            era: 26_000_000,
+            address: other.config.identity.clone(),
+            identity: Some("0xce07cbb7e054514d590a0262c93070d838bfba2e".to_string()),
+        };
+        let token = ewt::sign(&other.secret, &payload).expect("Issuing the token failed");
+
+        match adapter.session(&token, 26_000_000) {
+            Err(AdapterError::Authentication(_)) => {}
+            result => panic!("Expected an Authentication error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn the_auth_token_expires_after_an_era() {
+        let other = adapter("0101010101010101010101010101010101010101010101010101010101010101");
+        let adapter = adapter(SECRET);
+        let payload = ewt::Payload {
+            id: ADDRESS.to_string(),
+            era: 26_000_000,
+            address: other.config.identity.clone(),
+            identity: None,
+        };
+        let token = ewt::sign(&other.secret, &payload).expect("Issuing the token failed");
+
+        for era in &[25_999_999, 26_000_000, 26_000_001] {
+            let session = adapter
+                .session(&token, *era)
+                .expect("The token should be valid");
+            assert_eq!(26_000_000, session.era);
+        }
+
+        assert!(adapter.session(&token, 26_000_002).is_err());
+        assert!(adapter.session(&token, 25_999_998).is_err());
+    }
+
+    #[test]
+    fn loads_the_key_of_the_keystore() {
+        let adapter = EthereumAdapter::from_keystore(&keystore(), "adex").expect("Loading failed");
+        assert_eq!(ADDRESS, adapter.config.identity);
+
+        match EthereumAdapter::from_keystore(&keystore(), "wrong password") {
+            Err(AdapterError::Configuration(_)) => {}
+            _ => panic!("Expected a Configuration error"),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Ethereum Web Tokens (EWT) are JWT-like tokens, which are signed with `eth_sign` by the Ethereum key.
+//! A token is `header.payload.signature`, each part is URL-safe base64 without padding,
+//! where the signature is of the keccak256 hash of `header.payload`.
+use ethsign::SecretKey;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tiny_keccak::keccak256;
+
+use crate::adapter::AdapterError;
+
+use super::{eth_sign, recover_address, EthereumSignature};
+
+pub const ETH_ALG: &str = "ETH";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    #[serde(rename = "type")]
+    pub header_type: String,
+    pub alg: String,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            header_type: "JWT".to_string(),
+            alg: ETH_ALG.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payload {
+    /// The identity of the validator, which the token is intended for
+    pub id: String,
+    /// The era (minutes since the Unix epoch) in which the token was issued
+    pub era: i64,
+    /// The `0x` prefixed address of the key, which signed the token
+    pub address: String,
+    /// The identity contract, on behalf of which the address is acting (optional),
+    /// the adapter rejects such tokens until the privileges on the identity are checked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+}
+
+/// Signs the header & the payload with the key, the payload address should be the key address
+pub fn sign(secret: &SecretKey, payload: &Payload) -> Result<String, AdapterError> {
+    let header = encode_json(&Header::default())?;
+    let payload = encode_json(payload)?;
+    let message = format!("{}.{}", header, payload);
+
+    let signature = eth_sign(secret, &keccak256(message.as_bytes()))?;
+    let signature = base64::encode_config(&signature.to_bytes(), base64::URL_SAFE_NO_PAD);
+
+    Ok(format!("{}.{}", message, signature))
+}
+
+/// Verifies that the token has an `ETH` header and it's signed by the address of the payload
+pub fn verify(token: &str) -> Result<Payload, AdapterError> {
+    let parts: Vec<&str> = token.split('.').collect();
+    let (header, payload, signature) = match parts.as_slice() {
+        [header, payload, signature] => (*header, *payload, *signature),
+        _ => return Err(authentication("The token should have 3 parts")),
+    };
+
+    let decoded_header: Header = decode_json(header)?;
+    if decoded_header.alg != ETH_ALG {
+        return Err(authentication("The token alg should be ETH"));
+    }
+    let decoded_payload: Payload = decode_json(payload)?;
+
+    let signature = base64::decode_config(signature, base64::URL_SAFE_NO_PAD)
+        .map_err(|_| authentication("The token signature is not base64"))?;
+    let signature = EthereumSignature::from_bytes(&signature)
+        .map_err(|_| authentication("The token signature should be 65 bytes"))?;
+
+    let message = format!("{}.{}", header, payload);
+    let signer = recover_address(&keccak256(message.as_bytes()), &signature)
+        .map(|address| format!("0x{}", hex::encode(address)));
+
+    match signer {
+        // the address of the payload is replaced with the (lowercase) recovered signer,
+        // since it's only compared case-insensitively
+        Some(signer) if signer.eq_ignore_ascii_case(&decoded_payload.address) => Ok(Payload {
+            address: signer,
+            ..decoded_payload
+        }),
+        _ => Err(authentication(
+            "The token is not signed by the payload address",
+        )),
+    }
+}
+
+fn encode_json<T: Serialize>(value: &T) -> Result<String, AdapterError> {
+    let json =
+        serde_json::to_vec(value).map_err(|error| AdapterError::Signature(error.to_string()))?;
+
+    Ok(base64::encode_config(&json, base64::URL_SAFE_NO_PAD))
+}
+
+fn decode_json<T: DeserializeOwned>(part: &str) -> Result<T, AdapterError> {
+    let json = base64::decode_config(part, base64::URL_SAFE_NO_PAD)
+        .map_err(|_| authentication("The token part is not base64"))?;
+
+    serde_json::from_slice(&json).map_err(|_| authentication("The token part is not valid JSON"))
+}
+
+fn authentication(message: &str) -> AdapterError {
+    AdapterError::Authentication(message.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ADDRESS: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+
+    fn secret() -> SecretKey {
+        let secret = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+        SecretKey::from_raw(&hex::decode(secret).unwrap()).expect("Invalid secret")
+    }
+
+    fn payload(address: &str) -> Payload {
+        Payload {
+            id: "0xce07cbb7e054514d590a0262c93070d838bfba2e".to_string(),
+            era: 26_000_000,
+            address: address.to_string(),
+            identity: None,
+        }
+    }
+
+    #[test]
+    fn signs_and_verifies_the_payload() {
+        let payload = Payload {
+            identity: Some("0x033ed90e0fec3f3ea1c9b005c724d704501e0196".to_string()),
+            ..payload(ADDRESS)
+        };
+
+        let token = sign(&secret(), &payload).expect("Signing failed");
+
+        assert_eq!(3, token.split('.').count());
+        assert_eq!(Ok(payload), verify(&token));
+    }
+
+    #[test]
+    fn the_header_is_a_jwt_with_the_eth_alg() {
+        let token = sign(&secret(), &payload(ADDRESS)).expect("Signing failed");
+        let header = token
+            .split('.')
+            .next()
+            .expect("The token should have a header");
+
+        let expected =
+            base64::encode_config(r#"{"type":"JWT","alg":"ETH"}"#, base64::URL_SAFE_NO_PAD);
+        assert_eq!(expected, header);
+    }
+
+    #[test]
+    fn verifies_the_payload_with_the_recovered_address() {
+        let checksummed = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+        let token = sign(&secret(), &payload(checksummed)).expect("Signing failed");
+
+        let verified = verify(&token).expect("The checksummed address is the signer");
+        assert_eq!(ADDRESS, verified.address);
+    }
+
+    #[test]
+    fn does_not_verify_the_payload_of_another_address() {
+        let other_address = "0xce07cbb7e054514d590a0262c93070d838bfba2e";
+        let token = sign(&secret(), &payload(other_address)).expect("Signing failed");
+
+        assert!(verify(&token).is_err());
+    }
+
+    #[test]
+    fn does_not_verify_a_tampered_token() {
+        let token = sign(&secret(), &payload(ADDRESS)).expect("Signing failed");
+        let parts: Vec<&str> = token.split('.').collect();
+
+        let other_payload = Payload {
+            era: 26_000_001,
+            ..payload(ADDRESS)
+        };
+        let other_payload = encode_json(&other_payload).expect("Encoding failed");
+        let tampered = format!("{}.{}.{}", parts[0], other_payload, parts[2]);
+
+        match verify(&tampered) {
+            Err(AdapterError::Authentication(_)) => {}
+            result => panic!("Expected an Authentication error, got {:?}", result),
+        }
+
+        assert!(verify(&format!("{}.{}", parts[0], parts[1])).is_err());
+        assert!(verify("not.a.token").is_err());
+    }
+}
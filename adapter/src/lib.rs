@@ -9,4 +9,6 @@ pub use self::sanity::*;
 mod adapter;
 #[cfg(any(test, feature = "dummy-adapter"))]
 pub mod dummy;
+#[cfg(feature = "ethereum-adapter")]
+pub mod ethereum;
 mod sanity;
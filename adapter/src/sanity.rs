@@ -95,7 +95,17 @@ pub enum SanityError {
 
 impl fmt::Display for SanityError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Sanity error",)
+        let message = match self {
+            SanityError::AdapterNotIncluded => "the adapter identity is not a channel validator",
+            SanityError::PassedValidUntil => "the channel validUntil has passed",
+            SanityError::UnlistedValidator => "a channel validator is not whitelisted",
+            SanityError::UnlistedCreator => "the channel creator is not whitelisted",
+            SanityError::UnlistedAsset => "the channel deposit asset is not whitelisted",
+            SanityError::MinimumDepositNotMet => "the channel deposit is less than the minimum",
+            SanityError::MinimumValidatorFeeNotMet => "the validator fee is less than the minimum",
+        };
+
+        write!(f, "Sanity error: {}", message)
     }
 }
 
@@ -2,8 +2,10 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{BigNum, Channel, DomainError, ValidatorDesc};
+use crate::merkle_tree::{MerkleItem, MerkleTree};
+use crate::{BigNum, Channel, DomainError, Event, EventAggregate, ValidatorDesc};
 use num::rational::Ratio;
+use tiny_keccak::keccak256;
 
 type InnerBTreeMap = BTreeMap<String, BigNum>;
 
@@ -11,7 +13,121 @@ type InnerBTreeMap = BTreeMap<String, BigNum>;
 #[serde(transparent)]
 pub struct BalancesMap(InnerBTreeMap);
 
+impl From<InnerBTreeMap> for BalancesMap {
+    fn from(balances: InnerBTreeMap) -> Self {
+        Self(balances)
+    }
+}
+
 impl BalancesMap {
+    /// The sum of all the balances
+    pub fn total(&self) -> BigNum {
+        self.0.values().sum()
+    }
+
+    /// Adds the payouts of the aggregates to the balances in order,
+    /// the payouts which would exceed the Channel deposit are capped to the remaining amount.
+    /// An aggregate with `CLOSE` events pays the remaining deposit back to the creator after its payouts,
+    /// so the channel is closed only once and the later payouts are capped to nothing.
+    pub fn merge_aggregates(&self, aggregates: &[EventAggregate], on_channel: &Channel) -> Self {
+        let mut balances = self.0.clone();
+        let mut remaining = &on_channel.deposit_amount - &self.total();
+        let zero = BigNum::from(0);
+
+        for aggregate in aggregates {
+            let payouts = aggregate
+                .events
+                .values()
+                .flat_map(|aggregate_events| aggregate_events.event_payouts.iter());
+
+            for (earner, payout) in payouts {
+                let to_add = std::cmp::min(&remaining, payout).clone();
+                if to_add == zero {
+                    continue;
+                }
+
+                remaining = &remaining - &to_add;
+                *balances
+                    .entry(earner.clone())
+                    .or_insert_with(|| zero.clone()) += &to_add;
+            }
+
+            let is_closed = aggregate.events.contains_key(Event::Close.event_type());
+            if is_closed && remaining > zero {
+                *balances
+                    .entry(on_channel.creator.clone())
+                    .or_insert_with(|| zero.clone()) += &remaining;
+                remaining = zero.clone();
+            }
+        }
+
+        Self(balances)
+    }
+
+    /// Whether the transition from these (previously approved) balances to the `next` ones
+    /// is valid by OUTPACE: no balance decreases and the total doesn't exceed the Channel deposit
+    pub fn is_valid_transition(&self, next: &BalancesMap, on_channel: &Channel) -> bool {
+        let next_total = next.total();
+        if next_total < self.total() || next_total > on_channel.deposit_amount {
+            return false;
+        }
+
+        self.0
+            .iter()
+            .all(|(earner, amount)| next.0.get(earner).map_or(false, |next| next >= amount))
+    }
+
+    /// The health of the `proposed` balances compared to these ones (our own accounting)
+    /// in promilles, i.e. how much of our total payouts the proposed balances include as well.
+    /// Without any accounted payouts, the proposed balances are fully healthy.
+    pub fn health_promilles(&self, proposed: &BalancesMap) -> BigNum {
+        let our_total = self.total();
+        let zero = BigNum::from(0);
+        if our_total == zero {
+            return BigNum::from(1_000);
+        }
+
+        let mut included = BigNum::from(0);
+        for (earner, our_amount) in self.0.iter() {
+            let proposed_amount = proposed.0.get(earner).unwrap_or(&zero);
+            included += std::cmp::min(our_amount, proposed_amount);
+        }
+
+        included * &BigNum::from(1_000) / &our_total
+    }
+
+    /// Whether the health of the `proposed` balances reaches the threshold (in promilles)
+    pub fn is_healthy(&self, proposed: &BalancesMap, threshold_promilles: u64) -> bool {
+        self.health_promilles(proposed) >= BigNum::from(threshold_promilles)
+    }
+
+    /// The balance of the earner, if it has any
+    pub fn get(&self, earner: &str) -> Option<&BigNum> {
+        self.0.get(earner)
+    }
+
+    /// The Merkle proof of the earner's balance leaf, if the earner has a balance
+    pub fn proof(&self, earner: &str) -> Result<Option<Vec<MerkleItem>>, DomainError> {
+        let amount = match self.0.get(earner) {
+            Some(amount) => amount,
+            None => return Ok(None),
+        };
+        let leaf = balance_leaf(earner, amount)?;
+
+        Ok(self.merkle_tree()?.proof(&leaf))
+    }
+
+    /// The Merkle tree of the balance leaves, its root is the balance root of the state
+    pub fn merkle_tree(&self) -> Result<MerkleTree, DomainError> {
+        let leaves = self
+            .0
+            .iter()
+            .map(|(earner, amount)| balance_leaf(earner, amount))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(MerkleTree::new(&leaves))
+    }
+
     pub fn apply_fees(&self, on_channel: &Channel) -> Result<Self, DomainError> {
         let distribution = Distribution::new(&self.0, &on_channel)?;
 
@@ -65,6 +181,42 @@ impl BalancesMap {
     }
 }
 
+/// The keccak256 hash of the abi-packed `(address earner, uint256 amount)`,
+/// the same way the protocol contracts hash a balance leaf.
+/// The earners which aren't addresses (e.g. the dummy identities) are packed as UTF-8 bytes.
+pub fn balance_leaf(earner: &str, amount: &BigNum) -> Result<MerkleItem, DomainError> {
+    let amount_bytes = amount.to_bytes_be();
+    if amount_bytes.len() > 32 {
+        return Err(DomainError::InvalidArgument(format!(
+            "The balance of {} doesn't fit in uint256",
+            earner
+        )));
+    }
+
+    let address = if earner.len() == 42 && earner.starts_with("0x") {
+        hex::decode(&earner[2..]).ok()
+    } else {
+        None
+    };
+    let mut packed = address.unwrap_or_else(|| earner.as_bytes().to_vec());
+    packed.resize(packed.len() + 32 - amount_bytes.len(), 0);
+    packed.extend_from_slice(&amount_bytes);
+
+    Ok(keccak256(&packed))
+}
+
+/// Whether the proof proves that the earner's balance is in the balances with the `balance_root`
+pub fn verify_balance_proof(
+    earner: &str,
+    amount: &BigNum,
+    proof: &[MerkleItem],
+    balance_root: &MerkleItem,
+) -> Result<bool, DomainError> {
+    let leaf = balance_leaf(earner, amount)?;
+
+    Ok(MerkleTree::verify(proof, balance_root, &leaf))
+}
+
 #[derive(Debug)]
 struct Distribution {
     pub deposit: BigNum,
@@ -136,8 +288,29 @@ impl Distribution {
     }
 }
 
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures {
+    use crate::BigNum;
+
+    use super::BalancesMap;
+
+    pub fn get_balances_map(balances: &[(&str, u64)]) -> BalancesMap {
+        let tree = balances
+            .iter()
+            .map(|(earner, amount)| (earner.to_string(), BigNum::from(*amount)))
+            .collect();
+
+        BalancesMap(tree)
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::convert::TryFrom;
+
+    use chrono::Utc;
+
+    use super::fixtures::get_balances_map;
     use super::*;
     use crate::channel::fixtures::{get_channel, get_channel_spec, ValidatorsOption};
     use crate::validator::fixtures::get_validator;
@@ -354,4 +527,158 @@ mod test {
         );
     }
 
+    #[test]
+    fn merges_the_aggregate_payouts_up_to_the_deposit() {
+        let mut channel = get_channel("merge aggregates", &None, None);
+        channel.deposit_amount = 100.into();
+
+        let mut aggregate = EventAggregate::new(channel.id, Utc::now());
+        let impressions = aggregate
+            .events
+            .entry("IMPRESSION".to_string())
+            .or_default();
+        impressions.event_payouts.insert("a".to_string(), 30.into());
+        impressions.event_payouts.insert("b".to_string(), 60.into());
+
+        let balances = BalancesMap(vec![("a".to_string(), 5.into())].into_iter().collect());
+        let merged = balances.merge_aggregates(&[aggregate.clone(), aggregate], &channel);
+
+        let expected: InnerBTreeMap =
+            vec![("a".to_string(), 40.into()), ("b".to_string(), 60.into())]
+                .into_iter()
+                .collect();
+        assert_eq!(expected, merged.0);
+        assert_eq!(BigNum::from(100), merged.total());
+    }
+
+    #[test]
+    fn close_pays_the_remaining_deposit_to_the_creator_once() {
+        let mut channel = get_channel("merge close", &None, None);
+        channel.creator = "creator".to_string();
+        channel.deposit_amount = 100.into();
+
+        let mut aggregate = EventAggregate::new(channel.id, Utc::now());
+        aggregate.add(&channel, &Event::Close);
+        aggregate.add(&channel, &Event::Close);
+        aggregate
+            .events
+            .entry("IMPRESSION".to_string())
+            .or_default()
+            .event_payouts
+            .insert("a".to_string(), 30.into());
+        let mut later = EventAggregate::new(channel.id, Utc::now());
+        later.add(&channel, &Event::Close);
+
+        let balances = get_balances_map(&[("a", 10)]);
+        let merged = balances.merge_aggregates(&[aggregate, later], &channel);
+
+        assert_eq!(get_balances_map(&[("a", 40), ("creator", 60)]), merged);
+        assert_eq!(BigNum::from(100), merged.total());
+    }
+
+    #[test]
+    fn valid_transitions_only_increase_the_balances_up_to_the_deposit() {
+        let mut channel = get_channel("transitions", &None, None);
+        channel.deposit_amount = 100.into();
+
+        let previous = get_balances_map(&[("a", 10), ("b", 20)]);
+
+        assert!(previous.is_valid_transition(&previous, &channel));
+        let increased = get_balances_map(&[("a", 10), ("b", 30), ("c", 60)]);
+        assert!(previous.is_valid_transition(&increased, &channel));
+        assert!(BalancesMap::default().is_valid_transition(&previous, &channel));
+
+        assert!(
+            !previous.is_valid_transition(&get_balances_map(&[("a", 5), ("b", 40)]), &channel),
+            "A balance decreased"
+        );
+        assert!(
+            !previous.is_valid_transition(&get_balances_map(&[("b", 40)]), &channel),
+            "A balance was removed"
+        );
+        assert!(
+            !previous.is_valid_transition(&get_balances_map(&[("a", 10), ("b", 91)]), &channel),
+            "The deposit is exceeded"
+        );
+    }
+
+    #[test]
+    fn health_is_the_share_of_our_payouts_included_in_the_proposed_balances() {
+        let ours = get_balances_map(&[("a", 500), ("b", 500)]);
+
+        let same = get_balances_map(&[("a", 500), ("b", 500)]);
+        assert_eq!(BigNum::from(1_000), ours.health_promilles(&same));
+
+        let over_reported = get_balances_map(&[("a", 600), ("b", 500), ("c", 100)]);
+        assert_eq!(BigNum::from(1_000), ours.health_promilles(&over_reported));
+
+        let under_reported = get_balances_map(&[("a", 450), ("b", 500)]);
+        assert_eq!(BigNum::from(950), ours.health_promilles(&under_reported));
+
+        let missing_earner = get_balances_map(&[("a", 500)]);
+        assert_eq!(BigNum::from(500), ours.health_promilles(&missing_earner));
+
+        assert_eq!(
+            BigNum::from(1_000),
+            BalancesMap::default().health_promilles(&missing_earner),
+            "Without our own payouts, any proposal is healthy"
+        );
+    }
+
+    #[test]
+    fn is_healthy_within_the_threshold() {
+        let ours = get_balances_map(&[("a", 1_000)]);
+
+        assert!(ours.is_healthy(&get_balances_map(&[("a", 950)]), 950));
+        assert!(!ours.is_healthy(&get_balances_map(&[("a", 949)]), 950));
+        assert!(ours.is_healthy(&get_balances_map(&[("a", 949)]), 900));
+    }
+
+    #[test]
+    fn the_balance_leaf_packs_the_address_and_the_uint256_amount() {
+        let earner = "0xce07CbB7e054514D590a0262C93070D838bFBA2e";
+        let leaf = balance_leaf(earner, &BigNum::from(1_000)).expect("Hashing the leaf failed");
+        assert_eq!(
+            "ed94846367f0a84759327be1e4c7d91e186cd070c109593012946bb3f40b4175",
+            hex::encode(leaf)
+        );
+
+        let two_pow_256 = BigNum::try_from(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+        )
+        .expect("Parsing the BigNum failed");
+        match balance_leaf(earner, &two_pow_256) {
+            Err(DomainError::InvalidArgument(_)) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn proves_the_balance_of_an_earner() {
+        let balances = get_balances_map(&[("a", 100), ("b", 200), ("c", 300)]);
+        let balance_root = balances
+            .merkle_tree()
+            .expect("Building the Merkle tree failed")
+            .root();
+
+        let proof = balances
+            .proof("b")
+            .expect("Building the proof failed")
+            .expect("The earner has a balance");
+        let verify = |earner: &str, amount: u64| {
+            verify_balance_proof(earner, &amount.into(), &proof, &balance_root)
+                .expect("Verifying the proof failed")
+        };
+        assert!(verify("b", 200));
+        assert!(
+            !verify("b", 201),
+            "The amount is not the one in the balances"
+        );
+        assert!(!verify("a", 200), "The proof is for another earner");
+
+        assert_eq!(
+            None,
+            balances.proof("d").expect("Building the proof failed")
+        );
+    }
 }
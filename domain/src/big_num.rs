@@ -41,6 +41,11 @@ impl BigNum {
 
         self.0.to_u64()
     }
+
+    /// The big-endian bytes of the number, without leading zeroes
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
 }
 
 impl Integer for BigNum {
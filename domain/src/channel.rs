@@ -6,8 +6,10 @@ use chrono::{DateTime, Utc};
 use hex::FromHex;
 use serde::{Deserialize, Serialize};
 use serde_hex::{SerHex, StrictPfx};
+use tiny_keccak::keccak256;
 
 use crate::big_num::BigNum;
+use crate::util::normalize_address;
 use crate::util::serde::ts_milliseconds_option;
 use crate::{
     AdUnit, Asset, DomainError, EventSubmission, TargetingTag, ValidatorDesc, ValidatorId,
@@ -119,6 +121,130 @@ pub struct Channel {
     pub spec: ChannelSpec,
 }
 
+impl Channel {
+    /// Lowercases the addresses of the creator, the validators & the event submission `uids`
+    /// (see `normalize_address`), so they can be compared as they are to the authenticated addresses
+    pub fn normalize_addresses(&mut self) {
+        self.creator = normalize_address(&self.creator);
+        for validator in self.spec.validators.0.iter_mut() {
+            validator.id.normalize();
+        }
+        for rule in self.spec.event_submission.allow.iter_mut() {
+            if let Some(uids) = &mut rule.uids {
+                for uid in uids.iter_mut() {
+                    *uid = normalize_address(uid);
+                }
+            }
+        }
+    }
+
+    /// Computes the id of the Channel the same way the AdEx Core contract does, i.e. the keccak256
+    /// hash of the ABI-encoded `(address core, address creator, address depositAsset,
+    /// uint256 depositAmount, uint256 validUntil, address[] validators, bytes32 spec)`,
+    /// where the `spec` is the `spec_hash` the creator committed to in the contract.
+    /// It can't be computed from the `ChannelSpec`, since its JSON isn't serialized the same way
+    /// by every client.
+    ///
+    /// The core, the creator, the deposit asset and the validators should be `0x` prefixed addresses
+    /// and the `spec_hash` a `0x` prefixed bytes32.
+    pub fn compute_id(
+        &self,
+        core_address: &str,
+        spec_hash: &str,
+    ) -> Result<ChannelId, DomainError> {
+        let deposit_asset: String = self.deposit_asset.clone().into();
+        let valid_until = u64::try_from(self.valid_until.timestamp()).map_err(|_| {
+            DomainError::InvalidArgument("validUntil should not be before 1970".to_string())
+        })?;
+
+        let validators = self
+            .spec
+            .validators
+            .into_iter()
+            .map(|validator| abi_address("validator", validator.id.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut encoded = Vec::with_capacity(32 * (8 + validators.len()));
+        encoded.extend_from_slice(&abi_address("core", core_address)?);
+        encoded.extend_from_slice(&abi_address("creator", &self.creator)?);
+        encoded.extend_from_slice(&abi_address("depositAsset", &deposit_asset)?);
+        encoded.extend_from_slice(&abi_uint("depositAmount", &self.deposit_amount)?);
+        encoded.extend_from_slice(&abi_uint("validUntil", &BigNum::from(valid_until))?);
+        // the offset of the dynamic `address[]`, which is encoded after the 7 head words
+        encoded.extend_from_slice(&abi_uint("validators", &BigNum::from(7 * 32))?);
+        encoded.extend_from_slice(&abi_bytes32("specHash", spec_hash)?);
+        let validators_count = BigNum::from(validators.len() as u64);
+        encoded.extend_from_slice(&abi_uint("validators", &validators_count)?);
+        for validator in &validators {
+            encoded.extend_from_slice(validator);
+        }
+
+        Ok(ChannelId {
+            bytes: keccak256(&encoded),
+        })
+    }
+}
+
+/// The ABI word of an `address`, i.e. its 20 bytes left padded with zeroes
+fn abi_address(name: &str, address: &str) -> Result<[u8; 32], DomainError> {
+    let bytes = if address.len() == 42 && address.starts_with("0x") {
+        hex::decode(&address[2..]).ok()
+    } else {
+        None
+    };
+
+    match bytes {
+        Some(bytes) => {
+            let mut word = [0; 32];
+            word[12..].copy_from_slice(&bytes);
+
+            Ok(word)
+        }
+        None => Err(DomainError::InvalidArgument(format!(
+            "The {} should be a 0x prefixed address",
+            name
+        ))),
+    }
+}
+
+/// The ABI word of a `bytes32`, i.e. its 32 bytes as they are
+fn abi_bytes32(name: &str, value: &str) -> Result<[u8; 32], DomainError> {
+    let bytes = if value.len() == 66 && value.starts_with("0x") {
+        hex::decode(&value[2..]).ok()
+    } else {
+        None
+    };
+
+    match bytes {
+        Some(bytes) => {
+            let mut word = [0; 32];
+            word.copy_from_slice(&bytes);
+
+            Ok(word)
+        }
+        None => Err(DomainError::InvalidArgument(format!(
+            "The {} should be a 0x prefixed bytes32",
+            name
+        ))),
+    }
+}
+
+/// The ABI word of an `uint256`, i.e. its big-endian bytes left padded with zeroes
+fn abi_uint(name: &str, value: &BigNum) -> Result<[u8; 32], DomainError> {
+    let bytes = value.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(DomainError::InvalidArgument(format!(
+            "The {} doesn't fit in uint256",
+            name
+        )));
+    }
+
+    let mut word = [0; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+
+    Ok(word)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ChannelSpec {
@@ -227,6 +353,12 @@ impl<'a> IntoIterator for &'a SpecValidators {
 #[path = "./channel_fixtures.rs"]
 pub mod fixtures;
 
+#[path = "./channel_status.rs"]
+pub mod status;
+
+#[path = "./channel_validation.rs"]
+pub mod validation;
+
 #[cfg(test)]
 #[path = "./channel_test.rs"]
 mod test;
@@ -101,6 +101,8 @@ pub fn get_channel_spec(validators_option: ValidatorsOption<'_>) -> ChannelSpec
     let min_targeting_score =
         take_one(&[&None, &Some(<Faker as Number>::between(1, 500))]).to_owned();
 
+    let created = Utc::now();
+
     ChannelSpec {
         validators,
         title,
@@ -110,10 +112,11 @@ pub fn get_channel_spec(validators_option: ValidatorsOption<'_>) -> ChannelSpec
         min_targeting_score,
         // @TODO: `EventSubmission` fixture issue #27
         event_submission: EventSubmission { allow: vec![] },
-        created: Utc::now(),
-        active_from: Some(Utc::now()),
+        created,
+        active_from: Some(created),
         nonce,
-        withdraw_period_start: Utc::now(),
+        // the channel fixtures have `valid_until` of at least 7 days from now
+        withdraw_period_start: created + Duration::days(1),
         ad_units: Vec::new(),
     }
 }
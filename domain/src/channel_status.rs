@@ -0,0 +1,244 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::validator::message::{ApproveState, Heartbeat, NewState, State};
+use crate::{BalancesMap, Channel};
+
+/// A validator is considered offline, if it hasn't sent a `Heartbeat` for this long
+pub fn heartbeat_time_limit() -> Duration {
+    Duration::minutes(4)
+}
+
+/// The lifecycle state of a Channel
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelStatus {
+    /// The channel is running normally
+    Active,
+    /// `spec.active_from` is still in the future
+    Ready,
+    /// The validated balances have reached the deposit amount
+    Exhausted,
+    /// `spec.withdraw_period_start` has passed, only `CLOSE` events are accepted
+    Withdraw,
+    /// `valid_until` has passed
+    Expired,
+    /// The follower reported the latest state as unhealthy
+    Unhealthy,
+    /// None of the validators has sent a recent `Heartbeat`
+    Offline,
+    /// Only one of the validators has sent a recent `Heartbeat`
+    Disconnected,
+}
+
+/// The latest validator messages of a Channel, needed for computing its status
+#[derive(Debug, Clone, Default)]
+pub struct LatestMessages {
+    /// The timestamp of the latest `Heartbeat` of the leader
+    pub leader_heartbeat: Option<DateTime<Utc>>,
+    /// The timestamp of the latest `Heartbeat` of the follower
+    pub follower_heartbeat: Option<DateTime<Utc>>,
+    /// The balances of the latest `NewState` of the leader
+    pub new_state_balances: Option<BalancesMap>,
+    /// The `is_healthy` flag of the latest `ApproveState` of the follower
+    pub approve_state_is_healthy: Option<bool>,
+}
+
+impl LatestMessages {
+    pub fn with_heartbeats<S: State>(
+        mut self,
+        leader: Option<&Heartbeat<S>>,
+        follower: Option<&Heartbeat<S>>,
+    ) -> Self {
+        self.leader_heartbeat = leader.map(|heartbeat| heartbeat.timestamp);
+        self.follower_heartbeat = follower.map(|heartbeat| heartbeat.timestamp);
+        self
+    }
+
+    pub fn with_new_state<S: State>(mut self, new_state: Option<&NewState<S>>) -> Self {
+        self.new_state_balances = new_state.map(|new_state| new_state.balances.clone());
+        self
+    }
+
+    pub fn with_approve_state<S: State>(mut self, approve_state: Option<&ApproveState<S>>) -> Self {
+        self.approve_state_is_healthy = approve_state.map(|approve_state| approve_state.is_healthy);
+        self
+    }
+}
+
+/// Computes the status of the Channel at `now`.
+/// The statuses are checked in order of precedence, e.g. an `Expired` channel is never `Offline`.
+pub fn channel_status(
+    channel: &Channel,
+    messages: &LatestMessages,
+    now: DateTime<Utc>,
+) -> ChannelStatus {
+    if now >= channel.valid_until {
+        return ChannelStatus::Expired;
+    }
+
+    if now >= channel.spec.withdraw_period_start {
+        return ChannelStatus::Withdraw;
+    }
+
+    let is_exhausted = messages
+        .new_state_balances
+        .as_ref()
+        .map(|balances| balances.total() >= channel.deposit_amount)
+        .unwrap_or(false);
+    if is_exhausted {
+        return ChannelStatus::Exhausted;
+    }
+
+    let is_before_active_from = channel
+        .spec
+        .active_from
+        .map_or(false, |active_from| now < active_from);
+    if is_before_active_from {
+        return ChannelStatus::Ready;
+    }
+
+    let is_recent = |heartbeat: Option<DateTime<Utc>>| {
+        heartbeat.map_or(false, |timestamp| now - timestamp < heartbeat_time_limit())
+    };
+    match (
+        is_recent(messages.leader_heartbeat),
+        is_recent(messages.follower_heartbeat),
+    ) {
+        (false, false) => return ChannelStatus::Offline,
+        (true, false) | (false, true) => return ChannelStatus::Disconnected,
+        (true, true) => {}
+    }
+
+    if messages.approve_state_is_healthy == Some(false) {
+        return ChannelStatus::Unhealthy;
+    }
+
+    ChannelStatus::Active
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::fixtures::get_channel;
+    use crate::BigNum;
+
+    use super::*;
+
+    fn online_messages(now: DateTime<Utc>) -> LatestMessages {
+        LatestMessages {
+            leader_heartbeat: Some(now - Duration::minutes(1)),
+            follower_heartbeat: Some(now - Duration::minutes(2)),
+            ..LatestMessages::default()
+        }
+    }
+
+    fn active_channel(now: DateTime<Utc>) -> Channel {
+        let mut channel = get_channel("channel 1", &Some(now + Duration::days(30)), None);
+        channel.spec.active_from = Some(now - Duration::days(1));
+        channel.spec.withdraw_period_start = now + Duration::days(20);
+        channel.deposit_amount = 1_000.into();
+
+        channel
+    }
+
+    #[test]
+    fn channel_with_recent_heartbeats_is_active() {
+        let now = Utc::now();
+
+        let status = channel_status(&active_channel(now), &online_messages(now), now);
+        assert_eq!(ChannelStatus::Active, status);
+    }
+
+    #[test]
+    fn expired_and_withdraw_take_precedence() {
+        let now = Utc::now();
+        let channel = active_channel(now);
+        let no_messages = LatestMessages::default();
+
+        let expired_at = channel.valid_until;
+        assert_eq!(
+            ChannelStatus::Expired,
+            channel_status(&channel, &no_messages, expired_at)
+        );
+
+        let withdraw_at = channel.spec.withdraw_period_start;
+        assert_eq!(
+            ChannelStatus::Withdraw,
+            channel_status(&channel, &no_messages, withdraw_at)
+        );
+    }
+
+    #[test]
+    fn channel_with_balances_reaching_the_deposit_is_exhausted() {
+        let now = Utc::now();
+        let channel = active_channel(now);
+
+        let mut balances = BTreeMap::new();
+        balances.insert("publisher 1".to_string(), BigNum::from(400));
+        balances.insert("publisher 2".to_string(), BigNum::from(599));
+        let mut messages = online_messages(now);
+        messages.new_state_balances = Some(balances.clone().into());
+        assert_eq!(
+            ChannelStatus::Active,
+            channel_status(&channel, &messages, now)
+        );
+
+        balances.insert("publisher 3".to_string(), BigNum::from(1));
+        messages.new_state_balances = Some(balances.into());
+        assert_eq!(
+            ChannelStatus::Exhausted,
+            channel_status(&channel, &messages, now)
+        );
+    }
+
+    #[test]
+    fn channel_before_active_from_is_ready() {
+        let now = Utc::now();
+        let mut channel = active_channel(now);
+        channel.spec.active_from = Some(now + Duration::hours(1));
+
+        assert_eq!(
+            ChannelStatus::Ready,
+            channel_status(&channel, &LatestMessages::default(), now)
+        );
+    }
+
+    #[test]
+    fn missing_heartbeats_are_offline_or_disconnected() {
+        let now = Utc::now();
+        let channel = active_channel(now);
+
+        let mut messages = online_messages(now);
+        messages.follower_heartbeat = Some(now - heartbeat_time_limit());
+        assert_eq!(
+            ChannelStatus::Disconnected,
+            channel_status(&channel, &messages, now)
+        );
+
+        messages.leader_heartbeat = None;
+        assert_eq!(
+            ChannelStatus::Offline,
+            channel_status(&channel, &messages, now)
+        );
+    }
+
+    #[test]
+    fn unhealthy_approve_state_is_unhealthy() {
+        let now = Utc::now();
+        let channel = active_channel(now);
+
+        let mut messages = online_messages(now);
+        messages.approve_state_is_healthy = Some(false);
+        assert_eq!(
+            ChannelStatus::Unhealthy,
+            channel_status(&channel, &messages, now)
+        );
+
+        messages.approve_state_is_healthy = Some(true);
+        assert_eq!(
+            ChannelStatus::Active,
+            channel_status(&channel, &messages, now)
+        );
+    }
+}
@@ -1,6 +1,37 @@
 use std::convert::TryFrom;
 
-use crate::channel::ChannelId;
+use tiny_keccak::keccak256;
+
+use crate::channel::{Channel, ChannelId};
+use crate::event_submission::Rule;
+use crate::{DomainError, ValidatorId};
+
+const CORE_ADDRESS: &str = "0x333420fc6a897356e69b62417cd17ff012177d2b";
+const SPEC_HASH: &str = "0x5f3a0a6e3b6f7a1b5c2d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b";
+
+fn ethereum_channel() -> Channel {
+    let json = r#"{
+        "id": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "creator": "0x033ed90e0fec3f3ea1c9b005c724d704501e0196",
+        "depositAsset": "0x89d24a6b4ccb1b6faa2625fe562bdd9a23260359",
+        "depositAmount": "1000",
+        "validUntil": 4102444800,
+        "spec": {
+            "validators": [
+                {"id": "0xce07cbb7e054514d590a0262c93070d838bfba2e", "url": "http://localhost:8005", "fee": "100"},
+                {"id": "0xc91763d7f14ac5c5ddfbcd012e0d2a61ab9bded3", "url": "http://localhost:8006", "fee": "100"}
+            ],
+            "maxPerImpression": "10",
+            "minPerImpression": "1",
+            "eventSubmission": {"allow": []},
+            "created": 1564383600000,
+            "nonce": "987654321",
+            "withdrawPeriodStart": 4100000000000
+        }
+    }"#;
+
+    serde_json::from_str(json).expect("Invalid channel JSON")
+}
 
 #[test]
 fn coverts_str_to_channel_id() {
@@ -55,3 +86,119 @@ fn serialize_and_deserialize_channel_id() {
     let from_hex: ChannelId = serde_json::from_str(expected_json).unwrap();
     assert_eq!(from_hex, channel_id);
 }
+
+#[test]
+fn computes_the_channel_id_of_the_abi_encoded_channel() {
+    let channel = ethereum_channel();
+
+    // the ABI encoding of the Core `ChannelLibrary.hash`, which can be reproduced by any client
+    let encoded = [
+        // core
+        "000000000000000000000000333420fc6a897356e69b62417cd17ff012177d2b",
+        // creator
+        "000000000000000000000000033ed90e0fec3f3ea1c9b005c724d704501e0196",
+        // depositAsset
+        "00000000000000000000000089d24a6b4ccb1b6faa2625fe562bdd9a23260359",
+        // depositAmount: 1000
+        "00000000000000000000000000000000000000000000000000000000000003e8",
+        // validUntil: 4102444800
+        "00000000000000000000000000000000000000000000000000000000f4865700",
+        // the offset of the validators
+        "00000000000000000000000000000000000000000000000000000000000000e0",
+        // spec, the SPEC_HASH supplied by the creator
+        "5f3a0a6e3b6f7a1b5c2d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b",
+        // the validators count
+        "0000000000000000000000000000000000000000000000000000000000000002",
+        "000000000000000000000000ce07cbb7e054514d590a0262c93070d838bfba2e",
+        "000000000000000000000000c91763d7f14ac5c5ddfbcd012e0d2a61ab9bded3",
+    ]
+    .concat();
+    let expected = format!(
+        "0x{}",
+        hex::encode(keccak256(&hex::decode(encoded).unwrap()))
+    );
+    assert_eq!(
+        "0xd23d1ce89611f753dc8c3f0f7abd84326ae84155313f33e2dbb46994846a4db5",
+        expected
+    );
+
+    let channel_id = channel
+        .compute_id(CORE_ADDRESS, SPEC_HASH)
+        .expect("Computing the id failed");
+    assert_eq!(expected, channel_id.to_string());
+
+    let other_core = "0x0000000000000000000000000000000000000001";
+    assert_ne!(Ok(channel_id), channel.compute_id(other_core, SPEC_HASH));
+
+    let other_spec_hash = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    assert_ne!(
+        Ok(channel_id),
+        channel.compute_id(CORE_ADDRESS, other_spec_hash)
+    );
+}
+
+#[test]
+fn the_channel_id_requires_addresses() {
+    let mut channel = ethereum_channel();
+    channel.creator = "creator".to_string();
+
+    match channel.compute_id(CORE_ADDRESS, SPEC_HASH) {
+        Err(DomainError::InvalidArgument(message)) => assert!(message.contains("creator")),
+        result => panic!("Expected an InvalidArgument error, got {:?}", result),
+    }
+
+    assert!(ethereum_channel().compute_id("core", SPEC_HASH).is_err());
+    assert!(ethereum_channel()
+        .compute_id(CORE_ADDRESS, "0x1234")
+        .is_err());
+}
+
+#[test]
+fn normalizes_the_addresses_of_the_channel() {
+    let checksummed_creator = "0x033ED90e0FeC3F3ea1C9b005C724D704501e0196";
+    let checksummed_leader = "0xCe07CbB7e054514D590a0262C93070D838bFBA2e";
+    let json = serde_json::to_string(&ethereum_channel())
+        .unwrap()
+        .replace(
+            "0x033ed90e0fec3f3ea1c9b005c724d704501e0196",
+            checksummed_creator,
+        )
+        .replace(
+            "0xce07cbb7e054514d590a0262c93070d838bfba2e",
+            checksummed_leader,
+        );
+    let mut channel: Channel = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        checksummed_leader,
+        channel.spec.validators.leader().id.as_ref()
+    );
+
+    channel.normalize_addresses();
+
+    let expected = ethereum_channel();
+    assert_eq!(expected.creator, channel.creator);
+    assert_eq!(
+        expected.spec.validators.leader().id,
+        channel.spec.validators.leader().id
+    );
+    assert_eq!(
+        ValidatorId::try_from(checksummed_leader).unwrap(),
+        channel.spec.validators.leader().id
+    );
+
+    let mut dummy = ethereum_channel();
+    dummy.creator = "awesomeCreator".to_string();
+    dummy.spec.event_submission.allow = vec![Rule {
+        uids: Some(vec![
+            checksummed_creator.to_string(),
+            "awesomeUser".to_string(),
+        ]),
+        rate_limit: None,
+    }];
+    dummy.normalize_addresses();
+    assert_eq!("awesomeCreator", dummy.creator);
+    assert_eq!(
+        Some(vec![expected.creator.clone(), "awesomeUser".to_string()]),
+        dummy.spec.event_submission.allow[0].uids
+    );
+}
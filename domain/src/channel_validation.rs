@@ -0,0 +1,328 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::event_submission::MAX_TIME_FRAME;
+use crate::{AdUnit, BigNum, Channel};
+
+/// The allowed `AdUnit.media_mime` values
+pub const AD_UNIT_MEDIA_MIMES: [&str; 2] = ["image/jpeg", "image/png"];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelRule {
+    /// `spec.created < spec.withdraw_period_start`
+    CreatedBeforeWithdrawPeriodStart,
+    /// `spec.withdraw_period_start < valid_until`
+    WithdrawPeriodStartBeforeValidUntil,
+    /// `spec.min_per_impression <= spec.max_per_impression`
+    MinPerImpressionNotAboveMax,
+    /// `deposit_amount >=` the sum of the validators fees
+    DepositCoversValidatorFees,
+    /// `AdUnit.media_url` should use the `ipfs://` protocol
+    AdUnitMediaUrl,
+    /// `AdUnit.media_mime` should be one of `AD_UNIT_MEDIA_MIMES`
+    AdUnitMediaMime,
+    /// `AdUnit.target_url` should be a `http(s)://` URL
+    AdUnitTargetUrl,
+    /// `RateLimit.time_frame` of the `spec.event_submission` rules should be `<= MAX_TIME_FRAME`
+    RateLimitTimeFrame,
+}
+
+impl ChannelRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChannelRule::CreatedBeforeWithdrawPeriodStart => "created_before_withdraw_period_start",
+            ChannelRule::WithdrawPeriodStartBeforeValidUntil => {
+                "withdraw_period_start_before_valid_until"
+            }
+            ChannelRule::MinPerImpressionNotAboveMax => "min_per_impression_not_above_max",
+            ChannelRule::DepositCoversValidatorFees => "deposit_covers_validator_fees",
+            ChannelRule::AdUnitMediaUrl => "ad_unit_media_url",
+            ChannelRule::AdUnitMediaMime => "ad_unit_media_mime",
+            ChannelRule::AdUnitTargetUrl => "ad_unit_target_url",
+            ChannelRule::RateLimitTimeFrame => "rate_limit_time_frame",
+        }
+    }
+}
+
+impl fmt::Display for ChannelRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RuleViolation {
+    pub rule: ChannelRule,
+    pub message: String,
+}
+
+impl RuleViolation {
+    fn new(rule: ChannelRule, message: String) -> Self {
+        Self { rule, message }
+    }
+}
+
+/// Checks all the domain rules of the Channel and returns every violated one
+pub fn validate_channel(channel: &Channel) -> Result<(), Vec<RuleViolation>> {
+    let spec = &channel.spec;
+    let mut violations = Vec::new();
+
+    if spec.created >= spec.withdraw_period_start {
+        violations.push(RuleViolation::new(
+            ChannelRule::CreatedBeforeWithdrawPeriodStart,
+            "spec.created should be before spec.withdrawPeriodStart".to_string(),
+        ));
+    }
+
+    if spec.withdraw_period_start >= channel.valid_until {
+        violations.push(RuleViolation::new(
+            ChannelRule::WithdrawPeriodStartBeforeValidUntil,
+            "spec.withdrawPeriodStart should be before validUntil".to_string(),
+        ));
+    }
+
+    if spec.min_per_impression > spec.max_per_impression {
+        violations.push(RuleViolation::new(
+            ChannelRule::MinPerImpressionNotAboveMax,
+            "spec.minPerImpression should be <= spec.maxPerImpression".to_string(),
+        ));
+    }
+
+    let validators_fees: BigNum = spec
+        .validators
+        .into_iter()
+        .map(|validator| &validator.fee)
+        .sum();
+    if channel.deposit_amount < validators_fees {
+        violations.push(RuleViolation::new(
+            ChannelRule::DepositCoversValidatorFees,
+            format!(
+                "depositAmount should be >= the sum of the validators fees ({})",
+                validators_fees.to_string()
+            ),
+        ));
+    }
+
+    for (index, ad_unit) in spec.ad_units.iter().enumerate() {
+        violations.extend(validate_ad_unit(index, ad_unit));
+    }
+
+    for (index, rule) in spec.event_submission.allow.iter().enumerate() {
+        let out_of_range = match &rule.rate_limit {
+            Some(rate_limit) => rate_limit.duration().is_none(),
+            None => false,
+        };
+
+        if out_of_range {
+            violations.push(RuleViolation::new(
+                ChannelRule::RateLimitTimeFrame,
+                format!(
+                    "spec.eventSubmission.allow[{}].rateLimit.timeframe should be <= {}",
+                    index, MAX_TIME_FRAME
+                ),
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+fn validate_ad_unit(index: usize, ad_unit: &AdUnit) -> Vec<RuleViolation> {
+    let mut violations = Vec::new();
+
+    if !ad_unit.media_url.starts_with("ipfs://") {
+        violations.push(RuleViolation::new(
+            ChannelRule::AdUnitMediaUrl,
+            format!("spec.adUnits[{}].mediaUrl should use ipfs://", index),
+        ));
+    }
+
+    if !AD_UNIT_MEDIA_MIMES.contains(&ad_unit.media_mime.as_str()) {
+        violations.push(RuleViolation::new(
+            ChannelRule::AdUnitMediaMime,
+            format!(
+                "spec.adUnits[{}].mediaMime should be one of: {}",
+                index,
+                AD_UNIT_MEDIA_MIMES.join(", ")
+            ),
+        ));
+    }
+
+    let target_url = ad_unit.target_url.as_str();
+    if !target_url.starts_with("https://") && !target_url.starts_with("http://") {
+        violations.push(RuleViolation::new(
+            ChannelRule::AdUnitTargetUrl,
+            format!("spec.adUnits[{}].targetUrl should be a http(s) URL", index),
+        ));
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use time::Duration;
+
+    use crate::event_submission::{RateLimit, RateLimitType, Rule};
+    use crate::fixtures::{get_channel, get_channel_spec, get_validator, ValidatorsOption};
+
+    use super::*;
+
+    fn get_ad_unit(media_url: &str, media_mime: &str, target_url: &str) -> AdUnit {
+        AdUnit {
+            ipfs: "QmcUVX7fvoLMM93uN2bD3wGTH8MXSxeL8hojYfL2Lhp7mR".to_string(),
+            ad_type: "legacy_300x250".to_string(),
+            media_url: media_url.to_string(),
+            media_mime: media_mime.to_string(),
+            target_url: target_url.to_string(),
+            targeting: Vec::new(),
+            min_targeting_score: None,
+            tags: Vec::new(),
+            owner: "owner".to_string(),
+            created: Utc::now(),
+            title: None,
+            description: None,
+            archived: false,
+            modified: None,
+        }
+    }
+
+    fn violated_rules(channel: &Channel) -> Vec<ChannelRule> {
+        validate_channel(channel)
+            .expect_err("The channel should violate a rule")
+            .into_iter()
+            .map(|violation| violation.rule)
+            .collect()
+    }
+
+    #[test]
+    fn channel_fixture_is_valid() {
+        let channel = get_channel("channel 1", &None, None);
+
+        assert_eq!(Ok(()), validate_channel(&channel));
+    }
+
+    #[test]
+    fn withdraw_period_start_should_be_between_created_and_valid_until() {
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.spec.withdraw_period_start = channel.spec.created - Duration::seconds(1);
+        assert_eq!(
+            vec![ChannelRule::CreatedBeforeWithdrawPeriodStart],
+            violated_rules(&channel)
+        );
+
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.spec.withdraw_period_start = channel.valid_until + Duration::seconds(1);
+        assert_eq!(
+            vec![ChannelRule::WithdrawPeriodStartBeforeValidUntil],
+            violated_rules(&channel)
+        );
+    }
+
+    #[test]
+    fn min_per_impression_should_not_be_above_max() {
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.spec.min_per_impression = &channel.spec.max_per_impression + &BigNum::from(1);
+
+        assert_eq!(
+            vec![ChannelRule::MinPerImpressionNotAboveMax],
+            violated_rules(&channel)
+        );
+    }
+
+    #[test]
+    fn deposit_should_cover_the_validators_fees() {
+        let validators = ValidatorsOption::Pair {
+            leader: get_validator("leader", Some(60.into())),
+            follower: get_validator("follower", Some(50.into())),
+        };
+        let mut channel = get_channel("channel 1", &None, Some(get_channel_spec(validators)));
+
+        channel.deposit_amount = 110.into();
+        assert_eq!(Ok(()), validate_channel(&channel));
+
+        channel.deposit_amount = 109.into();
+        assert_eq!(
+            vec![ChannelRule::DepositCoversValidatorFees],
+            violated_rules(&channel)
+        );
+    }
+
+    #[test]
+    fn ad_units_should_be_sane() {
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.spec.ad_units = vec![
+            get_ad_unit("ipfs://QmcUVX7fvoLMM93uN2bD3wGTH8", "image/png", "https://adex.network"),
+            get_ad_unit("https://adex.network/image.png", "image/gif", "adex.network"),
+        ];
+
+        let violations = validate_channel(&channel).expect_err("The second ad unit is invalid");
+
+        assert_eq!(
+            vec![
+                ChannelRule::AdUnitMediaUrl,
+                ChannelRule::AdUnitMediaMime,
+                ChannelRule::AdUnitTargetUrl,
+            ],
+            violations
+                .iter()
+                .map(|violation| violation.rule)
+                .collect::<Vec<_>>()
+        );
+        assert!(violations
+            .iter()
+            .all(|violation| violation.message.starts_with("spec.adUnits[1]")));
+    }
+
+    #[test]
+    fn rate_limit_time_frames_should_be_in_range() {
+        let rule = |time_frame| Rule {
+            uids: None,
+            rate_limit: Some(RateLimit {
+                limit_type: RateLimitType::Ip,
+                time_frame,
+            }),
+        };
+
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.spec.event_submission.allow = vec![rule(MAX_TIME_FRAME)];
+        assert_eq!(Ok(()), validate_channel(&channel));
+
+        channel.spec.event_submission.allow = vec![rule(1_000), rule(u64::max_value())];
+        let violations = validate_channel(&channel).expect_err("The timeframe is out of range");
+        assert_eq!(
+            vec![RuleViolation::new(
+                ChannelRule::RateLimitTimeFrame,
+                format!(
+                    "spec.eventSubmission.allow[1].rateLimit.timeframe should be <= {}",
+                    MAX_TIME_FRAME
+                ),
+            )],
+            violations
+        );
+    }
+
+    #[test]
+    fn rule_violation_is_serialized_with_the_rule_name() {
+        let violation = RuleViolation::new(
+            ChannelRule::DepositCoversValidatorFees,
+            "message".to_string(),
+        );
+
+        assert_eq!(
+            r#"{"rule":"deposit_covers_validator_fees","message":"message"}"#,
+            serde_json::to_string(&violation).unwrap()
+        );
+        assert_eq!(
+            "deposit_covers_validator_fees",
+            ChannelRule::DepositCoversValidatorFees.as_str()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Channel, DomainError};
+
+/// An event submitted to a Channel, e.g. `{"type":"IMPRESSION","publisher":"0x..."}`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Event {
+    Impression {
+        publisher: String,
+        #[serde(rename = "adUnit", default, skip_serializing_if = "Option::is_none")]
+        ad_unit: Option<String>,
+    },
+    Click {
+        publisher: String,
+        #[serde(rename = "adUnit", default, skip_serializing_if = "Option::is_none")]
+        ad_unit: Option<String>,
+    },
+    /// Closes the Channel, the only event accepted during the withdraw period
+    Close,
+}
+
+impl Event {
+    /// The serialized `type` of the Event
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Event::Impression { .. } => "IMPRESSION",
+            Event::Click { .. } => "CLICK",
+            Event::Close => "CLOSE",
+        }
+    }
+
+    /// Checks if the Event can be submitted to the Channel at `now`.
+    /// The Channel should not be expired and its `active_from` should have passed,
+    /// during the withdraw period only `CLOSE` events are accepted.
+    pub fn validate(&self, channel: &Channel, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if now >= channel.valid_until {
+            return Err(DomainError::RuleViolation(
+                "The channel is expired".to_string(),
+            ));
+        }
+
+        let is_before_active_from = channel
+            .spec
+            .active_from
+            .map_or(false, |active_from| now < active_from);
+        if is_before_active_from {
+            return Err(DomainError::RuleViolation(
+                "The channel is not active yet".to_string(),
+            ));
+        }
+
+        let is_withdraw_period = now >= channel.spec.withdraw_period_start;
+        match self {
+            Event::Close => Ok(()),
+            _ if is_withdraw_period => Err(DomainError::RuleViolation(
+                "The channel is in its withdraw period, only CLOSE events are accepted".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use crate::fixtures::get_channel;
+
+    use super::*;
+
+    fn impression() -> Event {
+        Event::Impression {
+            publisher: "publisher".to_string(),
+            ad_unit: None,
+        }
+    }
+
+    fn active_channel(now: DateTime<Utc>) -> Channel {
+        let mut channel = get_channel("channel 1", &Some(now + Duration::days(30)), None);
+        channel.spec.active_from = Some(now - Duration::days(1));
+        channel.spec.withdraw_period_start = now + Duration::days(20);
+
+        channel
+    }
+
+    #[test]
+    fn serializes_with_the_event_type() {
+        let click = Event::Click {
+            publisher: "publisher".to_string(),
+            ad_unit: Some("ad unit".to_string()),
+        };
+        let json = r#"{"type":"CLICK","publisher":"publisher","adUnit":"ad unit"}"#;
+
+        assert_eq!(
+            json,
+            serde_json::to_string(&click).expect("Should serialize")
+        );
+        assert_eq!(
+            click,
+            serde_json::from_str(json).expect("Should deserialize")
+        );
+        assert_eq!(
+            Event::Close,
+            serde_json::from_str(r#"{"type":"CLOSE"}"#).expect("Should deserialize")
+        );
+        assert_eq!("CLICK", click.event_type());
+        assert_eq!("CLOSE", Event::Close.event_type());
+    }
+
+    #[test]
+    fn accepts_events_for_active_channel() {
+        let now = Utc::now();
+        let channel = active_channel(now);
+
+        assert_eq!(Ok(()), impression().validate(&channel, now));
+        assert_eq!(Ok(()), Event::Close.validate(&channel, now));
+    }
+
+    #[test]
+    fn rejects_events_for_expired_or_not_yet_active_channel() {
+        let now = Utc::now();
+        let mut channel = active_channel(now);
+
+        let expired_at = channel.valid_until;
+        assert!(impression().validate(&channel, expired_at).is_err());
+        assert!(Event::Close.validate(&channel, expired_at).is_err());
+
+        channel.spec.active_from = Some(now + Duration::hours(1));
+        assert!(impression().validate(&channel, now).is_err());
+    }
+
+    #[test]
+    fn accepts_only_close_events_in_the_withdraw_period() {
+        let now = Utc::now();
+        let channel = active_channel(now);
+        let withdraw_at = channel.spec.withdraw_period_start;
+
+        match impression().validate(&channel, withdraw_at) {
+            Err(DomainError::RuleViolation(_)) => {}
+            result => panic!("Expected a RuleViolation error, got {:?}", result),
+        }
+        assert_eq!(Ok(()), Event::Close.validate(&channel, withdraw_at));
+    }
+}
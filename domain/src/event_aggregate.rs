@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{BigNum, Channel, ChannelId, Event};
+
+/// The events of a Channel folded per event type, e.g.
+/// `{"channelId":"0x...","created":"...","events":{"IMPRESSION":{"eventCounts":{"0x...":"2"},"eventPayouts":{"0x...":"20"}}}}`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventAggregate {
+    pub channel_id: ChannelId,
+    pub created: DateTime<Utc>,
+    pub events: BTreeMap<String, AggregateEvents>,
+}
+
+/// The number of events and the sum of their payouts per earner
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateEvents {
+    pub event_counts: BTreeMap<String, BigNum>,
+    pub event_payouts: BTreeMap<String, BigNum>,
+}
+
+impl EventAggregate {
+    pub fn new(channel_id: ChannelId, created: DateTime<Utc>) -> Self {
+        Self {
+            channel_id,
+            created,
+            events: BTreeMap::default(),
+        }
+    }
+
+    /// Folds the Event into the aggregate of its type.
+    /// An `IMPRESSION` pays `min_per_impression` to its publisher, while a `CLICK` and a `CLOSE`
+    /// (of the creator) are only counted. The unspent deposit is paid back to the creator
+    /// when the aggregate is merged into the balances, see `BalancesMap::merge_aggregates`.
+    pub fn add(&mut self, channel: &Channel, event: &Event) {
+        let (earner, payout) = match event {
+            Event::Impression { publisher, .. } => {
+                (publisher, channel.spec.min_per_impression.clone())
+            }
+            Event::Click { publisher, .. } => (publisher, BigNum::from(0)),
+            Event::Close => (&channel.creator, BigNum::from(0)),
+        };
+
+        let aggregate_events = self
+            .events
+            .entry(event.event_type().to_string())
+            .or_default();
+
+        *aggregate_events
+            .event_counts
+            .entry(earner.clone())
+            .or_insert_with(|| BigNum::from(0)) += &BigNum::from(1);
+        *aggregate_events
+            .event_payouts
+            .entry(earner.clone())
+            .or_insert_with(|| BigNum::from(0)) += &payout;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixtures::get_channel;
+
+    use super::*;
+
+    fn impression(publisher: &str) -> Event {
+        Event::Impression {
+            publisher: publisher.to_string(),
+            ad_unit: None,
+        }
+    }
+
+    fn earners(values: &[(&str, u64)]) -> BTreeMap<String, BigNum> {
+        values
+            .iter()
+            .map(|(earner, value)| (earner.to_string(), BigNum::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn folds_the_events_per_type_and_earner() {
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.creator = "creator".to_string();
+        channel.deposit_amount = BigNum::from(1_000);
+        channel.spec.min_per_impression = BigNum::from(10);
+
+        let mut aggregate = EventAggregate::new(channel.id, Utc::now());
+        assert!(aggregate.is_empty());
+
+        let click = Event::Click {
+            publisher: "second".to_string(),
+            ad_unit: None,
+        };
+        for event in &[
+            impression("first"),
+            impression("second"),
+            impression("first"),
+            click,
+        ] {
+            aggregate.add(&channel, event);
+        }
+        aggregate.add(&channel, &Event::Close);
+
+        let impressions = &aggregate.events["IMPRESSION"];
+        assert_eq!(
+            earners(&[("first", 2), ("second", 1)]),
+            impressions.event_counts
+        );
+        assert_eq!(
+            earners(&[("first", 20), ("second", 10)]),
+            impressions.event_payouts
+        );
+
+        let clicks = &aggregate.events["CLICK"];
+        assert_eq!(earners(&[("second", 1)]), clicks.event_counts);
+        assert_eq!(earners(&[("second", 0)]), clicks.event_payouts);
+
+        let close = &aggregate.events["CLOSE"];
+        assert_eq!(earners(&[("creator", 1)]), close.event_counts);
+        assert_eq!(earners(&[("creator", 0)]), close.event_payouts);
+    }
+
+    #[test]
+    fn serializes_the_events_with_camel_case() {
+        let channel = get_channel("channel 1", &None, None);
+        let mut aggregate = EventAggregate::new(channel.id, Utc::now());
+        aggregate.add(&channel, &impression("publisher"));
+
+        let json = serde_json::to_string(&aggregate).expect("Should serialize");
+        let value: serde_json::Value = serde_json::from_str(&json).expect("Should be valid JSON");
+
+        assert_eq!(
+            "1",
+            value["events"]["IMPRESSION"]["eventCounts"]["publisher"]
+        );
+        assert!(value["channelId"].is_string());
+        assert_eq!(
+            aggregate,
+            serde_json::from_str(&json).expect("Should deserialize")
+        );
+    }
+}
@@ -1,12 +1,54 @@
+use std::convert::TryFrom;
+
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
 
+/// The time frame (in milliseconds) of the default IP rate limit,
+/// used when the Channel has no `allow` rules
+pub const DEFAULT_IP_TIME_FRAME: u64 = 20_000;
+
+/// The longest time frame (in milliseconds) of a rate limit, one year
+pub const MAX_TIME_FRAME: u64 = 365 * 24 * 60 * 60 * 1_000;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EventSubmission {
     #[serde(default)]
     pub allow: Vec<Rule>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl EventSubmission {
+    /// The `allow` rules or, if there are none, the default ones:
+    /// the `creator` is allowed without limits and anyone else is rate limited by IP.
+    pub fn rules(&self, creator: &str) -> Vec<Rule> {
+        if !self.allow.is_empty() {
+            return self.allow.clone();
+        }
+
+        vec![
+            Rule {
+                uids: Some(vec![creator.to_string()]),
+                rate_limit: None,
+            },
+            Rule {
+                uids: None,
+                rate_limit: Some(RateLimit {
+                    limit_type: RateLimitType::Ip,
+                    time_frame: DEFAULT_IP_TIME_FRAME,
+                }),
+            },
+        ]
+    }
+
+    /// Picks the first Rule which allows the submitter, `uid` is its authenticated identity if any.
+    /// Returns `None` if the submitter is not allowed to submit events.
+    pub fn allowed_rule(&self, creator: &str, uid: Option<&str>) -> Option<Rule> {
+        self.rules(creator)
+            .into_iter()
+            .find(|rule| rule.allows(uid))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Rule {
     #[serde(default)]
@@ -15,13 +57,148 @@ pub struct Rule {
     pub rate_limit: Option<RateLimit>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Rule {
+    /// A Rule without `uids` allows any submitter, even the ones which are not authenticated
+    pub fn allows(&self, uid: Option<&str>) -> bool {
+        match (&self.uids, uid) {
+            (None, _) => true,
+            (Some(uids), Some(uid)) => uids.iter().any(|allowed| allowed == uid),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct RateLimit {
-    /// "ip", "uid"
     #[serde(rename = "type")]
-    pub limit_type: String,
+    pub limit_type: RateLimitType,
     /// in milliseconds
     #[serde(rename = "timeframe")]
     pub time_frame: u64,
 }
+
+impl RateLimit {
+    /// The `time_frame` as a `Duration`, `None` if it is longer than the `MAX_TIME_FRAME`
+    pub fn duration(&self) -> Option<Duration> {
+        if self.time_frame > MAX_TIME_FRAME {
+            return None;
+        }
+
+        i64::try_from(self.time_frame)
+            .ok()
+            .map(Duration::milliseconds)
+    }
+}
+
+/// What the submissions are rate limited by
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitType {
+    /// The IP address of the submitter
+    Ip,
+    /// The authenticated identity of the submitter
+    Uid,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rule(uids: Option<&[&str]>, limit_type: Option<RateLimitType>) -> Rule {
+        Rule {
+            uids: uids.map(|uids| uids.iter().map(|uid| uid.to_string()).collect()),
+            rate_limit: limit_type.map(|limit_type| RateLimit {
+                limit_type,
+                time_frame: 1_000,
+            }),
+        }
+    }
+
+    #[test]
+    fn deserializes_the_rate_limit_type() {
+        let json =
+            r#"{"allow":[{"uids":["0xce07"]},{"rateLimit":{"type":"uid","timeframe":1000}}]}"#;
+        let event_submission: EventSubmission =
+            serde_json::from_str(json).expect("Should deserialize");
+
+        assert_eq!(
+            vec![
+                rule(Some(&["0xce07"]), None),
+                rule(None, Some(RateLimitType::Uid))
+            ],
+            event_submission.allow
+        );
+
+        let invalid = r#"{"allow":[{"rateLimit":{"type":"session","timeframe":1000}}]}"#;
+        assert!(serde_json::from_str::<EventSubmission>(invalid).is_err());
+    }
+
+    #[test]
+    fn picks_the_first_rule_which_allows_the_submitter() {
+        let event_submission = EventSubmission {
+            allow: vec![
+                rule(Some(&["publisher"]), None),
+                rule(None, Some(RateLimitType::Uid)),
+            ],
+        };
+
+        assert_eq!(
+            Some(rule(Some(&["publisher"]), None)),
+            event_submission.allowed_rule("creator", Some("publisher"))
+        );
+        assert_eq!(
+            Some(rule(None, Some(RateLimitType::Uid))),
+            event_submission.allowed_rule("creator", Some("creator"))
+        );
+        assert_eq!(
+            Some(rule(None, Some(RateLimitType::Uid))),
+            event_submission.allowed_rule("creator", None)
+        );
+
+        let only_publishers = EventSubmission {
+            allow: vec![rule(Some(&["publisher"]), None)],
+        };
+        assert_eq!(None, only_publishers.allowed_rule("creator", None));
+        assert_eq!(
+            None,
+            only_publishers.allowed_rule("creator", Some("creator"))
+        );
+    }
+
+    #[test]
+    fn without_rules_the_creator_is_not_rate_limited() {
+        let event_submission = EventSubmission { allow: vec![] };
+
+        let creator_rule = event_submission
+            .allowed_rule("creator", Some("creator"))
+            .expect("The creator should be allowed");
+        assert_eq!(None, creator_rule.rate_limit);
+
+        let rate_limit = event_submission
+            .allowed_rule("creator", None)
+            .and_then(|rule| rule.rate_limit)
+            .expect("Anyone else should be rate limited");
+        assert_eq!(RateLimitType::Ip, rate_limit.limit_type);
+        assert_eq!(DEFAULT_IP_TIME_FRAME, rate_limit.time_frame);
+    }
+
+    #[test]
+    fn rate_limit_duration_is_bounded_by_the_max_time_frame() {
+        let rate_limit = |time_frame| RateLimit {
+            limit_type: RateLimitType::Ip,
+            time_frame,
+        };
+
+        assert_eq!(
+            Some(Duration::milliseconds(20_000)),
+            rate_limit(20_000).duration()
+        );
+        assert_eq!(
+            Some(Duration::days(365)),
+            rate_limit(MAX_TIME_FRAME).duration()
+        );
+        assert_eq!(None, rate_limit(MAX_TIME_FRAME + 1).duration());
+        assert_eq!(None, rate_limit(u64::max_value()).duration());
+    }
+}
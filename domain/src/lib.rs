@@ -11,7 +11,10 @@ pub use self::asset::Asset;
 pub use self::balances_map::BalancesMap;
 pub use self::big_num::BigNum;
 pub use self::channel::{Channel, ChannelId, ChannelSpec, SpecValidator, SpecValidators};
+pub use self::event::Event;
+pub use self::event_aggregate::{AggregateEvents, EventAggregate};
 pub use self::event_submission::EventSubmission;
+pub use self::merkle_tree::MerkleTree;
 #[cfg(feature = "repositories")]
 pub use self::repository::*;
 pub use self::targeting_tag::TargetingTag;
@@ -22,7 +25,10 @@ pub mod asset;
 pub mod balances_map;
 pub mod big_num;
 pub mod channel;
+pub mod event;
+pub mod event_aggregate;
 pub mod event_submission;
+pub mod merkle_tree;
 pub mod targeting_tag;
 pub mod util;
 pub mod validator;
@@ -31,6 +37,7 @@ pub mod validator;
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures {
     pub use super::asset::fixtures::*;
+    pub use super::balances_map::fixtures::*;
     pub use super::channel::fixtures::*;
     pub use super::targeting_tag::fixtures::*;
     pub use super::validator::fixtures::*;
@@ -44,7 +51,10 @@ pub enum DomainError {
 
 impl fmt::Display for DomainError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Domain error",)
+        match self {
+            DomainError::InvalidArgument(message) => write!(f, "Invalid argument: {}", message),
+            DomainError::RuleViolation(message) => write!(f, "Rule violation: {}", message),
+        }
     }
 }
 
@@ -68,8 +78,9 @@ pub mod repository {
     pub enum RepositoryError {
         /// An error with the underlying implementation occurred
         IO(Box<dyn IOError>),
-        /// Error handling save errors, like Primary key already exists and etc.
-        /// @TODO: Add and underlying implementation for this error
+        /// When saving a record, which Primary key already exists
+        AlreadyExists,
+        /// Error handling other save errors
         User,
     }
 
@@ -78,6 +89,7 @@ pub mod repository {
     impl fmt::Display for RepositoryError {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
+                RepositoryError::AlreadyExists => write!(f, "Record already exists"),
                 RepositoryError::User => write!(f, "User error: TODO"),
                 RepositoryError::IO(error) => write!(f, "IO error: {}", error),
             }
@@ -0,0 +1,151 @@
+use tiny_keccak::keccak256;
+
+pub type MerkleItem = [u8; 32];
+
+/// A Merkle tree of sorted leaves, where each pair is hashed sorted as well,
+/// as the `MerkleProof` verification of the AdEx protocol contracts expects it.
+/// A node without a pair is moved up to the next layer as it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleTree {
+    /// The sorted leaves first and the root last
+    layers: Vec<Vec<MerkleItem>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree of the leaves, which are sorted and deduplicated
+    pub fn new(leaves: &[MerkleItem]) -> Self {
+        let mut leaves = leaves.to_vec();
+        leaves.sort();
+        leaves.dedup();
+
+        let mut layers = vec![leaves];
+        while layers[layers.len() - 1].len() > 1 {
+            let next_layer = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => combined_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!("The chunks are of 1 or 2 items"),
+                })
+                .collect();
+
+            layers.push(next_layer);
+        }
+
+        Self { layers }
+    }
+
+    /// The root of the tree or zeroes for a tree without leaves
+    pub fn root(&self) -> MerkleItem {
+        self.layers[self.layers.len() - 1]
+            .first()
+            .cloned()
+            .unwrap_or([0; 32])
+    }
+
+    /// The sibling hashes from the leaf up to the root, if the leaf is in the tree
+    pub fn proof(&self, leaf: &MerkleItem) -> Option<Vec<MerkleItem>> {
+        let mut index = self.layers[0].binary_search(leaf).ok()?;
+
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let pair_index = if index % 2 == 0 { index + 1 } else { index - 1 };
+            if let Some(pair) = layer.get(pair_index) {
+                proof.push(*pair);
+            }
+
+            index /= 2;
+        }
+
+        Some(proof)
+    }
+
+    /// Whether the proof leads from the leaf up to the root
+    pub fn verify(proof: &[MerkleItem], root: &MerkleItem, leaf: &MerkleItem) -> bool {
+        let computed_root = proof
+            .iter()
+            .fold(*leaf, |hash, pair| combined_hash(&hash, pair));
+
+        &computed_root == root
+    }
+}
+
+/// The keccak256 hash of the pair, concatenated in ascending order
+fn combined_hash(left: &MerkleItem, right: &MerkleItem) -> MerkleItem {
+    let (first, second) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+
+    let mut concatenated = [0; 64];
+    concatenated[..32].copy_from_slice(first);
+    concatenated[32..].copy_from_slice(second);
+
+    keccak256(&concatenated)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn leaf(value: &str) -> MerkleItem {
+        keccak256(value.as_bytes())
+    }
+
+    #[test]
+    fn the_root_of_a_single_leaf_is_the_leaf() {
+        let tree = MerkleTree::new(&[leaf("a")]);
+
+        assert_eq!(leaf("a"), tree.root());
+        assert_eq!(Some(vec![]), tree.proof(&leaf("a")));
+        assert_eq!([0; 32], MerkleTree::new(&[]).root());
+    }
+
+    #[test]
+    fn the_root_does_not_depend_on_the_order_of_the_leaves() {
+        let (a, b, c) = (leaf("a"), leaf("b"), leaf("c"));
+
+        let tree = MerkleTree::new(&[a, b, c]);
+        assert_eq!(tree, MerkleTree::new(&[c, a, b, a]));
+
+        let mut sorted = [a, b, c];
+        sorted.sort();
+        let expected_root = combined_hash(&combined_hash(&sorted[0], &sorted[1]), &sorted[2]);
+        assert_eq!(expected_root, tree.root());
+    }
+
+    #[test]
+    fn the_proof_contains_the_pair_of_each_layer() {
+        let mut leaves = [leaf("a"), leaf("b"), leaf("c")];
+        leaves.sort();
+        let tree = MerkleTree::new(&leaves);
+
+        let first_pair = combined_hash(&leaves[0], &leaves[1]);
+        assert_eq!(Some(vec![leaves[1], leaves[2]]), tree.proof(&leaves[0]));
+        assert_eq!(Some(vec![first_pair]), tree.proof(&leaves[2]));
+        assert_eq!(None, tree.proof(&leaf("d")));
+    }
+
+    #[test]
+    fn verifies_the_proofs_of_the_leaves() {
+        let leaves: Vec<MerkleItem> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .cloned()
+            .map(leaf)
+            .collect();
+        let tree = MerkleTree::new(&leaves);
+        let root = tree.root();
+
+        for item in &leaves {
+            let proof = tree.proof(item).expect("The leaf should be in the tree");
+            assert!(MerkleTree::verify(&proof, &root, item));
+        }
+
+        let proof = tree
+            .proof(&leaves[0])
+            .expect("The leaf should be in the tree");
+        assert!(!MerkleTree::verify(&proof, &root, &leaf("f")));
+        assert!(!MerkleTree::verify(&proof[1..], &root, &leaves[0]));
+    }
+}
@@ -1,6 +1,20 @@
 #[cfg(any(test, feature = "fixtures"))]
 pub mod tests;
 
+/// Lowercases a `0x` prefixed hex address, since the case of an Ethereum address is only its
+/// (EIP-55) checksum. Any other value, e.g. a dummy identity, is kept as it is.
+pub fn normalize_address(address: &str) -> String {
+    let is_address = address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit());
+
+    if is_address {
+        address.to_ascii_lowercase()
+    } else {
+        address.to_string()
+    }
+}
+
 pub mod serde {
     pub mod ts_milliseconds_option {
         use chrono::serde::ts_milliseconds::deserialize as from_ts_milliseconds;
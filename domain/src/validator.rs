@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 pub use message::Message;
 
+use crate::util::normalize_address;
 use crate::{BigNum, DomainError};
 
 pub mod message;
@@ -18,7 +19,15 @@ impl TryFrom<&str> for ValidatorId {
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         // @TODO: Should we have some constrains(like valid hex string starting with `0x`)? If not this should be just `From`.
-        Ok(Self(value.to_string()))
+        Ok(Self(normalize_address(value)))
+    }
+}
+
+impl ValidatorId {
+    /// Lowercases the id, if it's an address (see `normalize_address`).
+    /// Needed for the deserialized ids, which aren't created with `ValidatorId::try_from`.
+    pub fn normalize(&mut self) {
+        self.0 = normalize_address(&self.0);
     }
 }
 
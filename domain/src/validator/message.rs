@@ -1,22 +1,31 @@
+use std::convert::TryFrom;
 use std::fmt;
 
 use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use crate::BalancesMap;
+use crate::{BalancesMap, DomainError};
 
-pub trait State: Clone {
-    type Signature: DeserializeOwned + Serialize + fmt::Display + fmt::Debug + Clone;
-    type StateRoot: DeserializeOwned + Serialize + fmt::Display + fmt::Debug + Clone;
+pub trait State: Clone + Send + Sync {
+    type Signature: DeserializeOwned + Serialize + fmt::Display + fmt::Debug + Clone + Send + Sync;
+    type StateRoot: DeserializeOwned
+        + Serialize
+        + fmt::Display
+        + fmt::Debug
+        + Clone
+        + PartialEq
+        + Send
+        + Sync;
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(tag = "type")]
+/// The `bound` lets every `State` (de)serialize the messages, not only the (de)serializable ones
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", bound = "S: State")]
 pub enum Message<S: State> {
     ApproveState(ApproveState<S>),
     NewState(NewState<S>),
-    RejectState(RejectState),
+    RejectState(RejectState<S>),
     Heartbeat(Heartbeat<S>),
     Accounting(Accounting),
 }
@@ -41,9 +50,52 @@ impl<S: State> Message<S> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MessageType(&'static str);
 
+impl MessageType {
+    /// The `type` tag of the serialized `Message`, e.g. `ApproveState` for `approve`
+    pub fn message_tag(&self) -> &'static str {
+        match self.0 {
+            "approve" => "ApproveState",
+            "new" => "NewState",
+            "reject" => "RejectState",
+            "heartbeat" => "Heartbeat",
+            "accounting" => "Accounting",
+            _ => unreachable!("MessageType can only be one of ALL_TYPES"),
+        }
+    }
+}
+
+impl TryFrom<&str> for MessageType {
+    type Error = DomainError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        ALL_TYPES
+            .iter()
+            .find(|message_type| message_type.0 == value)
+            .map(|&&message_type| message_type)
+            .ok_or_else(|| {
+                let all_types: Vec<&str> = ALL_TYPES
+                    .iter()
+                    .map(|message_type| message_type.0)
+                    .collect();
+
+                DomainError::InvalidArgument(format!(
+                    "Unknown message type '{}', expected one of: {}",
+                    value,
+                    all_types.join(", ")
+                ))
+            })
+    }
+}
+
+impl fmt::Display for MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 pub const TYPE_APPROVE: MessageType = MessageType("approve");
 pub const TYPE_NEW: MessageType = MessageType("new");
 pub const TYPE_REJECT: MessageType = MessageType("reject");
@@ -58,7 +110,7 @@ pub const ALL_TYPES: [&MessageType; 5] = [
 ];
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", bound = "S: State")]
 pub struct ApproveState<S: State> {
     pub state_root: S::StateRoot,
     pub signature: S::Signature,
@@ -66,7 +118,7 @@ pub struct ApproveState<S: State> {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", bound = "S: State")]
 pub struct NewState<S: State> {
     pub state_root: S::StateRoot,
     pub signature: S::Signature,
@@ -74,13 +126,15 @@ pub struct NewState<S: State> {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct RejectState {
+#[serde(rename_all = "camelCase", bound = "S: State")]
+pub struct RejectState<S: State> {
     pub reason: String,
+    /// The `state_root` of the rejected `NewState`
+    pub state_root: S::StateRoot,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", bound = "S: State")]
 pub struct Heartbeat<S: State> {
     pub signature: S::Signature,
     pub state_root: S::StateRoot,
@@ -151,9 +205,13 @@ pub mod fixtures {
         }
     }
 
-    pub fn get_reject_state(reason: Option<String>) -> RejectState {
+    pub fn get_reject_state<S: State>(
+        state_root: S::StateRoot,
+        reason: Option<String>,
+    ) -> RejectState<S> {
         RejectState {
             reason: reason.unwrap_or_else(|| <Faker as Lorem>::sentence(5, 4)),
+            state_root,
         }
     }
 
@@ -187,3 +245,22 @@ pub mod fixtures {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn message_type_from_str() {
+        for message_type in ALL_TYPES.iter() {
+            let parsed = MessageType::try_from(message_type.to_string().as_str())
+                .expect("Should parse all the message types");
+            assert_eq!(**message_type, parsed);
+        }
+
+        match MessageType::try_from("ApproveState") {
+            Err(DomainError::InvalidArgument(_)) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+    }
+}
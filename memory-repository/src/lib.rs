@@ -12,6 +12,17 @@ pub struct MemoryRepository<S: Clone, V> {
     cmp: Arc<dyn Fn(&S, &V) -> bool + Send + Sync>,
 }
 
+// `#[derive(Clone)]` would require `V` to be `Clone` as well
+impl<S: Clone, V> Clone for MemoryRepository<S, V> {
+    /// The clone shares the records with the original repository
+    fn clone(&self) -> Self {
+        Self {
+            records: self.records.clone(),
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
 impl<S: Clone, V> MemoryRepository<S, V> {
     /// The `cmp` function is used for `find`, `add` and `has`, where there
     /// is need to see if the record already exist based on a value. For example:
@@ -83,6 +94,17 @@ impl<S: Clone, V> MemoryRepository<S, V> {
             .map_err(MemoryRepositoryError::from)
     }
 
+    /// All the records matching the value, in their order
+    pub fn find_all(&self, cmp_value: &V) -> Result<Vec<S>, MemoryRepositoryError> {
+        self.list_all(|current| {
+            if (self.cmp)(current, cmp_value) {
+                Some(current.clone())
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn add(&self, cmp_value: &V, record: S) -> Result<(), MemoryRepositoryError> {
         if self.has(cmp_value)? {
             Err(MemoryRepositoryError::AlreadyExists)
@@ -97,6 +119,30 @@ impl<S: Clone, V> MemoryRepository<S, V> {
             }
         }
     }
+
+    /// Adds all the records at once, without checking if they already exist
+    pub fn extend(&self, records: Vec<S>) -> Result<(), MemoryRepositoryError> {
+        self.records
+            .write()
+            .map(|mut writer| writer.extend(records))
+            .map_err(MemoryRepositoryError::from)
+    }
+
+    /// Removes all the records matching the `predicate` and returns them in their order
+    pub fn remove_all<F>(&self, predicate: F) -> Result<Vec<S>, MemoryRepositoryError>
+    where
+        F: Fn(&S) -> bool,
+    {
+        self.records
+            .write()
+            .map(|mut writer| {
+                let (removed, kept) = writer.drain(..).partition(|record| predicate(record));
+                *writer = kept;
+
+                removed
+            })
+            .map_err(MemoryRepositoryError::from)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -144,8 +190,7 @@ impl Into<RepositoryError> for MemoryRepositoryError {
             MemoryRepositoryError::Reading | MemoryRepositoryError::Writing => {
                 RepositoryError::IO(Box::new(self))
             }
-            // @TODO: Implement AlreadyExist Error
-            MemoryRepositoryError::AlreadyExists => RepositoryError::User,
+            MemoryRepositoryError::AlreadyExists => RepositoryError::AlreadyExists,
         }
     }
 }
@@ -243,4 +288,52 @@ mod test {
 
         assert_eq!(3, list_all.len())
     }
+
+    #[test]
+    fn extends_with_records_in_order() {
+        let dummy_one = Dummy(1);
+        let cmp = Arc::new(|lhs: &Dummy, rhs: &Dummy| lhs == rhs);
+        let repo = MemoryRepository::new(&[dummy_one], cmp);
+
+        repo.extend(vec![Dummy(2), Dummy(1)])
+            .expect("Extending the records should succeed");
+
+        assert_eq!(
+            vec![dummy_one, Dummy(2), Dummy(1)],
+            repo.list_all(|x| Some(*x))
+                .expect("No error should happen here")
+        );
+    }
+
+    #[test]
+    fn finds_all_the_matching_records_in_order() {
+        let cmp = Arc::new(|lhs: &Dummy, rhs: &u8| lhs.0 % 2 == *rhs);
+        let repo = MemoryRepository::new(&[Dummy(1), Dummy(2), Dummy(3)], cmp);
+
+        assert_eq!(
+            vec![Dummy(1), Dummy(3)],
+            repo.find_all(&1).expect("No error should happen here")
+        );
+        assert_eq!(
+            Vec::<Dummy>::new(),
+            repo.find_all(&5).expect("No error should happen here")
+        );
+    }
+
+    #[test]
+    fn removes_all_the_matching_records_in_order() {
+        let cmp = Arc::new(|lhs: &Dummy, rhs: &Dummy| lhs == rhs);
+        let repo = MemoryRepository::new(&[Dummy(1), Dummy(2), Dummy(3), Dummy(4)], cmp);
+
+        assert_eq!(
+            vec![Dummy(1), Dummy(3)],
+            repo.remove_all(|x| x.0 % 2 == 1)
+                .expect("No error should happen here")
+        );
+        assert_eq!(
+            vec![Dummy(2), Dummy(4)],
+            repo.list_all(|x| Some(*x))
+                .expect("No error should happen here")
+        );
+    }
 }
@@ -0,0 +1,14 @@
+-- `IF NOT EXISTS` baselines the databases created by the former `init-db.sql`, which already have the table
+CREATE TABLE IF NOT EXISTS channels
+(
+    channel_id      VARCHAR(66)              NOT NULL,
+    creator         VARCHAR(255)             NOT NULL,
+    deposit_asset   VARCHAR(42)              NOT NULL,
+    deposit_amount  VARCHAR(255)             NOT NULL, -- @TODO change the deposit to BigNum compatible field
+    valid_until     TIMESTAMP WITH TIME ZONE NOT NULL,
+    spec            JSONB                    NOT NULL,
+    -- keeps the order in which the channels were added, used for listing them
+    insertion_order BIGSERIAL                NOT NULL,
+
+    PRIMARY KEY (channel_id)
+);
@@ -0,0 +1,5 @@
+-- NUMERIC(78, 0) can hold any uint256 value
+ALTER TABLE channels
+    ALTER COLUMN deposit_amount TYPE NUMERIC(78, 0) USING deposit_amount::NUMERIC(78, 0);
+
+CREATE INDEX channels_deposit_amount_idx ON channels (deposit_amount);
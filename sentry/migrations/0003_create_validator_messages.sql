@@ -0,0 +1,12 @@
+CREATE TABLE validator_messages
+(
+    id         BIGSERIAL                NOT NULL,
+    channel_id VARCHAR(66)              NOT NULL,
+    "from"     VARCHAR(255)             NOT NULL,
+    received   TIMESTAMP WITH TIME ZONE NOT NULL,
+    msg        JSONB                    NOT NULL,
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX validator_messages_channel_id_received_idx ON validator_messages (channel_id, received);
@@ -0,0 +1,11 @@
+CREATE TABLE events
+(
+    id         BIGSERIAL                NOT NULL,
+    channel_id VARCHAR(66)              NOT NULL,
+    created    TIMESTAMP WITH TIME ZONE NOT NULL,
+    event      JSONB                    NOT NULL,
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX events_channel_id_created_idx ON events (channel_id, created);
@@ -0,0 +1,11 @@
+CREATE TABLE event_aggregates
+(
+    id         BIGSERIAL                NOT NULL,
+    channel_id VARCHAR(66)              NOT NULL,
+    created    TIMESTAMP WITH TIME ZONE NOT NULL,
+    events     JSONB                    NOT NULL,
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX event_aggregates_channel_id_created_idx ON event_aggregates (channel_id, created);
@@ -0,0 +1,23 @@
+-- the channels tables created by the former `init-db.sql` were baselined by `create_channels` without this column.
+-- `ADD COLUMN` numbers the existing rows in their heap order, so they are renumbered by the creation of their spec.
+-- The tables created by `create_channels` already have the column, their order is kept.
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'channels' AND column_name = 'insertion_order'
+    ) THEN
+        ALTER TABLE channels
+            ADD COLUMN insertion_order BIGSERIAL NOT NULL;
+
+        -- the row numbers are within the values already taken from the sequence, so it doesn't need a reset
+        UPDATE channels
+        SET insertion_order = ordered.insertion_order
+        FROM (
+            SELECT channel_id, row_number() OVER (ORDER BY (spec->>'created')::BIGINT, channel_id) AS insertion_order
+            FROM channels
+        ) AS ordered
+        WHERE channels.channel_id = ordered.channel_id;
+    END IF;
+END
+$$;
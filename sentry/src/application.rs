@@ -1,2 +1,5 @@
+pub mod config;
 pub mod error;
+pub mod event_aggregator;
+pub mod middleware;
 pub mod resource;
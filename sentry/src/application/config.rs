@@ -0,0 +1,678 @@
+//! Sentry configuration, layered from (lowest to highest priority):
+//! the defaults, a TOML config file, the environment variables and the CLI arguments.
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use domain::util::normalize_address;
+use domain::DomainError;
+
+use crate::infrastructure::persistence::Storage;
+
+pub const DEFAULT_PORT: u16 = 8005;
+pub const DEFAULT_DATABASE_POOL_SIZE: u32 = 10;
+pub const DEFAULT_CHANNEL_LIST_LIMIT: u32 = 200;
+/// In milliseconds
+pub const DEFAULT_EVENT_AGGREGATE_INTERVAL: u64 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdapterKind {
+    Dummy,
+    /// Signs with the key of a JSON keystore
+    #[cfg(feature = "ethereum-adapter")]
+    Ethereum,
+}
+
+impl AdapterKind {
+    #[cfg(not(feature = "ethereum-adapter"))]
+    pub const VALUES: [&'static str; 1] = ["dummy"];
+    #[cfg(feature = "ethereum-adapter")]
+    pub const VALUES: [&'static str; 2] = ["dummy", "ethereum"];
+
+    /// Whether the adapter requires the `keystore` and its password
+    pub fn requires_keystore(self) -> bool {
+        match self {
+            AdapterKind::Dummy => false,
+            #[cfg(feature = "ethereum-adapter")]
+            AdapterKind::Ethereum => true,
+        }
+    }
+}
+
+impl Default for AdapterKind {
+    fn default() -> Self {
+        AdapterKind::Dummy
+    }
+}
+
+impl TryFrom<&str> for AdapterKind {
+    type Error = DomainError;
+
+    fn try_from(adapter: &str) -> Result<Self, Self::Error> {
+        match adapter {
+            "dummy" => Ok(AdapterKind::Dummy),
+            #[cfg(feature = "ethereum-adapter")]
+            "ethereum" => Ok(AdapterKind::Ethereum),
+            _ => Err(DomainError::InvalidArgument(format!(
+                "Unknown adapter '{}', expected one of: {}",
+                adapter,
+                AdapterKind::VALUES.join(", ")
+            ))),
+        }
+    }
+}
+
+/// The validated configuration, can only be created from a `PartialConfig`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub port: u16,
+    pub bind_address: IpAddr,
+    /// Required when the `storage` is `Postgres`
+    pub database_url: Option<String>,
+    pub database_pool_size: u32,
+    pub channel_list_limit: u32,
+    /// How often (in milliseconds) the submitted events are folded into aggregates
+    pub event_aggregate_interval: u64,
+    pub storage: Storage,
+    pub adapter: AdapterKind,
+    /// The validator identity used with the adapter, lowercased if it's an address.
+    /// Required only for serving, see `Config::adapter_settings()`
+    pub identity: Option<String>,
+    /// The JSON keystore of the validator key, required for the `ethereum` adapter
+    pub keystore: Option<PathBuf>,
+    /// The password of the `keystore`
+    pub keystore_password: Option<String>,
+    /// The address of the AdEx Core contract, when set the ids of the created Channels are checked
+    pub core_address: Option<String>,
+    /// The addresses of the reverse proxies, which are trusted to set the `X-Forwarded-For` header
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Config {
+    pub fn socket_address(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_address, self.port)
+    }
+
+    /// Validates the adapter settings, which are required for serving, but not e.g. for migrating
+    pub fn adapter_settings(&self) -> Result<AdapterSettings, DomainError> {
+        let identity = match &self.identity {
+            Some(identity) if identity.is_empty() => {
+                return Err(invalid_argument("The identity should not be empty"))
+            }
+            Some(identity) => identity.clone(),
+            None => return Err(invalid_argument("The identity is required for the adapter")),
+        };
+
+        let keystore = match (&self.keystore, &self.keystore_password) {
+            (Some(keystore), Some(password)) => Some((keystore.clone(), password.clone())),
+            _ => None,
+        };
+        if self.adapter.requires_keystore() && keystore.is_none() {
+            return Err(invalid_argument(
+                "The keystore and its password are required for the adapter",
+            ));
+        }
+
+        Ok(AdapterSettings {
+            adapter: self.adapter,
+            identity,
+            keystore,
+        })
+    }
+}
+
+/// The validated settings of the adapter
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdapterSettings {
+    pub adapter: AdapterKind,
+    /// The validator identity, lowercased if it's an address
+    pub identity: String,
+    /// The JSON keystore of the validator key and its password, set for the `ethereum` adapter
+    pub keystore: Option<(PathBuf, String)>,
+}
+
+impl TryFrom<PartialConfig> for Config {
+    type Error = DomainError;
+
+    fn try_from(partial: PartialConfig) -> Result<Self, Self::Error> {
+        let config = Self {
+            port: partial.port.unwrap_or(DEFAULT_PORT),
+            bind_address: partial
+                .bind_address
+                .unwrap_or_else(|| IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            database_url: partial.database_url,
+            database_pool_size: partial
+                .database_pool_size
+                .unwrap_or(DEFAULT_DATABASE_POOL_SIZE),
+            channel_list_limit: partial
+                .channel_list_limit
+                .unwrap_or(DEFAULT_CHANNEL_LIST_LIMIT),
+            event_aggregate_interval: partial
+                .event_aggregate_interval
+                .unwrap_or(DEFAULT_EVENT_AGGREGATE_INTERVAL),
+            storage: partial.storage.unwrap_or_default(),
+            adapter: partial.adapter.unwrap_or_default(),
+            identity: partial
+                .identity
+                .map(|identity| normalize_address(&identity)),
+            keystore: partial.keystore,
+            keystore_password: partial.keystore_password,
+            core_address: partial.core_address,
+            trusted_proxies: partial.trusted_proxies.unwrap_or_default(),
+        };
+
+        if config.database_pool_size < 1 {
+            return Err(invalid_argument("The database pool size should be >= 1"));
+        }
+
+        if config.channel_list_limit < 1 {
+            return Err(invalid_argument("The channel list limit should be >= 1"));
+        }
+
+        if config.event_aggregate_interval < 1 {
+            return Err(invalid_argument(
+                "The event aggregate interval should be >= 1",
+            ));
+        }
+
+        if config.storage == Storage::Postgres && config.database_url.is_none() {
+            return Err(invalid_argument(
+                "The database url is required for the postgres storage",
+            ));
+        }
+
+        if config.database_url.as_ref().map(String::is_empty) == Some(true) {
+            return Err(invalid_argument("The database url should not be empty"));
+        }
+
+        if let Some(core_address) = &config.core_address {
+            let is_address = core_address.len() == 42
+                && core_address.starts_with("0x")
+                && hex::decode(&core_address[2..]).is_ok();
+            if !is_address {
+                return Err(invalid_argument(
+                    "The core address should be a 0x prefixed address",
+                ));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// A single configuration setting, used for reading it from the different sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    Port,
+    BindAddress,
+    DatabaseUrl,
+    DatabasePoolSize,
+    ChannelListLimit,
+    EventAggregateInterval,
+    Storage,
+    Adapter,
+    Identity,
+    Keystore,
+    KeystorePassword,
+    CoreAddress,
+    TrustedProxies,
+}
+
+impl Setting {
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Setting::Port => "PORT",
+            Setting::BindAddress => "SENTRY_BIND_ADDRESS",
+            Setting::DatabaseUrl => "DATABASE_URL",
+            Setting::DatabasePoolSize => "SENTRY_DATABASE_POOL_SIZE",
+            Setting::ChannelListLimit => "SENTRY_CHANNEL_LIST_LIMIT",
+            Setting::EventAggregateInterval => "SENTRY_EVENT_AGGREGATE_INTERVAL",
+            Setting::Storage => "SENTRY_STORAGE",
+            Setting::Adapter => "SENTRY_ADAPTER",
+            Setting::Identity => "SENTRY_IDENTITY",
+            Setting::Keystore => "SENTRY_KEYSTORE",
+            Setting::KeystorePassword => "SENTRY_KEYSTORE_PASSWORD",
+            Setting::CoreAddress => "SENTRY_CORE_ADDRESS",
+            Setting::TrustedProxies => "SENTRY_TRUSTED_PROXIES",
+        }
+    }
+
+    /// The key in the TOML config file, also used as the name of the CLI argument
+    pub fn name(self) -> &'static str {
+        match self {
+            Setting::Port => "port",
+            Setting::BindAddress => "bind_address",
+            Setting::DatabaseUrl => "database_url",
+            Setting::DatabasePoolSize => "database_pool_size",
+            Setting::ChannelListLimit => "channel_list_limit",
+            Setting::EventAggregateInterval => "event_aggregate_interval",
+            Setting::Storage => "storage",
+            Setting::Adapter => "adapter",
+            Setting::Identity => "identity",
+            Setting::Keystore => "keystore",
+            Setting::KeystorePassword => "keystore_password",
+            Setting::CoreAddress => "core_address",
+            Setting::TrustedProxies => "trusted_proxies",
+        }
+    }
+}
+
+/// Configuration from a single source, where every setting is optional
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialConfig {
+    pub port: Option<u16>,
+    pub bind_address: Option<IpAddr>,
+    pub database_url: Option<String>,
+    pub database_pool_size: Option<u32>,
+    pub channel_list_limit: Option<u32>,
+    pub event_aggregate_interval: Option<u64>,
+    pub storage: Option<Storage>,
+    pub adapter: Option<AdapterKind>,
+    pub identity: Option<String>,
+    pub keystore: Option<PathBuf>,
+    pub keystore_password: Option<String>,
+    pub core_address: Option<String>,
+    pub trusted_proxies: Option<Vec<IpAddr>>,
+}
+
+impl PartialConfig {
+    pub fn from_toml(toml: &str) -> Result<Self, DomainError> {
+        toml::from_str(toml)
+            .map_err(|error| invalid_argument(&format!("Invalid config file: {}", error)))
+    }
+
+    /// Only the environment variables of the settings are used, all the others are ignored.
+    pub fn from_env<I>(vars: I) -> Result<Self, DomainError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let vars: HashMap<String, String> = vars.into_iter().collect();
+
+        Self::from_values(
+            |setting| vars.get(setting.env_var()).map(String::as_str),
+            |setting| setting.env_var().to_string(),
+        )
+    }
+
+    /// Parses the settings from string values, e.g. environment variables or CLI arguments.
+    /// `source_name` is used for the error messages, e.g. `PORT` or `--port`.
+    pub fn from_values<'a, V, N>(value: V, source_name: N) -> Result<Self, DomainError>
+    where
+        V: Fn(Setting) -> Option<&'a str>,
+        N: Fn(Setting) -> String,
+    {
+        Ok(Self {
+            port: parse_setting(Setting::Port, &value, &source_name)?,
+            bind_address: parse_setting(Setting::BindAddress, &value, &source_name)?,
+            database_url: value(Setting::DatabaseUrl).map(ToString::to_string),
+            database_pool_size: parse_setting(Setting::DatabasePoolSize, &value, &source_name)?,
+            channel_list_limit: parse_setting(Setting::ChannelListLimit, &value, &source_name)?,
+            event_aggregate_interval: parse_setting(
+                Setting::EventAggregateInterval,
+                &value,
+                &source_name,
+            )?,
+            storage: value(Setting::Storage).map(Storage::try_from).transpose()?,
+            adapter: value(Setting::Adapter).map(AdapterKind::try_from).transpose()?,
+            identity: value(Setting::Identity).map(ToString::to_string),
+            keystore: value(Setting::Keystore).map(PathBuf::from),
+            keystore_password: value(Setting::KeystorePassword).map(ToString::to_string),
+            core_address: value(Setting::CoreAddress).map(ToString::to_string),
+            trusted_proxies: parse_ip_list(Setting::TrustedProxies, &value, &source_name)?,
+        })
+    }
+
+    /// The settings of `other` take priority over the ones of `self`
+    pub fn merge(self, other: PartialConfig) -> PartialConfig {
+        PartialConfig {
+            port: other.port.or(self.port),
+            bind_address: other.bind_address.or(self.bind_address),
+            database_url: other.database_url.or(self.database_url),
+            database_pool_size: other.database_pool_size.or(self.database_pool_size),
+            channel_list_limit: other.channel_list_limit.or(self.channel_list_limit),
+            event_aggregate_interval: other
+                .event_aggregate_interval
+                .or(self.event_aggregate_interval),
+            storage: other.storage.or(self.storage),
+            adapter: other.adapter.or(self.adapter),
+            identity: other.identity.or(self.identity),
+            keystore: other.keystore.or(self.keystore),
+            keystore_password: other.keystore_password.or(self.keystore_password),
+            core_address: other.core_address.or(self.core_address),
+            trusted_proxies: other.trusted_proxies.or(self.trusted_proxies),
+        }
+    }
+}
+
+fn parse_setting<'a, T, V, N>(
+    setting: Setting,
+    value: &V,
+    source_name: &N,
+) -> Result<Option<T>, DomainError>
+where
+    T: FromStr,
+    T::Err: Display,
+    V: Fn(Setting) -> Option<&'a str>,
+    N: Fn(Setting) -> String,
+{
+    value(setting)
+        .map(|value| {
+            value.parse().map_err(|error| {
+                let message = format!("Invalid {} '{}': {}", source_name(setting), value, error);
+
+                invalid_argument(&message)
+            })
+        })
+        .transpose()
+}
+
+/// Parses a comma separated list of IP addresses, e.g. `10.0.0.1,10.0.0.2`
+fn parse_ip_list<'a, V, N>(
+    setting: Setting,
+    value: &V,
+    source_name: &N,
+) -> Result<Option<Vec<IpAddr>>, DomainError>
+where
+    V: Fn(Setting) -> Option<&'a str>,
+    N: Fn(Setting) -> String,
+{
+    value(setting)
+        .map(|list| {
+            list.split(',')
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty())
+                .map(|ip| {
+                    ip.parse().map_err(|error| {
+                        let message =
+                            format!("Invalid {} '{}': {}", source_name(setting), ip, error);
+
+                        invalid_argument(&message)
+                    })
+                })
+                .collect()
+        })
+        .transpose()
+}
+
+fn invalid_argument(message: &str) -> DomainError {
+    DomainError::InvalidArgument(message.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CORE_ADDRESS: &str = "0x333420fc6a897356e69b62417cd17ff012177d2b";
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn defaults_are_used_for_missing_settings() {
+        let identity_only = PartialConfig {
+            identity: Some("awesomeLeader".to_string()),
+            ..PartialConfig::default()
+        };
+        let config = Config::try_from(identity_only).expect("Should be valid");
+
+        assert_eq!(DEFAULT_PORT, config.port);
+        assert_eq!(IpAddr::V4(Ipv4Addr::UNSPECIFIED), config.bind_address);
+        assert_eq!(DEFAULT_DATABASE_POOL_SIZE, config.database_pool_size);
+        assert_eq!(DEFAULT_CHANNEL_LIST_LIMIT, config.channel_list_limit);
+        assert_eq!(
+            DEFAULT_EVENT_AGGREGATE_INTERVAL,
+            config.event_aggregate_interval
+        );
+        assert_eq!(Storage::Memory, config.storage);
+        assert_eq!(AdapterKind::Dummy, config.adapter);
+        assert_eq!(None, config.database_url);
+        assert_eq!(Some("awesomeLeader".to_string()), config.identity);
+        assert_eq!(None, config.keystore);
+        assert_eq!(None, config.core_address);
+        assert!(config.trusted_proxies.is_empty());
+        assert_eq!("0.0.0.0:8005".parse(), Ok(config.socket_address()));
+    }
+
+    #[test]
+    fn reads_the_settings_from_env() {
+        let vars = env(&[
+            ("PORT", "9000"),
+            ("SENTRY_BIND_ADDRESS", "127.0.0.1"),
+            ("DATABASE_URL", "postgresql://localhost/sentry"),
+            ("SENTRY_DATABASE_POOL_SIZE", "5"),
+            ("SENTRY_CHANNEL_LIST_LIMIT", "50"),
+            ("SENTRY_EVENT_AGGREGATE_INTERVAL", "1000"),
+            ("SENTRY_STORAGE", "postgres"),
+            ("SENTRY_ADAPTER", "dummy"),
+            ("SENTRY_IDENTITY", "awesomeLeader"),
+            ("SENTRY_KEYSTORE", "keystore.json"),
+            ("SENTRY_KEYSTORE_PASSWORD", "password"),
+            ("SENTRY_CORE_ADDRESS", CORE_ADDRESS),
+            ("SENTRY_TRUSTED_PROXIES", "10.0.0.1, 10.0.0.2"),
+            ("UNRELATED", "value"),
+        ]);
+
+        let expected = PartialConfig {
+            port: Some(9000),
+            bind_address: Some("127.0.0.1".parse().unwrap()),
+            database_url: Some("postgresql://localhost/sentry".to_string()),
+            database_pool_size: Some(5),
+            channel_list_limit: Some(50),
+            event_aggregate_interval: Some(1000),
+            storage: Some(Storage::Postgres),
+            adapter: Some(AdapterKind::Dummy),
+            identity: Some("awesomeLeader".to_string()),
+            keystore: Some(PathBuf::from("keystore.json")),
+            keystore_password: Some("password".to_string()),
+            core_address: Some(CORE_ADDRESS.to_string()),
+            trusted_proxies: Some(vec![
+                "10.0.0.1".parse().unwrap(),
+                "10.0.0.2".parse().unwrap(),
+            ]),
+        };
+
+        assert_eq!(Ok(expected), PartialConfig::from_env(vars));
+    }
+
+    #[test]
+    fn invalid_env_value_errors_with_its_name() {
+        let error = PartialConfig::from_env(env(&[("SENTRY_CHANNEL_LIST_LIMIT", "many")]))
+            .expect_err("Should not parse the limit");
+
+        match error {
+            DomainError::InvalidArgument(message) => {
+                assert!(message.contains("SENTRY_CHANNEL_LIST_LIMIT"))
+            }
+            _ => panic!("Expected InvalidArgument error"),
+        }
+
+        assert!(PartialConfig::from_env(env(&[("SENTRY_STORAGE", "mysql")])).is_err());
+        assert!(PartialConfig::from_env(env(&[("PORT", "70000")])).is_err());
+        let invalid_proxy = env(&[("SENTRY_TRUSTED_PROXIES", "10.0.0.1,proxy")]);
+        assert!(PartialConfig::from_env(invalid_proxy).is_err());
+    }
+
+    #[test]
+    fn reads_the_settings_from_toml() {
+        let toml = r#"
+            port = 9000
+            bind_address = "127.0.0.1"
+            storage = "postgres"
+            database_url = "postgresql://localhost/sentry"
+            channel_list_limit = 100
+            trusted_proxies = ["10.0.0.1"]
+        "#;
+
+        let expected = PartialConfig {
+            port: Some(9000),
+            bind_address: Some("127.0.0.1".parse().unwrap()),
+            database_url: Some("postgresql://localhost/sentry".to_string()),
+            channel_list_limit: Some(100),
+            storage: Some(Storage::Postgres),
+            trusted_proxies: Some(vec!["10.0.0.1".parse().unwrap()]),
+            ..PartialConfig::default()
+        };
+
+        assert_eq!(Ok(expected), PartialConfig::from_toml(toml));
+        assert!(PartialConfig::from_toml("unknown_setting = 1").is_err());
+        assert!(PartialConfig::from_toml("storage = \"mysql\"").is_err());
+    }
+
+    #[test]
+    fn merge_prefers_the_other_config() {
+        let file = PartialConfig {
+            port: Some(9000),
+            channel_list_limit: Some(100),
+            ..PartialConfig::default()
+        };
+        let env = PartialConfig {
+            port: Some(9001),
+            identity: Some("awesomeLeader".to_string()),
+            ..PartialConfig::default()
+        };
+
+        let merged = file.merge(env);
+
+        assert_eq!(Some(9001), merged.port);
+        assert_eq!(Some(100), merged.channel_list_limit);
+        assert_eq!(Some("awesomeLeader".to_string()), merged.identity);
+        assert_eq!(None, merged.storage);
+    }
+
+    #[test]
+    fn validates_the_config() {
+        let valid = PartialConfig {
+            identity: Some("awesomeLeader".to_string()),
+            ..PartialConfig::default()
+        };
+        assert!(Config::try_from(valid.clone()).is_ok());
+
+        let postgres_without_url = PartialConfig {
+            storage: Some(Storage::Postgres),
+            ..valid.clone()
+        };
+        assert!(Config::try_from(postgres_without_url).is_err());
+
+        let zero_limit = PartialConfig {
+            channel_list_limit: Some(0),
+            ..valid.clone()
+        };
+        assert!(Config::try_from(zero_limit).is_err());
+
+        let zero_interval = PartialConfig {
+            event_aggregate_interval: Some(0),
+            ..valid.clone()
+        };
+        assert!(Config::try_from(zero_interval).is_err());
+
+        let zero_pool_size = PartialConfig {
+            database_pool_size: Some(0),
+            ..valid.clone()
+        };
+        assert!(Config::try_from(zero_pool_size).is_err());
+
+        let invalid_core_address = PartialConfig {
+            core_address: Some("core".to_string()),
+            ..valid.clone()
+        };
+        assert!(Config::try_from(invalid_core_address).is_err());
+    }
+
+    #[test]
+    fn validates_the_adapter_settings() {
+        let valid = PartialConfig {
+            identity: Some("awesomeLeader".to_string()),
+            ..PartialConfig::default()
+        };
+        let expected = AdapterSettings {
+            adapter: AdapterKind::Dummy,
+            identity: "awesomeLeader".to_string(),
+            keystore: None,
+        };
+        let config = Config::try_from(valid.clone()).expect("Should be valid");
+        assert_eq!(Ok(expected), config.adapter_settings());
+
+        let empty_identity = PartialConfig {
+            identity: Some(String::new()),
+            ..valid.clone()
+        };
+        let config = Config::try_from(empty_identity).expect("Should be valid for migrating");
+        assert!(config.adapter_settings().is_err());
+
+        let missing_identity = PartialConfig {
+            identity: None,
+            ..valid
+        };
+        let config = Config::try_from(missing_identity).expect("Should be valid for migrating");
+        assert!(config.adapter_settings().is_err());
+    }
+
+    #[test]
+    fn the_database_url_is_enough_for_migrating() {
+        let database_only = PartialConfig {
+            database_url: Some("postgresql://localhost/sentry".to_string()),
+            ..PartialConfig::default()
+        };
+
+        let config = Config::try_from(database_only).expect("Should be valid for migrating");
+        assert_eq!(
+            Some("postgresql://localhost/sentry".to_string()),
+            config.database_url
+        );
+        assert_eq!(None, config.identity);
+    }
+
+    #[cfg(feature = "ethereum-adapter")]
+    #[test]
+    fn the_ethereum_adapter_requires_the_keystore_and_its_password() {
+        let ethereum = PartialConfig {
+            adapter: Some(AdapterKind::Ethereum),
+            identity: Some("0x2c7536e3605d9c16a7a3d7b1898e529396a65c23".to_string()),
+            keystore: Some(PathBuf::from("keystore.json")),
+            keystore_password: Some("password".to_string()),
+            ..PartialConfig::default()
+        };
+        let settings = |partial: PartialConfig| {
+            Config::try_from(partial)
+                .expect("Should be valid for migrating")
+                .adapter_settings()
+        };
+        let expected = AdapterSettings {
+            adapter: AdapterKind::Ethereum,
+            identity: "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23".to_string(),
+            keystore: Some((PathBuf::from("keystore.json"), "password".to_string())),
+        };
+        assert_eq!(Ok(expected.clone()), settings(ethereum.clone()));
+        let checksummed = PartialConfig {
+            identity: Some("0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string()),
+            ..ethereum.clone()
+        };
+        assert_eq!(Ok(expected), settings(checksummed));
+        assert_eq!(
+            Ok(Some(AdapterKind::Ethereum)),
+            PartialConfig::from_env(env(&[("SENTRY_ADAPTER", "ethereum")])).map(|env| env.adapter)
+        );
+
+        let missing_keystore = PartialConfig {
+            keystore: None,
+            ..ethereum.clone()
+        };
+        assert!(settings(missing_keystore).is_err());
+
+        let missing_password = PartialConfig {
+            keystore_password: None,
+            ..ethereum
+        };
+        assert!(settings(missing_password).is_err());
+    }
+}
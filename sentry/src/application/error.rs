@@ -1,34 +1,304 @@
+use http::StatusCode;
+use serde::Serialize;
+
+use adapter::{AdapterError, SanityError};
+use domain::channel::validation::RuleViolation;
+use domain::{DomainError, RepositoryError};
+
+use crate::application::resource::json_response;
+
+const INTERNAL_SERVER_ERROR: &str = "Internal Server Error";
+
 #[derive(Debug)]
 pub enum ApplicationError {
+    /// The requested resource does not exist
     NotFound,
-    NoHost,
-    InvalidHostValue,
-    NoRedirectFound,
+    /// The request is missing the authentication
+    Unauthorized,
+    /// The authenticated identity is not allowed to make the request
+    Forbidden(String),
+    /// The request was rate limited
+    TooManyRequests(String),
+    Domain(DomainError),
+    Repository(RepositoryError),
+    Adapter(AdapterError),
+    /// The request violates one or more rules, e.g. when creating a Channel
+    Validation(Vec<ViolatedRule>),
     InternalError,
 }
 
 impl ApplicationError {
-    // @TODO: Error handling https://github.com/AdExNetwork/adex-validator-stack-rust/issues/7
-    pub fn as_response(&self) -> Result<hyper::Response<hyper::Body>, http::Error> {
-        hyper::Response::builder()
-            .status(match self {
-                ApplicationError::NoHost | ApplicationError::InvalidHostValue => {
-                    hyper::StatusCode::BAD_REQUEST
-                }
-                ApplicationError::NoRedirectFound | ApplicationError::NotFound => {
-                    hyper::StatusCode::NOT_FOUND
-                }
-                ApplicationError::InternalError => hyper::StatusCode::INTERNAL_SERVER_ERROR,
-            })
-            .body(
-                match self {
-                    ApplicationError::NotFound => "Route Not Found",
-                    ApplicationError::NoHost => "Missing Host header",
-                    ApplicationError::InvalidHostValue => "Invalid Host header",
-                    ApplicationError::NoRedirectFound => "No redirect found for that host",
-                    ApplicationError::InternalError => "Internal Server Error",
-                }
-                .into(),
-            )
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApplicationError::NotFound => StatusCode::NOT_FOUND,
+            ApplicationError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApplicationError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApplicationError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApplicationError::Domain(_) | ApplicationError::Validation(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            ApplicationError::Repository(RepositoryError::AlreadyExists) => StatusCode::CONFLICT,
+            ApplicationError::Adapter(AdapterError::Authentication(_)) => StatusCode::UNAUTHORIZED,
+            ApplicationError::Repository(_)
+            | ApplicationError::Adapter(_)
+            | ApplicationError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// A machine readable code of the error, returned in the response body
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApplicationError::NotFound => "not_found",
+            ApplicationError::Unauthorized => "unauthorized",
+            ApplicationError::Forbidden(_) => "forbidden",
+            ApplicationError::TooManyRequests(_) => "too_many_requests",
+            ApplicationError::Domain(DomainError::InvalidArgument(_)) => "invalid_argument",
+            ApplicationError::Domain(DomainError::RuleViolation(_)) => "rule_violation",
+            ApplicationError::Validation(_) => "validation_failed",
+            ApplicationError::Repository(RepositoryError::AlreadyExists) => "already_exists",
+            ApplicationError::Adapter(AdapterError::Authentication(_)) => "authentication_failed",
+            ApplicationError::Repository(_)
+            | ApplicationError::Adapter(_)
+            | ApplicationError::InternalError => "internal_error",
+        }
+    }
+
+    /// The internal errors are not exposed to the client
+    pub fn message(&self) -> String {
+        match self {
+            ApplicationError::NotFound => "Not Found".to_string(),
+            ApplicationError::Unauthorized => "Authentication is required".to_string(),
+            ApplicationError::Forbidden(message) | ApplicationError::TooManyRequests(message) => {
+                message.clone()
+            }
+            ApplicationError::Domain(error) => error.to_string(),
+            ApplicationError::Validation(_) => "The request violates one or more rules".to_string(),
+            ApplicationError::Repository(RepositoryError::AlreadyExists) => {
+                "The record already exists".to_string()
+            }
+            ApplicationError::Adapter(error @ AdapterError::Authentication(_)) => error.to_string(),
+            ApplicationError::Repository(_)
+            | ApplicationError::Adapter(_)
+            | ApplicationError::InternalError => INTERNAL_SERVER_ERROR.to_string(),
+        }
+    }
+
+    pub fn as_response(&self) -> http::Response<String> {
+        let violations = match self {
+            ApplicationError::Validation(violations) => violations.as_slice(),
+            _ => &[],
+        };
+
+        let body = ErrorResponse {
+            message: self.message(),
+            code: self.code(),
+            violations,
+        };
+
+        json_response(self.status(), &body)
+    }
+}
+
+impl From<DomainError> for ApplicationError {
+    fn from(error: DomainError) -> Self {
+        ApplicationError::Domain(error)
+    }
+}
+
+impl From<RepositoryError> for ApplicationError {
+    fn from(error: RepositoryError) -> Self {
+        ApplicationError::Repository(error)
+    }
+}
+
+impl From<AdapterError> for ApplicationError {
+    fn from(error: AdapterError) -> Self {
+        ApplicationError::Adapter(error)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse<'a> {
+    pub message: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "<[ViolatedRule]>::is_empty")]
+    pub violations: &'a [ViolatedRule],
+}
+
+/// A violated rule of the request, e.g. a domain rule of the Channel or an adapter check
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ViolatedRule {
+    pub rule: String,
+    pub message: String,
+}
+
+impl ViolatedRule {
+    pub fn adapter_validation() -> Self {
+        Self {
+            rule: "adapter_validation".to_string(),
+            message: "The adapter did not validate the channel".to_string(),
+        }
+    }
+
+    /// The channel id doesn't match the one computed from the channel
+    pub fn channel_id(message: String) -> Self {
+        Self {
+            rule: "channel_id".to_string(),
+            message,
+        }
+    }
+}
+
+impl From<RuleViolation> for ViolatedRule {
+    fn from(violation: RuleViolation) -> Self {
+        Self {
+            rule: violation.rule.to_string(),
+            message: violation.message,
+        }
+    }
+}
+
+impl From<SanityError> for ViolatedRule {
+    fn from(error: SanityError) -> Self {
+        Self {
+            rule: "adapter_sanity_check".to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+
+    use domain::IOError;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct DatabaseError;
+
+    impl fmt::Display for DatabaseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "connection refused")
+        }
+    }
+
+    impl std::error::Error for DatabaseError {}
+    impl IOError for DatabaseError {}
+
+    fn assert_response(error: ApplicationError, status: StatusCode, body: &str) {
+        let response = error.as_response();
+
+        assert_eq!(status, response.status());
+        assert_eq!(
+            "application/json",
+            response.headers()[http::header::CONTENT_TYPE]
+        );
+        assert_eq!(body, response.body());
+    }
+
+    #[test]
+    fn maps_not_found() {
+        assert_response(
+            ApplicationError::NotFound,
+            StatusCode::NOT_FOUND,
+            r#"{"message":"Not Found","code":"not_found"}"#,
+        );
+    }
+
+    #[test]
+    fn maps_authentication_errors() {
+        assert_response(
+            ApplicationError::Unauthorized,
+            StatusCode::UNAUTHORIZED,
+            r#"{"message":"Authentication is required","code":"unauthorized"}"#,
+        );
+        assert_response(
+            ApplicationError::Forbidden("Not a channel validator".to_string()),
+            StatusCode::FORBIDDEN,
+            r#"{"message":"Not a channel validator","code":"forbidden"}"#,
+        );
+    }
+
+    #[test]
+    fn maps_too_many_requests() {
+        assert_response(
+            ApplicationError::TooManyRequests("Rate limit exceeded".to_string()),
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"message":"Rate limit exceeded","code":"too_many_requests"}"#,
+        );
+    }
+
+    #[test]
+    fn maps_domain_errors() {
+        assert_response(
+            DomainError::InvalidArgument("Page should be >= 1".to_string()).into(),
+            StatusCode::BAD_REQUEST,
+            r#"{"message":"Invalid argument: Page should be >= 1","code":"invalid_argument"}"#,
+        );
+        assert_response(
+            DomainError::RuleViolation("Deposit too low".to_string()).into(),
+            StatusCode::BAD_REQUEST,
+            r#"{"message":"Rule violation: Deposit too low","code":"rule_violation"}"#,
+        );
+    }
+
+    #[test]
+    fn maps_repository_errors() {
+        assert_response(
+            RepositoryError::AlreadyExists.into(),
+            StatusCode::CONFLICT,
+            r#"{"message":"The record already exists","code":"already_exists"}"#,
+        );
+        // the underlying error should not be exposed
+        assert_response(
+            RepositoryError::IO(Box::new(DatabaseError)).into(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"message":"Internal Server Error","code":"internal_error"}"#,
+        );
+        assert_response(
+            RepositoryError::User.into(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"message":"Internal Server Error","code":"internal_error"}"#,
+        );
+    }
+
+    #[test]
+    fn maps_adapter_errors() {
+        assert_response(
+            AdapterError::Authentication("Invalid signature".to_string()).into(),
+            StatusCode::UNAUTHORIZED,
+            r#"{"message":"Authentication error: Invalid signature","code":"authentication_failed"}"#,
+        );
+        // the underlying error should not be exposed
+        assert_response(
+            AdapterError::Signature("invalid secret key".to_string()).into(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"message":"Internal Server Error","code":"internal_error"}"#,
+        );
+    }
+
+    #[test]
+    fn maps_validation_errors_with_the_violated_rules() {
+        let violations = vec![ViolatedRule {
+            rule: "deposit_covers_validator_fees".to_string(),
+            message: "message".to_string(),
+        }];
+
+        assert_response(
+            ApplicationError::Validation(violations),
+            StatusCode::BAD_REQUEST,
+            r#"{"message":"The request violates one or more rules","code":"validation_failed","violations":[{"rule":"deposit_covers_validator_fees","message":"message"}]}"#,
+        );
+    }
+
+    #[test]
+    fn maps_internal_error() {
+        assert_response(
+            ApplicationError::InternalError,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            r#"{"message":"Internal Server Error","code":"internal_error"}"#,
+        );
     }
 }
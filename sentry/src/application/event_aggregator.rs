@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures::future::FutureExt;
+use tokio::await;
+
+use domain::{Channel, EventAggregate, RepositoryError};
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::event::{ChannelEvent, EventFold, EventRepository};
+
+#[cfg(test)]
+#[path = "./event_aggregator_test.rs"]
+mod event_aggregator_test;
+
+/// Folds the submitted events into `EventAggregate`s, it's run periodically by the server
+pub struct EventAggregator {
+    channel_repository: Arc<dyn ChannelRepository>,
+    event_repository: Arc<dyn EventRepository>,
+}
+
+impl EventAggregator {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        event_repository: Arc<dyn EventRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            event_repository,
+        }
+    }
+}
+
+impl EventAggregator {
+    /// Takes the events created until `now` and stores them as one `EventAggregate` per Channel,
+    /// created at `now`. Returns the number of the stored aggregates.
+    /// If the aggregates can't be stored, the events are kept for the next run.
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn aggregate<'a>(&'a self, now: DateTime<Utc>) -> Result<usize, ApplicationError> {
+        // the aggregates are listed `after` a millisecond timestamp, with a more precise `created`
+        // the last aggregate of a page would be listed again on the next one
+        let now = Utc.timestamp_millis(now.timestamp_millis());
+        let channel_repository = self.channel_repository.clone();
+        let fold: EventFold = Box::new(move |channel_events| {
+            fold_events(channel_repository, channel_events, now).boxed()
+        });
+
+        let count = await!(self.event_repository.aggregate(now, fold))?;
+
+        Ok(count)
+    }
+}
+
+async fn fold_events(
+    channel_repository: Arc<dyn ChannelRepository>,
+    channel_events: Vec<ChannelEvent>,
+    now: DateTime<Utc>,
+) -> Result<Vec<EventAggregate>, RepositoryError> {
+    let mut folded: Vec<(Channel, EventAggregate)> = Vec::new();
+
+    for channel_event in channel_events {
+        let position = folded
+            .iter()
+            .position(|(channel, _)| channel.id == channel_event.channel_id);
+
+        let index = match position {
+            Some(index) => index,
+            None => match await!(channel_repository.find(&channel_event.channel_id))? {
+                Some(channel) => {
+                    let aggregate = EventAggregate::new(channel.id, now);
+                    folded.push((channel, aggregate));
+
+                    folded.len() - 1
+                }
+                // the events are accepted only for existing channels, which are never removed
+                None => continue,
+            },
+        };
+
+        let (channel, aggregate) = &mut folded[index];
+        aggregate.add(channel, &channel_event.event);
+    }
+
+    Ok(folded.into_iter().map(|(_, aggregate)| aggregate).collect())
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use chrono::{TimeZone, Timelike, Utc};
+use time::Duration;
+
+use domain::fixtures::*;
+use domain::{BigNum, Channel, Event};
+
+use crate::domain::event::ChannelEvent;
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::event::MemoryEventRepository;
+use crate::infrastructure::persistence::event_aggregate::MemoryEventAggregateRepository;
+
+use super::*;
+
+struct Repositories {
+    event_repository: Arc<MemoryEventRepository>,
+    event_aggregate_repository: Arc<MemoryEventAggregateRepository>,
+}
+
+fn aggregator(
+    channels: &[Channel],
+    channel_events: &[ChannelEvent],
+) -> (EventAggregator, Repositories) {
+    let event_aggregate_repository = Arc::new(MemoryEventAggregateRepository::new(None));
+    let repositories = Repositories {
+        event_repository: Arc::new(MemoryEventRepository::new(
+            Some(channel_events),
+            event_aggregate_repository.clone(),
+        )),
+        event_aggregate_repository,
+    };
+    let aggregator = EventAggregator::new(
+        Arc::new(MemoryChannelRepository::new(Some(channels))),
+        repositories.event_repository.clone(),
+    );
+
+    (aggregator, repositories)
+}
+
+fn impression(channel: &Channel, publisher: &str) -> ChannelEvent {
+    ChannelEvent {
+        channel_id: channel.id,
+        created: Utc::now() - Duration::seconds(1),
+        event: Event::Impression {
+            publisher: publisher.to_string(),
+            ad_unit: None,
+        },
+    }
+}
+
+#[test]
+fn folds_the_events_into_one_aggregate_per_channel() {
+    futures::executor::block_on(async {
+        let mut first = get_channel("channel 1", &None, None);
+        first.spec.min_per_impression = BigNum::from(10);
+        let second = get_channel("channel 2", &None, None);
+        let channel_events = [
+            impression(&first, "publisher"),
+            impression(&second, "publisher"),
+            impression(&first, "publisher"),
+        ];
+        let (aggregator, repositories) =
+            aggregator(&[first.clone(), second.clone()], &channel_events);
+
+        let now = Utc::now();
+        let count = await!(aggregator.aggregate(now)).expect("Aggregating the events failed");
+        assert_eq!(2, count);
+
+        let aggregate_repository = &repositories.event_aggregate_repository;
+        let aggregates = await!(aggregate_repository.list(&first.id, None, 10))
+            .expect("Listing the aggregates failed");
+        assert_eq!(1, aggregates.len());
+        assert_eq!(
+            now.timestamp_millis(),
+            aggregates[0].created.timestamp_millis()
+        );
+
+        let impressions = &aggregates[0].events["IMPRESSION"];
+        assert_eq!(BigNum::from(2), impressions.event_counts["publisher"]);
+        assert_eq!(BigNum::from(20), impressions.event_payouts["publisher"]);
+
+        let count =
+            await!(aggregator.aggregate(Utc::now())).expect("Aggregating the events failed");
+        assert_eq!(0, count, "The events should be aggregated only once");
+    })
+}
+
+#[test]
+fn without_events_no_aggregates_are_stored() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let later = ChannelEvent {
+            created: Utc::now() + Duration::minutes(1),
+            ..impression(&channel, "publisher")
+        };
+        let (aggregator, repositories) = aggregator(&[channel.clone()], &[later]);
+
+        let count =
+            await!(aggregator.aggregate(Utc::now())).expect("Aggregating the events failed");
+        assert_eq!(0, count);
+
+        let aggregate_repository = &repositories.event_aggregate_repository;
+        let aggregates = await!(aggregate_repository.list(&channel.id, None, 10))
+            .expect("Listing the aggregates failed");
+        assert!(aggregates.is_empty());
+    })
+}
+
+#[test]
+fn the_created_of_the_aggregates_round_trips_through_the_after_millis() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let (aggregator, repositories) =
+            aggregator(&[channel.clone()], &[impression(&channel, "publisher")]);
+
+        let sub_millisecond = (Utc::now() + Duration::seconds(1))
+            .with_nanosecond(123_456_789)
+            .expect("Should be a valid time");
+        await!(aggregator.aggregate(sub_millisecond)).expect("Aggregating the events failed");
+
+        let aggregate_repository = &repositories.event_aggregate_repository;
+        let aggregates = await!(aggregate_repository.list(&channel.id, None, 10))
+            .expect("Listing the aggregates failed");
+        let created = aggregates[0].created;
+        assert_eq!(123_000_000, created.nanosecond());
+
+        // `GET /channel/:id/events-aggregates?after=<created millis>`
+        let after = Utc.timestamp_millis(created.timestamp_millis());
+        assert_eq!(created, after);
+        let next_page = await!(aggregate_repository.list(&channel.id, Some(after), 10))
+            .expect("Listing the aggregates failed");
+        assert!(
+            next_page.is_empty(),
+            "The last aggregate should not be listed again"
+        );
+    })
+}
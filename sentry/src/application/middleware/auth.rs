@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use futures::future::TryFutureExt;
+use futures_legacy::future::{self, Future};
+use futures_legacy::{try_ready, Async, Poll};
+use http::header::AUTHORIZATION;
+use http::{Request, Response};
+use tower_service::Service;
+use tower_web::extract::{Context, Extract, Immediate};
+use tower_web::middleware::Middleware;
+use tower_web::util::BufStream;
+
+use adapter::{current_era, AdapterError, Session};
+
+use crate::application::error::ApplicationError;
+use crate::application::resource::bearer_token;
+use crate::domain::adapter::SentryAdapter;
+
+/// The maximum number of cached sessions, the cache is cleared when it's reached
+const SESSIONS_LIMIT: usize = 10_000;
+
+type Sessions = Arc<RwLock<HashMap<String, Session>>>;
+
+/// The authentication of a request, resolved by the `AuthMiddleware`.
+/// The handlers extract it as an argument, e.g. `auth: Auth`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Auth {
+    /// The request has no `Authorization: Bearer` token
+    Anonymous,
+    Session(Session),
+    /// The adapter couldn't verify the token of the request
+    Failed(AdapterError),
+}
+
+impl Auth {
+    /// The Session of the request or `None` for anonymous requests
+    pub fn session(&self) -> Result<Option<&Session>, ApplicationError> {
+        match self {
+            Auth::Anonymous => Ok(None),
+            Auth::Session(session) => Ok(Some(session)),
+            Auth::Failed(error) => Err(ApplicationError::Adapter(error.clone())),
+        }
+    }
+
+    /// The Session of the request, which is required
+    pub fn required_session(&self) -> Result<&Session, ApplicationError> {
+        self.session()?.ok_or(ApplicationError::Unauthorized)
+    }
+}
+
+impl<B: BufStream> Extract<B> for Auth {
+    type Future = Immediate<Auth>;
+
+    fn extract(context: &Context<'_>) -> Self::Future {
+        let auth = context.request().extensions().get::<Auth>().cloned();
+
+        Immediate::ok(auth.unwrap_or(Auth::Anonymous))
+    }
+}
+
+/// Verifies the `Authorization: Bearer` token of the requests with the adapter
+/// and caches the resulting Session by token, until the era of the Session passes
+#[derive(Clone)]
+pub struct AuthMiddleware {
+    adapter: Arc<dyn SentryAdapter>,
+    sessions: Sessions,
+}
+
+impl AuthMiddleware {
+    pub fn new(adapter: Arc<dyn SentryAdapter>) -> Self {
+        Self {
+            adapter,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl<S, RequestBody, ResponseBody> Middleware<S> for AuthMiddleware
+where
+    S: Service<Request = Request<RequestBody>, Response = Response<ResponseBody>> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    RequestBody: Send + 'static,
+{
+    type Request = Request<RequestBody>;
+    type Response = Response<ResponseBody>;
+    type Error = S::Error;
+    type Service = AuthService<S>;
+
+    fn wrap(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner: Arc::new(Mutex::new(inner)),
+            adapter: self.adapter.clone(),
+            sessions: self.sessions.clone(),
+        }
+    }
+}
+
+/// Inserts the `Auth` of the request in its extensions before calling the inner service
+pub struct AuthService<S> {
+    // the inner service is polled and called from the future, once the token is verified
+    inner: Arc<Mutex<S>>,
+    adapter: Arc<dyn SentryAdapter>,
+    sessions: Sessions,
+}
+
+impl<S> AuthService<S> {
+    fn auth<E: Send + 'static>(
+        &self,
+        token: Option<String>,
+    ) -> Box<dyn Future<Item = Auth, Error = E> + Send> {
+        let token = match token {
+            Some(token) => token,
+            None => return Box::new(future::ok(Auth::Anonymous)),
+        };
+
+        let cached = self
+            .sessions
+            .read()
+            .expect("The sessions lock is poisoned")
+            .get(&token)
+            .cloned();
+        match cached {
+            Some(session) if session.is_valid_in(current_era()) => {
+                return Box::new(future::ok(Auth::Session(session)));
+            }
+            // the token could have expired, so it's verified again
+            Some(_) => evict_session(&self.sessions, &token),
+            None => {}
+        }
+
+        let sessions = self.sessions.clone();
+        let verify = self.adapter.verify_auth(&token).compat();
+
+        Box::new(verify.then(move |result| {
+            let auth = match result {
+                Ok(session) => {
+                    cache_session(&sessions, token, session.clone());
+
+                    Auth::Session(session)
+                }
+                Err(error) => Auth::Failed(error),
+            };
+
+            Ok::<_, E>(auth)
+        }))
+    }
+}
+
+fn cache_session(sessions: &Sessions, token: String, session: Session) {
+    let mut sessions = sessions.write().expect("The sessions lock is poisoned");
+    if sessions.len() >= SESSIONS_LIMIT {
+        sessions.clear();
+    }
+
+    sessions.insert(token, session);
+}
+
+fn evict_session(sessions: &Sessions, token: &str) {
+    sessions
+        .write()
+        .expect("The sessions lock is poisoned")
+        .remove(token);
+}
+
+impl<S, RequestBody, ResponseBody> Service for AuthService<S>
+where
+    S: Service<Request = Request<RequestBody>, Response = Response<ResponseBody>> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    RequestBody: Send + 'static,
+{
+    type Request = Request<RequestBody>;
+    type Response = Response<ResponseBody>;
+    type Error = S::Error;
+    type Future = Box<dyn Future<Item = Self::Response, Error = Self::Error> + Send>;
+
+    /// Always ready, the readiness of the inner service is checked right before calling it,
+    /// as other requests could be verified and call it in the meantime
+    fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+        Ok(Async::Ready(()))
+    }
+
+    fn call(&mut self, mut request: Self::Request) -> Self::Future {
+        let authorization = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        let token = bearer_token(authorization).map(ToString::to_string);
+
+        let inner = self.inner.clone();
+        let response = self.auth(token).and_then(move |auth| {
+            request.extensions_mut().insert(auth);
+            let mut request = Some(request);
+
+            future::poll_fn(move || {
+                let mut inner = inner.lock().expect("The inner service lock is poisoned");
+                try_ready!(inner.poll_ready());
+                let request = request.take().expect("The request is called only once");
+
+                Ok(Async::Ready(inner.call(request)))
+            })
+            .flatten()
+        });
+
+        Box::new(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_legacy::task;
+
+    use adapter::dummy::{DummyAdapter, DummyParticipant};
+    use adapter::{ConfigBuilder, MAX_ERA_DIFFERENCE};
+
+    use super::*;
+
+    /// Responds with the `Auth` inserted by the middleware
+    struct AuthEcho;
+
+    impl Service for AuthEcho {
+        type Request = Request<()>;
+        type Response = Response<Option<Auth>>;
+        type Error = ();
+        type Future = future::FutureResult<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&mut self, request: Self::Request) -> Self::Future {
+            future::ok(Response::new(request.extensions().get::<Auth>().cloned()))
+        }
+    }
+
+    /// Gets ready on the second poll and responds with `None`
+    struct ReadyOnSecondPoll {
+        polls: usize,
+    }
+
+    impl Service for ReadyOnSecondPoll {
+        type Request = Request<()>;
+        type Response = Response<Option<Auth>>;
+        type Error = ();
+        type Future = future::FutureResult<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self) -> Poll<(), Self::Error> {
+            self.polls += 1;
+            if self.polls < 2 {
+                task::current().notify();
+
+                return Ok(Async::NotReady);
+            }
+
+            Ok(Async::Ready(()))
+        }
+
+        fn call(&mut self, _request: Self::Request) -> Self::Future {
+            assert!(
+                self.polls >= 2,
+                "The service was called before it was ready"
+            );
+
+            future::ok(Response::new(None))
+        }
+    }
+
+    fn middleware() -> AuthMiddleware {
+        let mut participants = HashMap::new();
+        participants.insert(
+            "publisher",
+            DummyParticipant {
+                identity: "publisher".to_string(),
+                token: "publisher token".to_string(),
+            },
+        );
+        let adapter = DummyAdapter {
+            config: ConfigBuilder::new("leader").build(),
+            participants,
+        };
+
+        AuthMiddleware::new(Arc::new(adapter))
+    }
+
+    fn auth(service: &mut AuthService<AuthEcho>, authorization: Option<&str>) -> Auth {
+        let mut request = Request::builder();
+        if let Some(authorization) = authorization {
+            request.header(AUTHORIZATION, authorization);
+        }
+        let request = request.body(()).expect("Building the request failed");
+
+        service
+            .call(request)
+            .wait()
+            .expect("The service should respond")
+            .into_body()
+            .expect("The Auth should be inserted")
+    }
+
+    #[test]
+    fn resolves_the_bearer_token_to_a_session() {
+        let middleware = middleware();
+        let mut service = middleware.wrap(AuthEcho);
+
+        let publisher = match auth(&mut service, Some("Bearer publisher token")) {
+            Auth::Session(session) => session,
+            auth => panic!("Expected a Session, got {:?}", auth),
+        };
+        assert_eq!("publisher", publisher.uid);
+        assert!(
+            publisher.is_valid_in(current_era()),
+            "The dummy Session should be of the current era, so it's cached"
+        );
+        assert_eq!(Auth::Anonymous, auth(&mut service, None));
+
+        match auth(&mut service, Some("Bearer wrong token")) {
+            Auth::Failed(AdapterError::Authentication(_)) => {}
+            auth => panic!("Expected a failed authentication, got {:?}", auth),
+        }
+
+        let sessions = middleware.sessions.read().expect("Reading failed");
+        assert_eq!(Some(&publisher), sessions.get("publisher token"));
+        assert_eq!(1, sessions.len(), "Only the verified tokens are cached");
+    }
+
+    #[test]
+    fn the_sessions_are_cached_only_in_their_era() {
+        let middleware = middleware();
+        let mut service = middleware.wrap(AuthEcho);
+        let cached = |era: i64| Session {
+            uid: "cached".to_string(),
+            era,
+        };
+
+        let current = cached(current_era());
+        cache_session(&middleware.sessions, "token".to_string(), current.clone());
+        assert_eq!(
+            Auth::Session(current),
+            auth(&mut service, Some("Bearer token"))
+        );
+
+        let expired = cached(current_era() - MAX_ERA_DIFFERENCE - 1);
+        cache_session(&middleware.sessions, "token".to_string(), expired);
+        match auth(&mut service, Some("Bearer token")) {
+            Auth::Failed(AdapterError::Authentication(_)) => {}
+            auth => panic!("Expected a failed authentication, got {:?}", auth),
+        }
+
+        let sessions = middleware.sessions.read().expect("Reading failed");
+        assert!(
+            sessions.get("token").is_none(),
+            "The expired Session is evicted"
+        );
+    }
+
+    #[test]
+    fn the_inner_service_is_called_once_it_is_ready() {
+        let mut service = middleware().wrap(ReadyOnSecondPoll { polls: 0 });
+        let request = Request::builder()
+            .header(AUTHORIZATION, "Bearer publisher token")
+            .body(())
+            .expect("Building the request failed");
+
+        let response = service
+            .call(request)
+            .wait()
+            .expect("The service should respond");
+        assert_eq!(None, response.into_body());
+    }
+
+    #[test]
+    fn the_failed_authentication_is_an_error() {
+        let failed = Auth::Failed(AdapterError::Authentication("Token not found".to_string()));
+
+        match failed.session() {
+            Err(ApplicationError::Adapter(AdapterError::Authentication(_))) => {}
+            result => panic!("Expected an Authentication error, got {:?}", result),
+        }
+
+        match Auth::Anonymous.required_session() {
+            Err(ApplicationError::Unauthorized) => {}
+            result => panic!("Expected an Unauthorized error, got {:?}", result),
+        }
+        assert_eq!(
+            None,
+            Auth::Anonymous.session().expect("Should be anonymous")
+        );
+    }
+}
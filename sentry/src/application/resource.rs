@@ -1 +1,98 @@
+use std::net::IpAddr;
+
+use http::StatusCode;
+use serde::Serialize;
+
+use crate::application::error::ApplicationError;
+
 pub mod channel;
+pub mod event;
+pub mod validator_message;
+
+pub(crate) fn json_response<T: Serialize>(status: StatusCode, body: &T) -> http::Response<String> {
+    let body = serde_json::to_string(body).expect("Serializing the response body failed");
+
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .expect("Building the response failed")
+}
+
+/// Responds with the JSON of the `Ok` body or with the `ApplicationError` response
+pub(crate) fn result_response<T: Serialize>(
+    result: Result<T, ApplicationError>,
+) -> http::Response<String> {
+    match result {
+        Ok(body) => json_response(StatusCode::OK, &body),
+        Err(error) => error.as_response(),
+    }
+}
+
+/// Returns the token of an `Authorization: Bearer <token>` header value
+pub(crate) fn bearer_token(authorization: Option<&str>) -> Option<&str> {
+    authorization
+        .filter(|value| value.starts_with("Bearer "))
+        .map(|value| value["Bearer ".len()..].trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// Returns the client IP address of a request, which is the address of the connection peer,
+/// unless the peer is a trusted proxy. Then it's the last `X-Forwarded-For` address,
+/// which isn't of a trusted proxy, since the ones before it could be set by the client.
+pub(crate) fn client_ip(
+    peer_addr: Option<IpAddr>,
+    x_forwarded_for: Option<&str>,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    let proxy = match peer_addr {
+        Some(proxy) if trusted_proxies.contains(&proxy) => proxy,
+        peer_addr => return peer_addr,
+    };
+
+    let forwarded = x_forwarded_for
+        .unwrap_or_default()
+        .rsplit(',')
+        .map(|ip| ip.trim().parse::<IpAddr>().ok());
+    for ip in forwarded {
+        match ip {
+            Some(ip) if trusted_proxies.contains(&ip) => continue,
+            Some(ip) => return Some(ip),
+            None => break,
+        }
+    }
+
+    Some(proxy)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_the_bearer_token() {
+        assert_eq!(Some("token"), bearer_token(Some("Bearer token")));
+        assert_eq!(None, bearer_token(Some("Bearer ")));
+        assert_eq!(None, bearer_token(Some("Basic dXNlcjpwYXNz")));
+        assert_eq!(None, bearer_token(None));
+    }
+
+    #[test]
+    fn the_client_ip_is_the_peer_address_unless_it_is_a_trusted_proxy() {
+        let ip = |ip: &str| ip.parse::<IpAddr>().unwrap();
+        let client = Some(ip("203.0.113.1"));
+        let proxy = Some(ip("10.0.0.1"));
+        let trusted_proxies = &[ip("10.0.0.1"), ip("10.0.0.2")];
+
+        let spoofed = Some("198.51.100.2");
+        assert_eq!(client, client_ip(client, spoofed, trusted_proxies));
+        assert_eq!(client, client_ip(client, None, &[]));
+        assert_eq!(None, client_ip(None, spoofed, trusted_proxies));
+
+        let forwarded = Some("198.51.100.2, 203.0.113.1, 10.0.0.2");
+        assert_eq!(client, client_ip(proxy, forwarded, trusted_proxies));
+        assert_eq!(proxy, client_ip(proxy, forwarded, &[]));
+        assert_eq!(proxy, client_ip(proxy, Some("not an ip"), trusted_proxies));
+        assert_eq!(proxy, client_ip(proxy, None, trusted_proxies));
+    }
+}
@@ -3,19 +3,30 @@ use futures_legacy::Future;
 use tokio::await;
 use tower_web::{derive_resource_impl, impl_web, Deserialize, Extract};
 
-use channel_create::{ChannelCreateHandler, ChannelCreateResponse, ChannelInput};
-use channel_list::{ChannelListHandler, ChannelListResponse};
+use channel_create::{ChannelCreateHandler, ChannelInput};
+use channel_get::ChannelGetHandler;
+use channel_list::ChannelListHandler;
+use channel_status::ChannelStatusHandler;
 
+use crate::application::resource::result_response;
+use crate::domain::adapter::SentryAdapter;
 use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::ValidatorMessageRepository;
 use std::sync::Arc;
 
 mod channel_create;
+mod channel_get;
 mod channel_list;
+mod channel_status;
 
 #[derive(Clone)]
 pub struct ChannelResource {
     pub channel_list_limit: u32,
     pub channel_repository: Arc<dyn ChannelRepository>,
+    pub validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+    pub adapter: Arc<dyn SentryAdapter>,
+    /// The address of the AdEx Core contract, the ids of the created channels are checked with it
+    pub core_address: Option<String>,
 }
 
 impl_web! {
@@ -23,19 +34,49 @@ impl_web! {
     impl ChannelResource {
         #[post("/channel")]
         #[content_type("application/json")]
-        async fn create_channel(&self, body: ChannelInput) -> ChannelCreateResponse {
+        async fn create_channel(&self, body: ChannelInput) -> http::Response<String> {
+            let handler = ChannelCreateHandler::new(
+                self.channel_repository.clone(),
+                self.adapter.clone(),
+                self.core_address.clone(),
+            );
 
-            let handler = ChannelCreateHandler::new(self.channel_repository.clone());
-
-            await!(handler.handle(body).boxed().compat()).unwrap()
+            result_response(await!(handler.handle(body).boxed().compat()))
         }
 
         #[get("/channel/list")]
         #[content_type("application/json")]
-        async fn channel_list(&self, query_string: ChannelListQuery) -> ChannelListResponse {
+        async fn channel_list(&self, query_string: ChannelListQuery) -> http::Response<String> {
             let handler = ChannelListHandler::new(self.channel_list_limit, self.channel_repository.clone());
 
-            await!(handler.handle(query_string.page(), query_string.validator()).boxed().compat()).unwrap()
+            let handle = handler.handle(
+                query_string.page(),
+                query_string.validator(),
+                query_string.deposit_amount_ge(),
+                query_string.order_by(),
+            );
+
+            result_response(await!(handle.boxed().compat()))
+        }
+
+        // declared after `/channel/list`, so that `list` is not matched as an id
+        #[get("/channel/:id")]
+        #[content_type("application/json")]
+        async fn channel_get(&self, id: String) -> http::Response<String> {
+            let handler = ChannelGetHandler::new(self.channel_repository.clone());
+
+            result_response(await!(handler.handle(&id).boxed().compat()))
+        }
+
+        #[get("/channel/:id/status")]
+        #[content_type("application/json")]
+        async fn channel_status(&self, id: String) -> http::Response<String> {
+            let handler = ChannelStatusHandler::new(
+                self.channel_repository.clone(),
+                self.validator_message_repository.clone(),
+            );
+
+            result_response(await!(handler.handle(&id).boxed().compat()))
         }
     }
 }
@@ -44,6 +85,9 @@ impl_web! {
 struct ChannelListQuery {
     page: Option<u64>,
     validator: Option<String>,
+    deposit_amount_ge: Option<String>,
+    /// `deposit_amount` for ascending or `-deposit_amount` for descending order
+    order_by: Option<String>,
 }
 
 impl ChannelListQuery {
@@ -55,12 +99,24 @@ impl ChannelListQuery {
     }
 
     pub fn validator(&self) -> Option<&str> {
-        self.validator.as_ref().and_then(|s| {
-            if s.is_empty() {
-                return None;
-            }
+        non_empty(&self.validator)
+    }
+
+    pub fn deposit_amount_ge(&self) -> Option<&str> {
+        non_empty(&self.deposit_amount_ge)
+    }
 
-            Some(s.as_str())
-        })
+    pub fn order_by(&self) -> Option<&str> {
+        non_empty(&self.order_by)
     }
 }
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_ref().and_then(|s| {
+        if s.is_empty() {
+            return None;
+        }
+
+        Some(s.as_str())
+    })
+}
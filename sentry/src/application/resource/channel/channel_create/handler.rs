@@ -1,28 +1,50 @@
+use std::sync::Arc;
+
 use tokio::await;
 
-use crate::domain::channel::ChannelRepository;
+use domain::channel::validation::validate_channel;
 use domain::Channel;
 
-use super::ChannelCreateResponse;
-use super::ChannelInput;
-use std::sync::Arc;
+use crate::application::error::{ApplicationError, ViolatedRule};
+use crate::domain::adapter::SentryAdapter;
+use crate::domain::channel::ChannelRepository;
+
+use super::{ChannelCreateResponse, ChannelInput};
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
 
 pub struct ChannelCreateHandler {
     channel_repository: Arc<dyn ChannelRepository>,
+    adapter: Arc<dyn SentryAdapter>,
+    /// When set, the channel id should be the one computed with the AdEx Core contract address,
+    /// otherwise the channel id isn't checked
+    core_address: Option<String>,
 }
 
 impl ChannelCreateHandler {
-    pub fn new(channel_repository: Arc<dyn ChannelRepository>) -> Self {
-        Self { channel_repository }
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        adapter: Arc<dyn SentryAdapter>,
+        core_address: Option<String>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            adapter,
+            core_address,
+        }
     }
 }
 
 impl ChannelCreateHandler {
     #[allow(clippy::needless_lifetimes)]
-    pub async fn handle(&self, channel_input: ChannelInput) -> Result<ChannelCreateResponse, ()> {
-        // @TODO: Creating Channel Validation
-
-        let channel = Channel {
+    pub async fn handle(
+        &self,
+        channel_input: ChannelInput,
+    ) -> Result<ChannelCreateResponse, ApplicationError> {
+        let spec_hash = channel_input.spec_hash;
+        let mut channel = Channel {
             id: channel_input.id,
             creator: channel_input.creator,
             deposit_asset: channel_input.deposit_asset,
@@ -31,8 +53,48 @@ impl ChannelCreateHandler {
             spec: channel_input.spec,
         };
 
-        let success = await!(self.channel_repository.add(channel)).is_ok();
+        let mut violations: Vec<ViolatedRule> = Vec::new();
+
+        // the id is of the channel as the creator submitted it, i.e. before its addresses are normalized
+        if let Some(core_address) = &self.core_address {
+            match spec_hash.map(|spec_hash| channel.compute_id(core_address, &spec_hash)) {
+                Some(Ok(channel_id)) if channel_id == channel.id => {}
+                Some(Ok(channel_id)) => violations.push(ViolatedRule::channel_id(format!(
+                    "The id should be {}",
+                    channel_id
+                ))),
+                Some(Err(error)) => violations.push(ViolatedRule::channel_id(error.to_string())),
+                None => violations.push(ViolatedRule::channel_id(
+                    "The specHash is required for checking the id".to_string(),
+                )),
+            }
+        }
+
+        channel.normalize_addresses();
+
+        if let Err(sanity_error) = self.adapter.sanity_check(&channel) {
+            violations.push(sanity_error.into());
+        }
+
+        if let Err(rule_violations) = validate_channel(&channel) {
+            violations.extend(rule_violations.into_iter().map(ViolatedRule::from));
+        }
+
+        // the adapter validation might be expensive, so only run it for an otherwise valid channel
+        if violations.is_empty() {
+            let is_valid = await!(self.adapter.validate_channel(&channel))?;
+
+            if !is_valid {
+                violations.push(ViolatedRule::adapter_validation());
+            }
+        }
+
+        if !violations.is_empty() {
+            return Err(ApplicationError::Validation(violations));
+        }
+
+        await!(self.channel_repository.add(channel))?;
 
-        Ok(ChannelCreateResponse { success })
+        Ok(ChannelCreateResponse { success: true })
     }
 }
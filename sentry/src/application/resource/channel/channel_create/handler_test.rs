@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use http::StatusCode;
+use time::Duration;
+
+use adapter::dummy::DummyAdapter;
+use adapter::ConfigBuilder;
+use domain::fixtures::*;
+use domain::{BigNum, Channel};
+
+use crate::domain::channel::ChannelRepository;
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+
+use super::*;
+
+const CORE_ADDRESS: &str = "0x333420fc6a897356e69b62417cd17ff012177d2b";
+const SPEC_HASH: &str = "0x5f3a0a6e3b6f7a1b5c2d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b";
+
+fn handler_with_identity(identity: &str) -> (ChannelCreateHandler, Arc<MemoryChannelRepository>) {
+    handler(identity, None)
+}
+
+fn handler(
+    identity: &str,
+    core_address: Option<&str>,
+) -> (ChannelCreateHandler, Arc<MemoryChannelRepository>) {
+    let channel_repository = Arc::new(MemoryChannelRepository::new(None));
+    let adapter = DummyAdapter {
+        config: ConfigBuilder::new(identity).build(),
+        participants: HashMap::new(),
+    };
+    let core_address = core_address.map(ToString::to_string);
+
+    let handler =
+        ChannelCreateHandler::new(channel_repository.clone(), Arc::new(adapter), core_address);
+
+    (handler, channel_repository)
+}
+
+/// A channel with addresses for the creator, the deposit asset & the validators
+fn ethereum_channel() -> Channel {
+    let leader = get_validator("0xce07cbb7e054514d590a0262c93070d838bfba2e", None);
+    let follower = get_validator("0xc91763d7f14ac5c5ddfbcd012e0d2a61ab9bded3", None);
+    let spec = get_channel_spec(ValidatorsOption::Pair { leader, follower });
+
+    let mut channel = get_channel("channel 1", &None, Some(spec));
+    channel.creator = "0x033ed90e0fec3f3ea1c9b005c724d704501e0196".to_string();
+    channel.deposit_asset = "0x89d24a6b4ccb1b6faa2625fe562bdd9a23260359".into();
+
+    channel
+}
+
+fn channel_input(channel: Channel) -> ChannelInput {
+    ChannelInput {
+        id: channel.id,
+        creator: channel.creator,
+        deposit_asset: channel.deposit_asset,
+        deposit_amount: channel.deposit_amount,
+        valid_until: channel.valid_until,
+        spec: channel.spec,
+        spec_hash: Some(SPEC_HASH.to_string()),
+    }
+}
+
+fn violated_rules(error: ApplicationError) -> Vec<String> {
+    match error {
+        ApplicationError::Validation(violations) => violations
+            .into_iter()
+            .map(|violation| violation.rule)
+            .collect(),
+        error => panic!("Expected a validation error, got {:?}", error),
+    }
+}
+
+#[test]
+fn creates_a_valid_channel() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let (handler, channel_repository) =
+            handler_with_identity(channel.spec.validators.leader().id.as_ref());
+
+        let response = await!(handler.handle(channel_input(channel.clone())))
+            .expect("The channel should be created");
+        assert!(response.success);
+
+        let saved = await!(channel_repository.find(&channel.id))
+            .expect("Should not fail")
+            .expect("Should find the channel");
+        assert_eq!(channel.id, saved.id);
+    })
+}
+
+#[test]
+fn reports_the_violated_domain_rules() {
+    futures::executor::block_on(async {
+        let mut channel = get_channel("channel 1", &None, None);
+        let (handler, channel_repository) =
+            handler_with_identity(channel.spec.validators.leader().id.as_ref());
+
+        channel.spec.withdraw_period_start = channel.valid_until + Duration::days(1);
+        channel.spec.min_per_impression = &channel.spec.max_per_impression + &BigNum::from(1);
+
+        let error = await!(handler.handle(channel_input(channel.clone())))
+            .expect_err("The channel should be invalid");
+        assert_eq!(
+            vec![
+                "withdraw_period_start_before_valid_until".to_string(),
+                "min_per_impression_not_above_max".to_string(),
+            ],
+            violated_rules(error)
+        );
+
+        let saved = await!(channel_repository.find(&channel.id)).expect("Should not fail");
+        assert!(saved.is_none());
+    })
+}
+
+#[test]
+fn reports_the_adapter_sanity_check() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let (handler, _) = handler_with_identity("non_existent_validator");
+
+        let error = await!(handler.handle(channel_input(channel)))
+            .expect_err("The adapter is not a validator of the channel");
+
+        assert_eq!(StatusCode::BAD_REQUEST, error.as_response().status());
+        assert_eq!(
+            vec!["adapter_sanity_check".to_string()],
+            violated_rules(error)
+        );
+    })
+}
+
+#[test]
+fn existing_channel_is_a_conflict() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let (handler, _) = handler_with_identity(channel.spec.validators.leader().id.as_ref());
+
+        await!(handler.handle(channel_input(channel.clone())))
+            .expect("The channel should be created");
+        let error =
+            await!(handler.handle(channel_input(channel))).expect_err("The channel already exists");
+
+        assert_eq!(StatusCode::CONFLICT, error.as_response().status());
+    })
+}
+
+#[test]
+fn creates_a_channel_with_the_computed_id() {
+    futures::executor::block_on(async {
+        let mut channel = ethereum_channel();
+        channel.id = channel
+            .compute_id(CORE_ADDRESS, SPEC_HASH)
+            .expect("Computing the id failed");
+        let (handler, channel_repository) = handler(
+            channel.spec.validators.leader().id.as_ref(),
+            Some(CORE_ADDRESS),
+        );
+
+        await!(handler.handle(channel_input(channel.clone())))
+            .expect("The channel should be created");
+
+        let saved = await!(channel_repository.find(&channel.id)).expect("Should not fail");
+        assert!(saved.is_some());
+    })
+}
+
+#[test]
+fn creates_a_channel_with_the_normalized_addresses() {
+    futures::executor::block_on(async {
+        let expected = ethereum_channel();
+        let checksummed_leader = "0xCe07CbB7e054514D590a0262C93070D838bFBA2e";
+        let json = serde_json::to_string(&expected)
+            .expect("Serializing the channel failed")
+            .replace(
+                "0x033ed90e0fec3f3ea1c9b005c724d704501e0196",
+                "0x033ED90e0FeC3F3ea1C9b005C724D704501e0196",
+            )
+            .replace(
+                expected.spec.validators.leader().id.as_ref(),
+                checksummed_leader,
+            );
+        let mut channel: Channel = serde_json::from_str(&json).expect("Invalid channel JSON");
+        // the id is of the checksummed addresses
+        channel.id = channel
+            .compute_id(CORE_ADDRESS, SPEC_HASH)
+            .expect("Computing the id failed");
+        let (handler, channel_repository) = handler(checksummed_leader, Some(CORE_ADDRESS));
+
+        await!(handler.handle(channel_input(channel.clone())))
+            .expect("The channel should be created");
+
+        let saved = await!(channel_repository.find(&channel.id))
+            .expect("Should not fail")
+            .expect("Should find the channel");
+        assert_eq!(expected.creator, saved.creator);
+        assert_eq!(
+            expected.spec.validators.leader().id.as_ref(),
+            saved.spec.validators.leader().id.as_ref()
+        );
+    })
+}
+
+#[test]
+fn reports_the_channel_id_which_does_not_match() {
+    futures::executor::block_on(async {
+        let channel = ethereum_channel();
+        let (handler, channel_repository) = handler(
+            channel.spec.validators.leader().id.as_ref(),
+            Some(CORE_ADDRESS),
+        );
+
+        let error = await!(handler.handle(channel_input(channel.clone())))
+            .expect_err("The channel id is spoofed");
+        assert_eq!(vec!["channel_id".to_string()], violated_rules(error));
+
+        let saved = await!(channel_repository.find(&channel.id)).expect("Should not fail");
+        assert!(saved.is_none());
+    })
+}
+
+#[test]
+fn reports_the_channel_id_of_a_channel_without_addresses() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let (handler, _) = handler(
+            channel.spec.validators.leader().id.as_ref(),
+            Some(CORE_ADDRESS),
+        );
+
+        let error = await!(handler.handle(channel_input(channel)))
+            .expect_err("The channel id can't be computed");
+        assert_eq!(vec!["channel_id".to_string()], violated_rules(error));
+    })
+}
+
+#[test]
+fn reports_the_channel_id_of_a_channel_without_a_spec_hash() {
+    futures::executor::block_on(async {
+        let mut channel = ethereum_channel();
+        channel.id = channel
+            .compute_id(CORE_ADDRESS, SPEC_HASH)
+            .expect("Computing the id failed");
+        let (handler, _) = handler(
+            channel.spec.validators.leader().id.as_ref(),
+            Some(CORE_ADDRESS),
+        );
+
+        let input = ChannelInput {
+            spec_hash: None,
+            ..channel_input(channel)
+        };
+        let error = await!(handler.handle(input)).expect_err("The specHash is missing");
+        assert_eq!(vec!["channel_id".to_string()], violated_rules(error));
+    })
+}
+
+#[test]
+fn creates_a_channel_without_a_spec_hash_when_the_id_is_not_checked() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let (handler, channel_repository) =
+            handler_with_identity(channel.spec.validators.leader().id.as_ref());
+
+        let input = ChannelInput {
+            spec_hash: None,
+            ..channel_input(channel.clone())
+        };
+        await!(handler.handle(input)).expect("The specHash is only needed for checking the id");
+
+        let saved = await!(channel_repository.find(&channel.id)).expect("Should not fail");
+        assert!(saved.is_some());
+    })
+}
@@ -15,4 +15,7 @@ pub struct ChannelInput {
     #[serde(with = "ts_seconds")]
     pub valid_until: DateTime<Utc>,
     pub spec: ChannelSpec,
+    /// The `0x` prefixed bytes32 hash of the spec, which the creator committed to on-chain
+    #[serde(default)]
+    pub spec_hash: Option<String>,
 }
@@ -1,7 +1,6 @@
 use serde::Serialize;
-use tower_web::Response;
 
-#[derive(Debug, Response)]
+#[derive(Debug, Serialize)]
 pub struct ChannelCreateResponse {
     pub success: bool,
 }
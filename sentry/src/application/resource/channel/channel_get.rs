@@ -0,0 +1,3 @@
+pub(crate) use self::handler::ChannelGetHandler;
+
+mod handler;
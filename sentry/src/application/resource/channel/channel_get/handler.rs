@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use tokio::await;
+
+use domain::{Channel, ChannelId};
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::ChannelRepository;
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+pub struct ChannelGetHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+}
+
+impl ChannelGetHandler {
+    pub fn new(channel_repository: Arc<dyn ChannelRepository>) -> Self {
+        Self { channel_repository }
+    }
+}
+
+impl ChannelGetHandler {
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(&'a self, channel_id: &'a str) -> Result<Channel, ApplicationError> {
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+
+        await!(self.channel_repository.find(&channel_id))?.ok_or(ApplicationError::NotFound)
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use domain::fixtures::*;
+use domain::DomainError;
+
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+
+use super::*;
+
+fn handler(channels: &[Channel]) -> ChannelGetHandler {
+    ChannelGetHandler::new(Arc::new(MemoryChannelRepository::new(Some(channels))))
+}
+
+#[test]
+fn gets_the_channel_by_its_hex_id() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&[get_channel("channel 2", &None, None), channel.clone()]);
+
+        let found =
+            await!(handler.handle(&channel.id.to_string())).expect("Should find the channel");
+
+        assert_eq!(channel.id, found.id);
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let handler = handler(&[get_channel("channel 1", &None, None)]);
+        let channel_id = get_channel_id("channel 2").to_string();
+
+        match await!(handler.handle(&channel_id)) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn invalid_channel_id_is_an_invalid_argument() {
+    futures::executor::block_on(async {
+        let handler = handler(&[]);
+
+        match await!(handler.handle("0xinvalid")) {
+            Err(ApplicationError::Domain(DomainError::InvalidArgument(_))) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+    })
+}
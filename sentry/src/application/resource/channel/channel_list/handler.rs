@@ -1,7 +1,12 @@
+use std::convert::TryFrom;
+
 use chrono::Utc;
 use tokio::await;
 
-use crate::domain::channel::{ChannelListParams, ChannelRepository};
+use domain::BigNum;
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::{ChannelListOrder, ChannelListParams, ChannelRepository};
 
 use super::ChannelListResponse;
 use std::sync::Arc;
@@ -26,16 +31,22 @@ impl ChannelListHandler {
         &'a self,
         page: u64,
         validator: Option<&'a str>,
-    ) -> Result<ChannelListResponse, ()> {
+        deposit_amount_ge: Option<&'a str>,
+        order_by: Option<&'a str>,
+    ) -> Result<ChannelListResponse, ApplicationError> {
+        let deposit_amount_ge = deposit_amount_ge.map(BigNum::try_from).transpose()?;
+        let order = order_by
+            .map(ChannelListOrder::try_from)
+            .transpose()?
+            .unwrap_or_default();
+
         let channel_list_params =
-            ChannelListParams::new(Utc::now(), self.limit_per_page, page, validator)
-                .expect("Params should be generated from valid data.");
-
-        let list_fut = self.channel_repository.list(&channel_list_params);
-        // @TODO: Proper error handling
-        let channels = await!(list_fut).unwrap();
-        let channels_count =
-            await!(self.channel_repository.list_count(&channel_list_params)).unwrap();
+            ChannelListParams::new(Utc::now(), self.limit_per_page, page, validator)?
+                .with_deposit_amount_ge(deposit_amount_ge)
+                .with_order(order);
+
+        let channels = await!(self.channel_repository.list(&channel_list_params))?;
+        let channels_count = await!(self.channel_repository.list_count(&channel_list_params))?;
 
         Ok(ChannelListResponse {
             channels,
@@ -1,9 +1,8 @@
 use serde::Serialize;
-use tower_web::Response;
 
 use domain::Channel;
 
-#[derive(Debug, Response, Serialize)]
+#[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChannelListResponse {
     pub channels: Vec<Channel>,
@@ -0,0 +1,5 @@
+pub(crate) use self::handler::ChannelStatusHandler;
+pub(crate) use self::response::ChannelStatusResponse;
+
+mod handler;
+mod response;
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::await;
+
+use domain::channel::status::{channel_status, LatestMessages};
+use domain::validator::message::{
+    ApproveState, Heartbeat, Message, MessageType, NewState, TYPE_APPROVE, TYPE_HEARTBEAT, TYPE_NEW,
+};
+use domain::{ChannelId, ValidatorId};
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::{
+    SentryState, ValidatorMessageListParams, ValidatorMessageRepository,
+};
+
+use super::ChannelStatusResponse;
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+pub struct ChannelStatusHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+}
+
+impl ChannelStatusHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            validator_message_repository,
+        }
+    }
+}
+
+impl ChannelStatusHandler {
+    /// Computes the status of the channel with the latest `Heartbeat`s of both validators,
+    /// the latest `NewState` of the leader and the latest `ApproveState` of the follower
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+    ) -> Result<ChannelStatusResponse, ApplicationError> {
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+
+        let channel = await!(self.channel_repository.find(&channel_id))?
+            .ok_or(ApplicationError::NotFound)?;
+
+        let leader = &channel.spec.validators.leader().id;
+        let follower = &channel.spec.validators.follower().id;
+
+        let leader_heartbeat = await!(self.latest(&channel_id, leader, TYPE_HEARTBEAT, heartbeat))?;
+        let follower_heartbeat =
+            await!(self.latest(&channel_id, follower, TYPE_HEARTBEAT, heartbeat))?;
+        let new_state = await!(self.latest(&channel_id, leader, TYPE_NEW, new_state))?;
+        let approve_state =
+            await!(self.latest(&channel_id, follower, TYPE_APPROVE, approve_state))?;
+
+        let messages = LatestMessages::default()
+            .with_heartbeats(leader_heartbeat.as_ref(), follower_heartbeat.as_ref())
+            .with_new_state(new_state.as_ref())
+            .with_approve_state(approve_state.as_ref());
+
+        Ok(ChannelStatusResponse {
+            status: channel_status(&channel, &messages, Utc::now()),
+        })
+    }
+
+    /// The latest message of the given type sent by the validator
+    #[allow(clippy::needless_lifetimes)]
+    async fn latest<'a, T>(
+        &'a self,
+        channel_id: &'a ChannelId,
+        from: &'a ValidatorId,
+        message_type: MessageType,
+        extract: fn(Message<SentryState>) -> Option<T>,
+    ) -> Result<Option<T>, ApplicationError> {
+        let message_type = message_type.to_string();
+        let params = ValidatorMessageListParams::new(
+            *channel_id,
+            Some(from.as_ref()),
+            Some(message_type.as_str()),
+            1,
+        )?;
+
+        let messages = await!(self.validator_message_repository.list(&params))?;
+
+        Ok(messages
+            .into_iter()
+            .next()
+            .and_then(|message| extract(message.msg)))
+    }
+}
+
+fn heartbeat(message: Message<SentryState>) -> Option<Heartbeat<SentryState>> {
+    match message {
+        Message::Heartbeat(heartbeat) => Some(heartbeat),
+        _ => None,
+    }
+}
+
+fn new_state(message: Message<SentryState>) -> Option<NewState<SentryState>> {
+    match message {
+        Message::NewState(new_state) => Some(new_state),
+        _ => None,
+    }
+}
+
+fn approve_state(message: Message<SentryState>) -> Option<ApproveState<SentryState>> {
+    match message {
+        Message::ApproveState(approve_state) => Some(approve_state),
+        _ => None,
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use time::Duration;
+
+use domain::channel::status::ChannelStatus;
+use domain::fixtures::*;
+use domain::validator::message::fixtures::get_approve_state;
+use domain::{Channel, ValidatorId};
+
+use crate::domain::validator_message::ValidatorMessage;
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::validator_message::MemoryValidatorMessageRepository;
+
+use super::*;
+
+fn handler(channel: &Channel, messages: &[ValidatorMessage]) -> ChannelStatusHandler {
+    ChannelStatusHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryValidatorMessageRepository::new(Some(messages))),
+    )
+}
+
+fn validator_message(
+    channel: &Channel,
+    from: &ValidatorId,
+    msg: Message<SentryState>,
+) -> ValidatorMessage {
+    ValidatorMessage {
+        channel_id: channel.id,
+        from: from.clone(),
+        received: Utc::now(),
+        msg,
+    }
+}
+
+fn active_channel() -> Channel {
+    let mut channel = get_channel("channel 1", &Some(Utc::now() + Duration::days(30)), None);
+    channel.spec.active_from = Some(Utc::now() - Duration::days(1));
+    channel.spec.withdraw_period_start = Utc::now() + Duration::days(20);
+
+    channel
+}
+
+fn heartbeat() -> Message<SentryState> {
+    Message::Heartbeat(Heartbeat::new("sig".to_string(), "root".to_string()))
+}
+
+#[test]
+fn computes_the_status_of_the_channel() {
+    futures::executor::block_on(async {
+        let mut channel = get_channel("channel 1", &None, None);
+        channel.spec.withdraw_period_start = Utc::now() - Duration::hours(1);
+
+        let handler = handler(&channel, &[]);
+
+        let response = await!(handler.handle(&channel.id.to_string()))
+            .expect("Should compute the channel status");
+
+        assert_eq!(ChannelStatus::Withdraw, response.status);
+    })
+}
+
+#[test]
+fn uses_the_latest_messages_of_the_validators() {
+    futures::executor::block_on(async {
+        let channel = active_channel();
+        let leader = &channel.spec.validators.leader().id;
+        let follower = &channel.spec.validators.follower().id;
+        let channel_id = channel.id.to_string();
+
+        let offline = await!(handler(&channel, &[]).handle(&channel_id))
+            .expect("Should compute the channel status");
+        assert_eq!(ChannelStatus::Offline, offline.status);
+
+        let leader_heartbeat = validator_message(&channel, leader, heartbeat());
+        let only_leader = handler(&channel, &[leader_heartbeat.clone()]);
+        let disconnected =
+            await!(only_leader.handle(&channel_id)).expect("Should compute the channel status");
+        assert_eq!(ChannelStatus::Disconnected, disconnected.status);
+
+        let mut messages = vec![
+            leader_heartbeat,
+            validator_message(&channel, follower, heartbeat()),
+        ];
+        let active = await!(handler(&channel, &messages).handle(&channel_id))
+            .expect("Should compute the channel status");
+        assert_eq!(ChannelStatus::Active, active.status);
+
+        let unhealthy = get_approve_state("root".to_string(), "sig".to_string(), false);
+        messages.push(validator_message(
+            &channel,
+            follower,
+            Message::ApproveState(unhealthy),
+        ));
+        let unhealthy = await!(handler(&channel, &messages).handle(&channel_id))
+            .expect("Should compute the channel status");
+        assert_eq!(ChannelStatus::Unhealthy, unhealthy.status);
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let handler = ChannelStatusHandler::new(
+            Arc::new(MemoryChannelRepository::new(None)),
+            Arc::new(MemoryValidatorMessageRepository::new(None)),
+        );
+        let channel_id = get_channel_id("channel 1").to_string();
+
+        match await!(handler.handle(&channel_id)) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+use domain::channel::status::ChannelStatus;
+
+#[derive(Debug, Serialize)]
+pub struct ChannelStatusResponse {
+    pub status: ChannelStatus,
+}
@@ -0,0 +1,81 @@
+use futures::future::{FutureExt, TryFutureExt};
+use futures_legacy::Future;
+use tokio::await;
+use tower_web::{derive_resource_impl, impl_web, Extract};
+
+use event_aggregate_list::EventAggregateListHandler;
+use event_create::{EventCreateHandler, EventsInput};
+
+use crate::application::middleware::auth::Auth;
+use crate::application::resource::{client_ip, result_response};
+use crate::domain::channel::ChannelRepository;
+use crate::domain::event::EventRepository;
+use crate::domain::event_aggregate::EventAggregateRepository;
+use crate::domain::rate_limit::RateLimiter;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+mod event_aggregate_list;
+mod event_create;
+
+#[derive(Clone)]
+pub struct EventResource {
+    pub channel_repository: Arc<dyn ChannelRepository>,
+    pub event_repository: Arc<dyn EventRepository>,
+    pub event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    pub rate_limiter: Arc<dyn RateLimiter>,
+    /// Only the `X-Forwarded-For` header of these proxies is used for the IP rate limits
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl_web! {
+    #[allow(clippy::needless_lifetimes)]
+    impl EventResource {
+        #[post("/channel/:id/events")]
+        #[content_type("application/json")]
+        async fn create_events(
+            &self,
+            id: String,
+            auth: Auth,
+            peer_addr: SocketAddr,
+            x_forwarded_for: Option<String>,
+            body: EventsInput,
+        ) -> http::Response<String> {
+            let handler = EventCreateHandler::new(
+                self.channel_repository.clone(),
+                self.event_repository.clone(),
+                self.rate_limiter.clone(),
+            );
+            let ip = client_ip(
+                Some(peer_addr.ip()),
+                x_forwarded_for.as_ref().map(String::as_str),
+                &self.trusted_proxies,
+            );
+
+            result_response(await!(handler.handle(&id, &auth, ip, body).boxed().compat()))
+        }
+
+        // `after` is a millisecond timestamp, e.g. the `created` of the last aggregate
+        #[get("/channel/:id/events-aggregates")]
+        #[content_type("application/json")]
+        async fn event_aggregates(
+            &self,
+            id: String,
+            auth: Auth,
+            query_string: EventAggregatesQuery,
+        ) -> http::Response<String> {
+            let handler = EventAggregateListHandler::new(
+                self.channel_repository.clone(),
+                self.event_aggregate_repository.clone(),
+            );
+            let handle = handler.handle(&id, &auth, query_string.after);
+
+            result_response(await!(handle.boxed().compat()))
+        }
+    }
+}
+
+#[derive(Extract)]
+struct EventAggregatesQuery {
+    after: Option<i64>,
+}
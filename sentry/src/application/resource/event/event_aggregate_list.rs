@@ -0,0 +1,5 @@
+pub(crate) use self::handler::EventAggregateListHandler;
+pub(crate) use self::response::EventAggregateListResponse;
+
+mod handler;
+mod response;
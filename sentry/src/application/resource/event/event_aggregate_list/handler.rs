@@ -0,0 +1,76 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use tokio::await;
+
+use domain::{ChannelId, DomainError, ValidatorId};
+
+use crate::application::error::ApplicationError;
+use crate::application::middleware::auth::Auth;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::event_aggregate::EventAggregateRepository;
+
+use super::EventAggregateListResponse;
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+/// The maximum number of aggregates per page
+pub const LIMIT: u32 = 100;
+
+pub struct EventAggregateListHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+}
+
+impl EventAggregateListHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            event_aggregate_repository,
+        }
+    }
+}
+
+impl EventAggregateListHandler {
+    /// Lists the aggregates of the channel created after `after` (a millisecond timestamp),
+    /// the authenticated identity should be a validator of the channel
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+        auth: &'a Auth,
+        after: Option<i64>,
+    ) -> Result<EventAggregateListResponse, ApplicationError> {
+        let session = auth.required_session()?;
+        let validator_id = ValidatorId::try_from(session.uid.as_str())?;
+
+        let after = match after {
+            Some(after) => Some(Utc.timestamp_millis_opt(after).single().ok_or_else(|| {
+                DomainError::InvalidArgument("The after timestamp is out of range".to_string())
+            })?),
+            None => None,
+        };
+
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+        let channel = await!(self.channel_repository.find(&channel_id))?
+            .ok_or(ApplicationError::NotFound)?;
+
+        if channel.spec.validators.find(&validator_id).is_none() {
+            return Err(ApplicationError::Forbidden(
+                "Only the channel validators can list the event aggregates".to_string(),
+            ));
+        }
+
+        let events = await!(self
+            .event_aggregate_repository
+            .list(&channel_id, after, LIMIT))?;
+
+        Ok(EventAggregateListResponse { channel, events })
+    }
+}
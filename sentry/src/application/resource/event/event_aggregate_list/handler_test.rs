@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use time::Duration;
+
+use adapter::Session;
+use domain::fixtures::*;
+use domain::{Channel, EventAggregate};
+
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::event_aggregate::MemoryEventAggregateRepository;
+
+use super::*;
+
+fn handler(channel: &Channel, aggregates: &[EventAggregate]) -> EventAggregateListHandler {
+    EventAggregateListHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryEventAggregateRepository::new(Some(aggregates))),
+    )
+}
+
+fn auth(uid: &str) -> Auth {
+    Auth::Session(Session {
+        uid: uid.to_string(),
+        era: 0,
+    })
+}
+
+fn aggregates(channel: &Channel, now: DateTime<Utc>) -> Vec<EventAggregate> {
+    (1..=3)
+        .map(|minutes| EventAggregate::new(channel.id, now - Duration::minutes(minutes)))
+        .collect()
+}
+
+#[test]
+fn lists_the_aggregates_created_after() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let now = Utc::now();
+        let handler = handler(&channel, &aggregates(&channel, now));
+        let channel_id = channel.id.to_string();
+        let follower = &auth(channel.spec.validators.follower().id.as_ref());
+
+        let all = await!(handler.handle(&channel_id, follower, None))
+            .expect("Should list the aggregates");
+        assert_eq!(channel.id, all.channel.id);
+        assert_eq!(3, all.events.len());
+        assert!(all.events[0].created < all.events[1].created);
+
+        let after = (now - Duration::minutes(2)).timestamp_millis();
+        let next_page = await!(handler.handle(&channel_id, follower, Some(after)))
+            .expect("Should list the aggregates");
+        assert_eq!(1, next_page.events.len());
+
+        match await!(handler.handle(&channel_id, follower, Some(i64::max_value()))) {
+            Err(ApplicationError::Domain(DomainError::InvalidArgument(_))) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn only_the_channel_validators_can_list() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+        let channel_id = channel.id.to_string();
+
+        match await!(handler.handle(&channel_id, &Auth::Anonymous, None)) {
+            Err(ApplicationError::Unauthorized) => {}
+            result => panic!("Expected an Unauthorized error, got {:?}", result),
+        }
+
+        match await!(handler.handle(&channel_id, &auth("outsider"), None)) {
+            Err(ApplicationError::Forbidden(_)) => {}
+            result => panic!("Expected a Forbidden error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+        let channel_id = get_channel_id("channel 2").to_string();
+        let follower = auth(channel.spec.validators.follower().id.as_ref());
+
+        match await!(handler.handle(&channel_id, &follower, None)) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+use domain::{Channel, EventAggregate};
+
+#[derive(Debug, Serialize)]
+pub struct EventAggregateListResponse {
+    pub channel: Channel,
+    /// The oldest first, the next page starts after the `created` of the last one
+    pub events: Vec<EventAggregate>,
+}
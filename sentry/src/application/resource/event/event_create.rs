@@ -0,0 +1,7 @@
+pub(crate) use self::handler::EventCreateHandler;
+pub(crate) use self::input::EventsInput;
+pub(crate) use self::response::EventCreateResponse;
+
+mod handler;
+mod input;
+mod response;
@@ -0,0 +1,149 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::await;
+
+use domain::event_submission::{RateLimit, RateLimitType};
+use domain::{ChannelId, DomainError, Event};
+
+use crate::application::error::ApplicationError;
+use crate::application::middleware::auth::Auth;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::event::{ChannelEvent, EventRepository};
+use crate::domain::rate_limit::RateLimiter;
+
+use super::{EventCreateResponse, EventsInput};
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+/// The IP rate limit key of the requests without a peer address
+const UNKNOWN_IP: &str = "unknown";
+
+pub struct EventCreateHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    event_repository: Arc<dyn EventRepository>,
+    rate_limiter: Arc<dyn RateLimiter>,
+}
+
+impl EventCreateHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        event_repository: Arc<dyn EventRepository>,
+        rate_limiter: Arc<dyn RateLimiter>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            event_repository,
+            rate_limiter,
+        }
+    }
+}
+
+impl EventCreateHandler {
+    /// Stores the events, if all of them can be submitted to the current state of the channel
+    /// and the first `EventSubmission` rule which allows the submitter doesn't limit them.
+    /// The authentication is optional, but some rules allow only authenticated submitters
+    /// and only the channel creator can submit `CLOSE` events.
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+        auth: &'a Auth,
+        ip: Option<IpAddr>,
+        input: EventsInput,
+    ) -> Result<EventCreateResponse, ApplicationError> {
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+        let channel = await!(self.channel_repository.find(&channel_id))?
+            .ok_or(ApplicationError::NotFound)?;
+
+        let uid = auth.session()?.map(|session| session.uid.as_str());
+
+        if input.events.is_empty() {
+            return Err(DomainError::InvalidArgument(
+                "At least one event is required".to_string(),
+            )
+            .into());
+        }
+
+        let created = Utc::now();
+        for event in input.events.iter() {
+            event.validate(&channel, created)?;
+        }
+
+        let is_creator = uid == Some(channel.creator.as_str());
+        if !is_creator && input.events.contains(&Event::Close) {
+            return Err(ApplicationError::Forbidden(
+                "Only the channel creator can close the channel".to_string(),
+            ));
+        }
+
+        let event_submission = &channel.spec.event_submission;
+        let rule = match event_submission.allowed_rule(&channel.creator, uid) {
+            Some(rule) => rule,
+            None => {
+                return Err(ApplicationError::Forbidden(
+                    "Not allowed to submit events to this channel".to_string(),
+                ))
+            }
+        };
+
+        if let Some(rate_limit) = rule.rate_limit {
+            let key = rate_limit_key(&channel_id, &rate_limit, uid, ip, input.events.len())?;
+            let time_frame = rate_limit.duration().ok_or_else(|| {
+                DomainError::InvalidArgument(
+                    "The rate limit timeframe of the channel is out of range".to_string(),
+                )
+            })?;
+
+            if !await!(self.rate_limiter.acquire(key, time_frame))? {
+                return Err(ApplicationError::TooManyRequests(
+                    "Rate limit exceeded, try again later".to_string(),
+                ));
+            }
+        }
+
+        let events = input
+            .events
+            .into_iter()
+            .map(|event| ChannelEvent {
+                channel_id,
+                created,
+                event,
+            })
+            .collect();
+
+        await!(self.event_repository.add(events))?;
+
+        Ok(EventCreateResponse { success: true })
+    }
+}
+
+/// The IP rate limited submissions are allowed to have only one event,
+/// while the uid rate limited ones require an authenticated submitter.
+/// Both are checked before acquiring the rate limit, so invalid submissions don't use it up.
+fn rate_limit_key(
+    channel_id: &ChannelId,
+    rate_limit: &RateLimit,
+    uid: Option<&str>,
+    ip: Option<IpAddr>,
+    events_count: usize,
+) -> Result<String, ApplicationError> {
+    match rate_limit.limit_type {
+        RateLimitType::Ip if events_count != 1 => Err(DomainError::InvalidArgument(
+            "Only one event per request is allowed".to_string(),
+        )
+        .into()),
+        RateLimitType::Ip => match ip {
+            Some(ip) => Ok(format!("{}:ip:{}", channel_id, ip)),
+            None => Ok(format!("{}:ip:{}", channel_id, UNKNOWN_IP)),
+        },
+        RateLimitType::Uid => {
+            let uid = uid.ok_or(ApplicationError::Unauthorized)?;
+
+            Ok(format!("{}:uid:{}", channel_id, uid))
+        }
+    }
+}
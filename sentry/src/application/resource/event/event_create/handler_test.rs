@@ -0,0 +1,213 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use chrono::Utc;
+use time::Duration;
+
+use adapter::Session;
+use domain::event_submission::{RateLimit, RateLimitType, Rule};
+use domain::fixtures::*;
+use domain::{Channel, DomainError, Event};
+
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::event::MemoryEventRepository;
+use crate::infrastructure::persistence::event_aggregate::MemoryEventAggregateRepository;
+use crate::infrastructure::persistence::rate_limit::MemoryRateLimiter;
+
+use super::*;
+
+fn handler(channel: &Channel) -> EventCreateHandler {
+    EventCreateHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryEventRepository::new(
+            None,
+            Arc::new(MemoryEventAggregateRepository::new(None)),
+        )),
+        Arc::new(MemoryRateLimiter::default()),
+    )
+}
+
+fn auth(uid: &str) -> Auth {
+    Auth::Session(Session {
+        uid: uid.to_string(),
+        era: 0,
+    })
+}
+
+fn active_channel() -> Channel {
+    let mut channel = get_channel("channel 1", &Some(Utc::now() + Duration::days(30)), None);
+    channel.creator = "creator".to_string();
+    channel.spec.active_from = Some(Utc::now() - Duration::days(1));
+    channel.spec.withdraw_period_start = Utc::now() + Duration::days(20);
+
+    channel
+}
+
+fn impression() -> Event {
+    Event::Impression {
+        publisher: "publisher".to_string(),
+        ad_unit: None,
+    }
+}
+
+fn input(events: Vec<Event>) -> EventsInput {
+    EventsInput { events }
+}
+
+#[test]
+fn stores_the_events_of_an_active_channel() {
+    futures::executor::block_on(async {
+        let channel = active_channel();
+        let handler = handler(&channel);
+        let channel_id = channel.id.to_string();
+        let events = input(vec![impression()]);
+
+        let response = await!(handler.handle(&channel_id, &Auth::Anonymous, None, events))
+            .expect("The events should be stored");
+
+        assert!(response.success);
+    })
+}
+
+#[test]
+fn accepts_only_close_events_in_the_withdraw_period() {
+    futures::executor::block_on(async {
+        let mut channel = active_channel();
+        channel.spec.withdraw_period_start = Utc::now() - Duration::hours(1);
+        let handler = handler(&channel);
+        let channel_id = channel.id.to_string();
+        let creator = &auth("creator");
+
+        let events = input(vec![Event::Close, impression()]);
+        match await!(handler.handle(&channel_id, creator, None, events)) {
+            Err(ApplicationError::Domain(DomainError::RuleViolation(_))) => {}
+            result => panic!("Expected a RuleViolation error, got {:?}", result),
+        }
+
+        await!(handler.handle(&channel_id, creator, None, input(vec![Event::Close])))
+            .expect("The CLOSE event should be stored");
+    })
+}
+
+#[test]
+fn empty_events_are_invalid() {
+    futures::executor::block_on(async {
+        let channel = active_channel();
+        let handler = handler(&channel);
+        let channel_id = channel.id.to_string();
+
+        match await!(handler.handle(&channel_id, &Auth::Anonymous, None, input(vec![]))) {
+            Err(ApplicationError::Domain(DomainError::InvalidArgument(_))) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let handler = handler(&active_channel());
+        let channel_id = get_channel_id("channel 2").to_string();
+        let anonymous = &Auth::Anonymous;
+
+        match await!(handler.handle(&channel_id, anonymous, None, input(vec![impression()]))) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn by_default_limits_the_submissions_by_ip_except_for_the_creator() {
+    futures::executor::block_on(async {
+        let channel = active_channel();
+        let handler = handler(&channel);
+        let channel_id = channel.id.to_string();
+        let ip = "203.0.113.1".parse::<IpAddr>().ok();
+        let anonymous = &Auth::Anonymous;
+
+        await!(handler.handle(&channel_id, anonymous, ip, input(vec![impression()])))
+            .expect("The first event should be stored");
+
+        match await!(handler.handle(&channel_id, anonymous, ip, input(vec![impression()]))) {
+            Err(ApplicationError::TooManyRequests(_)) => {}
+            result => panic!("Expected a TooManyRequests error, got {:?}", result),
+        }
+
+        let other_ip = "203.0.113.2".parse::<IpAddr>().ok();
+        let events = input(vec![impression(), impression()]);
+        match await!(handler.handle(&channel_id, anonymous, other_ip, events)) {
+            Err(ApplicationError::Domain(DomainError::InvalidArgument(_))) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+
+        await!(handler.handle(&channel_id, anonymous, other_ip, input(vec![impression()])))
+            .expect("The invalid submission should not use up the rate limit");
+
+        for _ in 0..2 {
+            let events = input(vec![impression(), impression()]);
+            await!(handler.handle(&channel_id, &auth("creator"), ip, events))
+                .expect("The creator should not be rate limited");
+        }
+    })
+}
+
+#[test]
+fn applies_the_first_rule_which_allows_the_submitter() {
+    futures::executor::block_on(async {
+        let mut channel = active_channel();
+        channel.spec.event_submission.allow = vec![Rule {
+            uids: None,
+            rate_limit: Some(RateLimit {
+                limit_type: RateLimitType::Uid,
+                time_frame: 60_000,
+            }),
+        }];
+        let uid_limited = handler(&channel);
+        let channel_id = channel.id.to_string();
+        let publisher = &auth("publisher");
+        let anonymous = &Auth::Anonymous;
+
+        match await!(uid_limited.handle(&channel_id, anonymous, None, input(vec![impression()]))) {
+            Err(ApplicationError::Unauthorized) => {}
+            result => panic!("Expected an Unauthorized error, got {:?}", result),
+        }
+
+        await!(uid_limited.handle(&channel_id, publisher, None, input(vec![impression()])))
+            .expect("The first event should be stored");
+        match await!(uid_limited.handle(&channel_id, publisher, None, input(vec![impression()]))) {
+            Err(ApplicationError::TooManyRequests(_)) => {}
+            result => panic!("Expected a TooManyRequests error, got {:?}", result),
+        }
+
+        channel.spec.event_submission.allow = vec![Rule {
+            uids: Some(vec!["creator".to_string()]),
+            rate_limit: None,
+        }];
+        let only_creator = handler(&channel);
+        match await!(only_creator.handle(&channel_id, publisher, None, input(vec![impression()]))) {
+            Err(ApplicationError::Forbidden(_)) => {}
+            result => panic!("Expected a Forbidden error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn only_the_creator_can_close_the_channel() {
+    futures::executor::block_on(async {
+        let channel = active_channel();
+        let handler = handler(&channel);
+        let channel_id = channel.id.to_string();
+
+        for submitter in &[Auth::Anonymous, auth("publisher")] {
+            match await!(handler.handle(&channel_id, submitter, None, input(vec![Event::Close]))) {
+                Err(ApplicationError::Forbidden(_)) => {}
+                result => panic!("Expected a Forbidden error, got {:?}", result),
+            }
+        }
+
+        let creator = &auth("creator");
+        await!(handler.handle(&channel_id, creator, None, input(vec![Event::Close])))
+            .expect("The CLOSE event of the creator should be stored");
+    })
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+use tower_web::Extract;
+
+use domain::Event;
+
+#[derive(Extract, Deserialize, Debug)]
+pub struct EventsInput {
+    pub events: Vec<Event>,
+}
@@ -0,0 +1,126 @@
+use futures::future::{FutureExt, TryFutureExt};
+use futures_legacy::Future;
+use tokio::await;
+use tower_web::{derive_resource_impl, impl_web, Extract};
+
+use balance_proof::BalanceProofHandler;
+use last_approved::LastApprovedHandler;
+use validator_message_create::{ValidatorMessageCreateHandler, ValidatorMessagesInput};
+use validator_message_list::ValidatorMessageListHandler;
+
+use crate::application::middleware::auth::Auth;
+use crate::application::resource::result_response;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::ValidatorMessageRepository;
+use std::sync::Arc;
+
+mod balance_proof;
+mod last_approved;
+mod validator_message_create;
+mod validator_message_list;
+
+#[derive(Clone)]
+pub struct ValidatorMessageResource {
+    pub channel_repository: Arc<dyn ChannelRepository>,
+    pub validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+}
+
+impl_web! {
+    #[allow(clippy::needless_lifetimes)]
+    impl ValidatorMessageResource {
+        #[post("/channel/:id/validator-messages")]
+        #[content_type("application/json")]
+        async fn create_validator_messages(
+            &self,
+            id: String,
+            auth: Auth,
+            body: ValidatorMessagesInput,
+        ) -> http::Response<String> {
+            let handler = ValidatorMessageCreateHandler::new(
+                self.channel_repository.clone(),
+                self.validator_message_repository.clone(),
+            );
+
+            result_response(await!(handler.handle(&id, &auth, body).boxed().compat()))
+        }
+
+        #[get("/channel/:id/validator-messages")]
+        #[content_type("application/json")]
+        async fn validator_messages(
+            &self,
+            id: String,
+            query_string: ValidatorMessagesQuery,
+        ) -> http::Response<String> {
+            let handler = self.list_handler();
+            let handle = handler.handle(&id, None, None, query_string.limit);
+
+            result_response(await!(handle.boxed().compat()))
+        }
+
+        #[get("/channel/:id/validator-messages/:validator")]
+        #[content_type("application/json")]
+        async fn validator_messages_from(
+            &self,
+            id: String,
+            validator: String,
+            query_string: ValidatorMessagesQuery,
+        ) -> http::Response<String> {
+            let handler = self.list_handler();
+            let handle = handler.handle(&id, Some(&validator), None, query_string.limit);
+
+            result_response(await!(handle.boxed().compat()))
+        }
+
+        // the `types` are separated by `+`, e.g. `/new+approve`
+        #[get("/channel/:id/validator-messages/:validator/:types")]
+        #[content_type("application/json")]
+        async fn validator_messages_from_by_types(
+            &self,
+            id: String,
+            validator: String,
+            types: String,
+            query_string: ValidatorMessagesQuery,
+        ) -> http::Response<String> {
+            let handler = self.list_handler();
+            let handle = handler.handle(&id, Some(&validator), Some(&types), query_string.limit);
+
+            result_response(await!(handle.boxed().compat()))
+        }
+
+        #[get("/channel/:id/last-approved")]
+        #[content_type("application/json")]
+        async fn last_approved(&self, id: String) -> http::Response<String> {
+            let handler = LastApprovedHandler::new(
+                self.channel_repository.clone(),
+                self.validator_message_repository.clone(),
+            );
+
+            result_response(await!(handler.handle(&id).boxed().compat()))
+        }
+
+        #[get("/channel/:id/proof/:earner")]
+        #[content_type("application/json")]
+        async fn balance_proof(&self, id: String, earner: String) -> http::Response<String> {
+            let handler = BalanceProofHandler::new(
+                self.channel_repository.clone(),
+                self.validator_message_repository.clone(),
+            );
+
+            result_response(await!(handler.handle(&id, &earner).boxed().compat()))
+        }
+    }
+}
+
+impl ValidatorMessageResource {
+    fn list_handler(&self) -> ValidatorMessageListHandler {
+        ValidatorMessageListHandler::new(
+            self.channel_repository.clone(),
+            self.validator_message_repository.clone(),
+        )
+    }
+}
+
+#[derive(Extract)]
+struct ValidatorMessagesQuery {
+    limit: Option<u32>,
+}
@@ -0,0 +1,5 @@
+pub(crate) use self::handler::BalanceProofHandler;
+pub(crate) use self::response::BalanceProofResponse;
+
+mod handler;
+mod response;
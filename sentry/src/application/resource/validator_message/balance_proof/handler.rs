@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use tokio::await;
+
+use domain::util::normalize_address;
+use domain::ChannelId;
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::ValidatorMessageRepository;
+
+use super::BalanceProofResponse;
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+pub struct BalanceProofHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+}
+
+impl BalanceProofHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            validator_message_repository,
+        }
+    }
+}
+
+impl BalanceProofHandler {
+    /// Proves the balance of the earner in the last approved state, which the earner
+    /// can withdraw with. It's `NotFound` until the earner has a balance in an approved state.
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+        earner: &'a str,
+    ) -> Result<BalanceProofResponse, ApplicationError> {
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+
+        let channel = await!(self.channel_repository.find(&channel_id))?
+            .ok_or(ApplicationError::NotFound)?;
+        let validators = &channel.spec.validators;
+
+        let last_approved = await!(self.validator_message_repository.last_approved(
+            &channel_id,
+            &validators.leader().id,
+            &validators.follower().id,
+        ))?
+        .ok_or(ApplicationError::NotFound)?;
+
+        // the balances are of the lowercase addresses, while the wallets use the checksummed ones
+        let earner = normalize_address(earner);
+        let balances = &last_approved.new_state.balances;
+        let balance = balances
+            .get(&earner)
+            .cloned()
+            .ok_or(ApplicationError::NotFound)?;
+        let proof = balances
+            .proof(&earner)?
+            .ok_or(ApplicationError::NotFound)?
+            .iter()
+            .map(|item| format!("0x{}", hex::encode(item)))
+            .collect();
+
+        Ok(BalanceProofResponse {
+            balance,
+            proof,
+            state_root: last_approved.new_state.state_root,
+            signatures: vec![
+                last_approved.new_state.signature,
+                last_approved.approve_state.signature,
+            ],
+        })
+    }
+}
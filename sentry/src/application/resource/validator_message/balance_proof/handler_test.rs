@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use domain::balances_map::verify_balance_proof;
+use domain::fixtures::*;
+use domain::validator::message::fixtures::{get_approve_state, get_new_state};
+use domain::validator::message::Message;
+use domain::{BigNum, Channel, ValidatorId};
+
+use crate::domain::validator_message::{SentryState, ValidatorMessage};
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::validator_message::MemoryValidatorMessageRepository;
+
+use super::*;
+
+fn handler(channel: &Channel, messages: &[ValidatorMessage]) -> BalanceProofHandler {
+    BalanceProofHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryValidatorMessageRepository::new(Some(messages))),
+    )
+}
+
+fn validator_message(
+    channel: &Channel,
+    from: &ValidatorId,
+    msg: Message<SentryState>,
+) -> ValidatorMessage {
+    ValidatorMessage {
+        channel_id: channel.id,
+        from: from.clone(),
+        received: Utc::now(),
+        msg,
+    }
+}
+
+/// The leader's `NewState` of the balances approved by the follower
+fn approved_messages(channel: &Channel, balances: &[(&str, u64)]) -> Vec<ValidatorMessage> {
+    let leader = &channel.spec.validators.leader().id;
+    let follower = &channel.spec.validators.follower().id;
+
+    let balances = get_balances_map(balances);
+    let new_state = get_new_state("root".to_string(), "leader sig".to_string(), balances);
+    let approve_state = get_approve_state("root".to_string(), "follower sig".to_string(), true);
+
+    vec![
+        validator_message(channel, leader, Message::NewState(new_state)),
+        validator_message(channel, follower, Message::ApproveState(approve_state)),
+    ]
+}
+
+#[test]
+fn proves_the_balance_of_the_earner_in_the_last_approved_state() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let balances = [("publisher", 100), ("another publisher", 200)];
+        let handler = handler(&channel, &approved_messages(&channel, &balances));
+
+        let response = await!(handler.handle(&channel.id.to_string(), "publisher"))
+            .expect("Should prove the balance");
+
+        assert_eq!(BigNum::from(100), response.balance);
+        assert_eq!("root", response.state_root);
+        assert_eq!(vec!["leader sig", "follower sig"], response.signatures);
+
+        let proof: Vec<[u8; 32]> = response
+            .proof
+            .iter()
+            .map(|item| {
+                let mut bytes = [0; 32];
+                bytes.copy_from_slice(&hex::decode(&item[2..]).expect("Should be hex"));
+
+                bytes
+            })
+            .collect();
+        let balance_root = get_balances_map(&balances)
+            .merkle_tree()
+            .expect("Building the Merkle tree failed")
+            .root();
+        let is_valid = verify_balance_proof("publisher", &response.balance, &proof, &balance_root)
+            .expect("Verifying the proof failed");
+        assert!(is_valid);
+    })
+}
+
+#[test]
+fn proves_the_balance_of_a_checksummed_earner() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let earner = "0xce07cbb7e054514d590a0262c93070d838bfba2e";
+        let balances = [(earner, 100), ("another publisher", 200)];
+        let handler = handler(&channel, &approved_messages(&channel, &balances));
+
+        let checksummed = "0xCe07CbB7e054514D590a0262C93070D838bFBA2e";
+        let response = await!(handler.handle(&channel.id.to_string(), checksummed))
+            .expect("Should prove the balance of the lowercase address");
+
+        assert_eq!(BigNum::from(100), response.balance);
+        assert!(!response.proof.is_empty());
+    })
+}
+
+#[test]
+fn earner_without_balance_is_not_found() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let messages = approved_messages(&channel, &[("publisher", 100)]);
+        let handler = handler(&channel, &messages);
+
+        match await!(handler.handle(&channel.id.to_string(), "another publisher")) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn without_approved_state_it_is_not_found() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+
+        match await!(handler.handle(&channel.id.to_string(), "publisher")) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+use domain::BigNum;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceProofResponse {
+    pub balance: BigNum,
+    /// The `0x` prefixed hashes of the Merkle proof, from the balance leaf up to the balance root
+    pub proof: Vec<String>,
+    pub state_root: String,
+    /// The signatures of the `state_root` by the leader and the follower, in this order
+    pub signatures: Vec<String>,
+}
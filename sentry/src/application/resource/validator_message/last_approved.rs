@@ -0,0 +1,5 @@
+pub(crate) use self::handler::LastApprovedHandler;
+pub(crate) use self::response::LastApprovedResponse;
+
+mod handler;
+mod response;
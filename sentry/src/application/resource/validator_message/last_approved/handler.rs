@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use tokio::await;
+
+use domain::ChannelId;
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::ValidatorMessageRepository;
+
+use super::LastApprovedResponse;
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+pub struct LastApprovedHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+}
+
+impl LastApprovedHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            validator_message_repository,
+        }
+    }
+}
+
+impl LastApprovedHandler {
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+    ) -> Result<LastApprovedResponse, ApplicationError> {
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+
+        let channel = await!(self.channel_repository.find(&channel_id))?
+            .ok_or(ApplicationError::NotFound)?;
+        let validators = &channel.spec.validators;
+
+        let last_approved = await!(self.validator_message_repository.last_approved(
+            &channel_id,
+            &validators.leader().id,
+            &validators.follower().id,
+        ))?;
+
+        Ok(LastApprovedResponse { last_approved })
+    }
+}
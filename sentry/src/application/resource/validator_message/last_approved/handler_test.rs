@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use domain::fixtures::*;
+use domain::validator::message::fixtures::{get_approve_state, get_new_state};
+use domain::validator::message::Message;
+use domain::{BalancesMap, Channel, ValidatorId};
+
+use crate::domain::validator_message::{SentryState, ValidatorMessage};
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::validator_message::MemoryValidatorMessageRepository;
+
+use super::*;
+
+fn handler(channel: &Channel, messages: &[ValidatorMessage]) -> LastApprovedHandler {
+    LastApprovedHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryValidatorMessageRepository::new(Some(messages))),
+    )
+}
+
+fn validator_message(
+    channel: &Channel,
+    from: &ValidatorId,
+    msg: Message<SentryState>,
+) -> ValidatorMessage {
+    ValidatorMessage {
+        channel_id: channel.id,
+        from: from.clone(),
+        received: Utc::now(),
+        msg,
+    }
+}
+
+#[test]
+fn returns_the_new_state_with_its_approve_state() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let leader = &channel.spec.validators.leader().id;
+        let follower = &channel.spec.validators.follower().id;
+
+        let balances = BalancesMap::default();
+        let new_state = get_new_state("root".to_string(), "sig".to_string(), balances);
+        let approve_state = get_approve_state("root".to_string(), "sig".to_string(), true);
+        let messages = [
+            validator_message(&channel, leader, Message::NewState(new_state)),
+            validator_message(&channel, follower, Message::ApproveState(approve_state)),
+        ];
+
+        let handler = handler(&channel, &messages);
+        let response = await!(handler.handle(&channel.id.to_string()))
+            .expect("Should find the last approved");
+
+        let last_approved = response.last_approved.expect("There should be a last approved");
+        assert_eq!("root", last_approved.new_state.state_root);
+        assert_eq!("root", last_approved.approve_state.state_root);
+    })
+}
+
+#[test]
+fn without_approve_state_the_last_approved_is_null() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+
+        let response = await!(handler.handle(&channel.id.to_string()))
+            .expect("Should find the last approved");
+
+        assert!(response.last_approved.is_none());
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+        let channel_id = get_channel_id("channel 2").to_string();
+
+        match await!(handler.handle(&channel_id)) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+use crate::domain::validator_message::LastApproved;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastApprovedResponse {
+    /// `null` if the follower hasn't approved any `NewState` yet
+    pub last_approved: Option<LastApproved>,
+}
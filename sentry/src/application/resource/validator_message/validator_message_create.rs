@@ -0,0 +1,7 @@
+pub(crate) use self::handler::ValidatorMessageCreateHandler;
+pub(crate) use self::input::ValidatorMessagesInput;
+pub(crate) use self::response::ValidatorMessageCreateResponse;
+
+mod handler;
+mod input;
+mod response;
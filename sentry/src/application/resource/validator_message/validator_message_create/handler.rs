@@ -0,0 +1,82 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio::await;
+
+use domain::{ChannelId, DomainError, ValidatorId};
+
+use crate::application::error::ApplicationError;
+use crate::application::middleware::auth::Auth;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::{ValidatorMessage, ValidatorMessageRepository};
+
+use super::{ValidatorMessageCreateResponse, ValidatorMessagesInput};
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+pub struct ValidatorMessageCreateHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+}
+
+impl ValidatorMessageCreateHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            validator_message_repository,
+        }
+    }
+}
+
+impl ValidatorMessageCreateHandler {
+    /// Stores the messages of the authenticated validator, who should be a validator of the channel
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+        auth: &'a Auth,
+        input: ValidatorMessagesInput,
+    ) -> Result<ValidatorMessageCreateResponse, ApplicationError> {
+        let session = auth.required_session()?;
+        let from = ValidatorId::try_from(session.uid.as_str())?;
+
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+        let channel = await!(self.channel_repository.find(&channel_id))?
+            .ok_or(ApplicationError::NotFound)?;
+
+        if channel.spec.validators.find(&from).is_none() {
+            return Err(ApplicationError::Forbidden(
+                "Only the channel validators can submit messages".to_string(),
+            ));
+        }
+
+        if input.messages.is_empty() {
+            return Err(DomainError::InvalidArgument(
+                "At least one message is required".to_string(),
+            )
+            .into());
+        }
+
+        let received = Utc::now();
+        let messages = input
+            .messages
+            .into_iter()
+            .map(|msg| ValidatorMessage {
+                channel_id,
+                from: from.clone(),
+                received,
+                msg,
+            })
+            .collect();
+
+        await!(self.validator_message_repository.add(messages))?;
+
+        Ok(ValidatorMessageCreateResponse { success: true })
+    }
+}
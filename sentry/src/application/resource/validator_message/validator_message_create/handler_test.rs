@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use adapter::{AdapterError, Session};
+use domain::fixtures::*;
+use domain::validator::message::fixtures::get_reject_state;
+use domain::validator::message::Message;
+use domain::Channel;
+
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::validator_message::MemoryValidatorMessageRepository;
+
+use super::*;
+
+fn handler(channel: &Channel) -> ValidatorMessageCreateHandler {
+    ValidatorMessageCreateHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryValidatorMessageRepository::new(None)),
+    )
+}
+
+fn auth(uid: &str) -> Auth {
+    Auth::Session(Session {
+        uid: uid.to_string(),
+        era: 0,
+    })
+}
+
+fn input() -> ValidatorMessagesInput {
+    ValidatorMessagesInput {
+        messages: vec![Message::RejectState(get_reject_state(
+            "root".to_string(),
+            None,
+        ))],
+    }
+}
+
+#[test]
+fn stores_the_messages_of_a_channel_validator() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel);
+
+        let channel_id = channel.id.to_string();
+        let leader = auth(channel.spec.validators.leader().id.as_ref());
+
+        let response = await!(handler.handle(&channel_id, &leader, input()))
+            .expect("The messages should be stored");
+
+        assert!(response.success);
+    })
+}
+
+#[test]
+fn requires_authentication() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel);
+        let channel_id = channel.id.to_string();
+
+        match await!(handler.handle(&channel_id, &Auth::Anonymous, input())) {
+            Err(ApplicationError::Unauthorized) => {}
+            result => panic!("Expected an Unauthorized error, got {:?}", result),
+        }
+
+        let failed = Auth::Failed(AdapterError::Authentication("Token not found".to_string()));
+        match await!(handler.handle(&channel_id, &failed, input())) {
+            Err(ApplicationError::Adapter(AdapterError::Authentication(_))) => {}
+            result => panic!("Expected an Authentication error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn rejects_identities_which_are_not_channel_validators() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel);
+
+        match await!(handler.handle(&channel.id.to_string(), &auth("outsider"), input())) {
+            Err(ApplicationError::Forbidden(_)) => {}
+            result => panic!("Expected a Forbidden error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel);
+        let channel_id = get_channel_id("channel 2").to_string();
+        let leader = auth(channel.spec.validators.leader().id.as_ref());
+
+        match await!(handler.handle(&channel_id, &leader, input())) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
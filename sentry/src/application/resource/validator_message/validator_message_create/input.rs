@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use tower_web::Extract;
+
+use domain::validator::message::Message;
+
+use crate::domain::validator_message::SentryState;
+
+#[derive(Extract, Deserialize, Debug)]
+pub struct ValidatorMessagesInput {
+    pub messages: Vec<Message<SentryState>>,
+}
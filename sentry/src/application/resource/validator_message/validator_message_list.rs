@@ -0,0 +1,5 @@
+pub(crate) use self::handler::ValidatorMessageListHandler;
+pub(crate) use self::response::ValidatorMessageListResponse;
+
+mod handler;
+mod response;
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use tokio::await;
+
+use domain::ChannelId;
+
+use crate::application::error::ApplicationError;
+use crate::domain::channel::ChannelRepository;
+use crate::domain::validator_message::{ValidatorMessageListParams, ValidatorMessageRepository};
+
+use super::ValidatorMessageListResponse;
+
+#[cfg(test)]
+#[path = "./handler_test.rs"]
+mod handler_test;
+
+/// The limit used when the request doesn't specify one
+pub const DEFAULT_LIMIT: u32 = 10;
+/// Larger limits are lowered to this one
+pub const MAX_LIMIT: u32 = 100;
+
+pub struct ValidatorMessageListHandler {
+    channel_repository: Arc<dyn ChannelRepository>,
+    validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+}
+
+impl ValidatorMessageListHandler {
+    pub fn new(
+        channel_repository: Arc<dyn ChannelRepository>,
+        validator_message_repository: Arc<dyn ValidatorMessageRepository>,
+    ) -> Self {
+        Self {
+            channel_repository,
+            validator_message_repository,
+        }
+    }
+}
+
+impl ValidatorMessageListHandler {
+    /// Lists the messages of the channel, the newest first
+    #[allow(clippy::needless_lifetimes)]
+    pub async fn handle<'a>(
+        &'a self,
+        channel_id: &'a str,
+        from: Option<&'a str>,
+        types: Option<&'a str>,
+        limit: Option<u32>,
+    ) -> Result<ValidatorMessageListResponse, ApplicationError> {
+        let channel_id = ChannelId::try_from_hex(channel_id)?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+        let params = ValidatorMessageListParams::new(channel_id, from, types, limit)?;
+
+        await!(self.channel_repository.find(&channel_id))?.ok_or(ApplicationError::NotFound)?;
+
+        let validator_messages = await!(self.validator_message_repository.list(&params))?;
+
+        Ok(ValidatorMessageListResponse { validator_messages })
+    }
+}
@@ -0,0 +1,109 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use domain::fixtures::*;
+use domain::validator::message::fixtures::{get_heartbeat, get_reject_state};
+use domain::validator::message::{Message, TYPE_HEARTBEAT};
+use domain::{Channel, DomainError, ValidatorId};
+
+use crate::domain::validator_message::ValidatorMessage;
+use crate::infrastructure::persistence::channel::MemoryChannelRepository;
+use crate::infrastructure::persistence::validator_message::MemoryValidatorMessageRepository;
+
+use super::*;
+
+fn handler(channel: &Channel, messages: &[ValidatorMessage]) -> ValidatorMessageListHandler {
+    ValidatorMessageListHandler::new(
+        Arc::new(MemoryChannelRepository::new(Some(&[channel.clone()]))),
+        Arc::new(MemoryValidatorMessageRepository::new(Some(messages))),
+    )
+}
+
+fn messages(channel: &Channel) -> Vec<ValidatorMessage> {
+    let leader = channel.spec.validators.leader().id.clone();
+    let follower = channel.spec.validators.follower().id.clone();
+    let heartbeat = Message::Heartbeat(get_heartbeat("root".to_string(), "sig".to_string()));
+
+    vec![
+        (
+            leader.clone(),
+            Message::RejectState(get_reject_state("root".to_string(), None)),
+        ),
+        (leader, heartbeat.clone()),
+        (follower, heartbeat),
+    ]
+    .into_iter()
+    .map(|(from, msg)| ValidatorMessage {
+        channel_id: channel.id,
+        from,
+        received: Utc::now(),
+        msg,
+    })
+    .collect()
+}
+
+#[test]
+fn lists_the_messages_filtered_by_validator_and_types() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &messages(&channel));
+        let channel_id = channel.id.to_string();
+        let leader = channel.spec.validators.leader().id.to_string();
+
+        let all = await!(handler.handle(&channel_id, None, None, None))
+            .expect("Should list the messages");
+        assert_eq!(3, all.validator_messages.len());
+
+        let from_leader = await!(handler.handle(&channel_id, Some(&leader), None, None))
+            .expect("Should list the messages");
+        assert_eq!(2, from_leader.validator_messages.len());
+
+        let heartbeats = await!(handler.handle(&channel_id, Some(&leader), Some("heartbeat"), None))
+            .expect("Should list the messages");
+        assert_eq!(1, heartbeats.validator_messages.len());
+        assert_eq!(
+            ValidatorId::try_from(leader.as_str()).unwrap(),
+            heartbeats.validator_messages[0].from
+        );
+        assert!(heartbeats.validator_messages[0].msg.is_type(&TYPE_HEARTBEAT));
+
+        let limited = await!(handler.handle(&channel_id, None, None, Some(1)))
+            .expect("Should list the messages");
+        assert_eq!(1, limited.validator_messages.len());
+    })
+}
+
+#[test]
+fn invalid_types_and_limit_are_invalid_arguments() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+        let channel_id = channel.id.to_string();
+
+        match await!(handler.handle(&channel_id, None, Some("heartbeat+unknown"), None)) {
+            Err(ApplicationError::Domain(DomainError::InvalidArgument(_))) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+
+        match await!(handler.handle(&channel_id, None, None, Some(0))) {
+            Err(ApplicationError::Domain(DomainError::InvalidArgument(_))) => {}
+            result => panic!("Expected an InvalidArgument error, got {:?}", result),
+        }
+    })
+}
+
+#[test]
+fn missing_channel_is_not_found() {
+    futures::executor::block_on(async {
+        let channel = get_channel("channel 1", &None, None);
+        let handler = handler(&channel, &[]);
+        let channel_id = get_channel_id("channel 2").to_string();
+
+        match await!(handler.handle(&channel_id, None, None, None)) {
+            Err(ApplicationError::NotFound) => {}
+            result => panic!("Expected a NotFound error, got {:?}", result),
+        }
+    })
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+use crate::domain::validator_message::ValidatorMessage;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorMessageListResponse {
+    pub validator_messages: Vec<ValidatorMessage>,
+}
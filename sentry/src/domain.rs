@@ -1 +1,6 @@
+pub mod adapter;
 pub mod channel;
+pub mod event;
+pub mod event_aggregate;
+pub mod rate_limit;
+pub mod validator_message;
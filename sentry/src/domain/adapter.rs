@@ -0,0 +1,35 @@
+use adapter::{Adapter, AdapterFuture, SanityError, Session};
+use domain::Channel;
+
+/// Object-safe view of the `Adapter` used by Sentry,
+/// so it can be shared as `Arc<dyn SentryAdapter>`. It's implemented for every `Adapter`.
+pub trait SentryAdapter: Send + Sync {
+    fn identity(&self) -> &str;
+
+    /// Runs the adapter `SanityChecker::check` with the adapter `Config`
+    fn sanity_check(&self, channel: &Channel) -> Result<(), SanityError>;
+
+    /// Runs `Adapter::validate_channel`
+    fn validate_channel(&self, channel: &Channel) -> AdapterFuture<bool>;
+
+    /// Runs `Adapter::verify_auth`
+    fn verify_auth(&self, token: &str) -> AdapterFuture<Session>;
+}
+
+impl<A: Adapter + Send + Sync> SentryAdapter for A {
+    fn identity(&self) -> &str {
+        &self.config().identity
+    }
+
+    fn sanity_check(&self, channel: &Channel) -> Result<(), SanityError> {
+        A::check(self.config(), channel)
+    }
+
+    fn validate_channel(&self, channel: &Channel) -> AdapterFuture<bool> {
+        Adapter::validate_channel(self, channel)
+    }
+
+    fn verify_auth(&self, token: &str) -> AdapterFuture<Session> {
+        Adapter::verify_auth(self, token)
+    }
+}
@@ -1,18 +1,54 @@
 use chrono::{DateTime, Utc};
 
-use domain::{Channel, RepositoryFuture, ValidatorId};
+use domain::{BigNum, Channel, RepositoryFuture, ValidatorId};
 use domain::{ChannelId, DomainError};
 use std::convert::TryFrom;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelListOrder {
+    /// The order in which the channels were added
+    Insertion,
+    DepositAmountAsc,
+    DepositAmountDesc,
+}
+
+impl Default for ChannelListOrder {
+    fn default() -> Self {
+        ChannelListOrder::Insertion
+    }
+}
+
+impl TryFrom<&str> for ChannelListOrder {
+    type Error = DomainError;
+
+    /// Parses the `order_by` value, a leading `-` means descending order
+    fn try_from(order_by: &str) -> Result<Self, Self::Error> {
+        match order_by {
+            "deposit_amount" => Ok(ChannelListOrder::DepositAmountAsc),
+            "-deposit_amount" => Ok(ChannelListOrder::DepositAmountDesc),
+            _ => Err(DomainError::InvalidArgument(format!(
+                "Channels can't be ordered by '{}'",
+                order_by
+            ))),
+        }
+    }
+}
+
 pub struct ChannelListParams {
     /// page to show, should be >= 1
     pub page: u64,
     /// channels limit per page, should be >= 1
     pub limit: u32,
+    /// the number of channels to skip, `(page - 1) * limit`
+    pub offset: i64,
     /// filters `valid_until` to be `>= valid_until_ge`
     pub valid_until_ge: DateTime<Utc>,
     /// filters the channels containing a specific validator if provided
     pub validator: Option<ValidatorId>,
+    /// filters `deposit_amount` to be `>= deposit_amount_ge` if provided
+    pub deposit_amount_ge: Option<BigNum>,
+    /// the order of the channels, channels with equal order keep their insertion order
+    pub order: ChannelListOrder,
     /// Ensures that this struct can only be created by calling `new()`
     _secret: (),
 }
@@ -36,6 +72,11 @@ impl ChannelListParams {
             ));
         }
 
+        let offset = (page - 1)
+            .checked_mul(u64::from(limit))
+            .and_then(|offset| i64::try_from(offset).ok())
+            .ok_or_else(|| DomainError::InvalidArgument("Page is out of range".to_string()))?;
+
         let validator = validator
             .and_then(|s| if s.is_empty() { None } else { Some(s) })
             .map(ValidatorId::try_from)
@@ -45,10 +86,23 @@ impl ChannelListParams {
             valid_until_ge,
             page,
             limit,
+            offset,
             validator,
+            deposit_amount_ge: None,
+            order: ChannelListOrder::default(),
             _secret: (),
         })
     }
+
+    pub fn with_deposit_amount_ge(mut self, deposit_amount_ge: Option<BigNum>) -> Self {
+        self.deposit_amount_ge = deposit_amount_ge;
+        self
+    }
+
+    pub fn with_order(mut self, order: ChannelListOrder) -> Self {
+        self.order = order;
+        self
+    }
 }
 
 pub trait ChannelRepository: Send + Sync {
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use domain::{ChannelId, Event, EventAggregate, RepositoryFuture};
+
+/// An Event submitted to a Channel, stored until it gets aggregated
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelEvent {
+    pub channel_id: ChannelId,
+    pub created: DateTime<Utc>,
+    pub event: Event,
+}
+
+/// Folds the taken events, in the order they were added, into the aggregates to add
+pub type EventFold =
+    Box<dyn FnOnce(Vec<ChannelEvent>) -> RepositoryFuture<Vec<EventAggregate>> + Send>;
+
+pub trait EventRepository: Send + Sync {
+    /// Adds all the events or none of them
+    fn add(&self, events: Vec<ChannelEvent>) -> RepositoryFuture<()>;
+
+    /// Removes the events created until (including) `until` and adds their aggregates by `fold`
+    /// in one transaction, so every event gets aggregated exactly once:
+    /// if folding or adding the aggregates fails, the events are kept for the next call.
+    /// `fold` isn't called without events. Returns the number of the added aggregates.
+    fn aggregate(&self, until: DateTime<Utc>, fold: EventFold) -> RepositoryFuture<usize>;
+}
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+
+use domain::{ChannelId, EventAggregate, RepositoryFuture};
+
+pub trait EventAggregateRepository: Send + Sync {
+    /// Lists the aggregates of the Channel created after `after`, the oldest first,
+    /// so the next page starts after the `created` of the last one
+    fn list(
+        &self,
+        channel_id: &ChannelId,
+        after: Option<DateTime<Utc>>,
+        limit: u32,
+    ) -> RepositoryFuture<Vec<EventAggregate>>;
+
+    /// Adds all the aggregates or none of them
+    fn add(&self, aggregates: Vec<EventAggregate>) -> RepositoryFuture<()>;
+}
@@ -0,0 +1,9 @@
+use chrono::Duration;
+
+use domain::RepositoryFuture;
+
+pub trait RateLimiter: Send + Sync {
+    /// Acquires the `key` for the `time_frame`.
+    /// Returns `false` if the `key` is already acquired, i.e. the submission should be limited.
+    fn acquire(&self, key: String, time_frame: Duration) -> RepositoryFuture<bool>;
+}
@@ -0,0 +1,102 @@
+use std::convert::TryFrom;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use domain::validator::message::{ApproveState, Message, MessageType, NewState, State};
+use domain::{ChannelId, DomainError, RepositoryFuture, ValidatorId};
+
+/// Sentry only stores and serves the messages, so it keeps the signatures and state roots
+/// in the same format as they were received from the validators
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SentryState {}
+
+impl State for SentryState {
+    type Signature = String;
+    type StateRoot = String;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorMessage {
+    pub channel_id: ChannelId,
+    /// The identity of the validator that sent the message
+    pub from: ValidatorId,
+    pub received: DateTime<Utc>,
+    pub msg: Message<SentryState>,
+}
+
+/// The latest `NewState` of the leader, which the follower approved with an `ApproveState`
+/// of the same `state_root`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LastApproved {
+    pub new_state: NewState<SentryState>,
+    pub approve_state: ApproveState<SentryState>,
+}
+
+pub struct ValidatorMessageListParams {
+    pub channel_id: ChannelId,
+    /// filters the messages sent by a specific validator if provided
+    pub from: Option<ValidatorId>,
+    /// filters the messages by their type, an empty list means all the types
+    pub types: Vec<MessageType>,
+    /// messages limit, should be >= 1
+    pub limit: u32,
+    /// Ensures that this struct can only be created by calling `new()`
+    _secret: (),
+}
+
+impl ValidatorMessageListParams {
+    /// The `types` are separated by `+`, e.g. `new+approve`
+    pub fn new(
+        channel_id: ChannelId,
+        from: Option<&str>,
+        types: Option<&str>,
+        limit: u32,
+    ) -> Result<Self, DomainError> {
+        if limit < 1 {
+            return Err(DomainError::InvalidArgument(
+                "Limit should be >= 1".to_string(),
+            ));
+        }
+
+        let from = from
+            .and_then(|s| if s.is_empty() { None } else { Some(s) })
+            .map(ValidatorId::try_from)
+            .transpose()?;
+
+        let types: Vec<MessageType> = match types {
+            Some(types) if !types.is_empty() => types
+                .split('+')
+                .map(MessageType::try_from)
+                .collect::<Result<_, _>>()?,
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            channel_id,
+            from,
+            types,
+            limit,
+            _secret: (),
+        })
+    }
+}
+
+pub trait ValidatorMessageRepository: Send + Sync {
+    /// Returns up to `limit` messages, the newest first
+    fn list(&self, params: &ValidatorMessageListParams) -> RepositoryFuture<Vec<ValidatorMessage>>;
+
+    /// Pairs the latest `NewState` sent by the `leader` with a healthy `ApproveState` of the same
+    /// `state_root` sent by the `follower`, if there is such. An unhealthy approval is ignored.
+    fn last_approved(
+        &self,
+        channel_id: &ChannelId,
+        leader: &ValidatorId,
+        follower: &ValidatorId,
+    ) -> RepositoryFuture<Option<LastApproved>>;
+
+    /// Adds all the messages or none of them
+    fn add(&self, messages: Vec<ValidatorMessage>) -> RepositoryFuture<()>;
+}
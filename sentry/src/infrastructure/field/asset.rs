@@ -7,6 +7,12 @@ use domain::Asset;
 #[derive(Debug)]
 pub(crate) struct AssetPg(Asset);
 
+impl From<Asset> for AssetPg {
+    fn from(asset: Asset) -> Self {
+        Self(asset)
+    }
+}
+
 impl Into<Asset> for AssetPg {
     fn into(self) -> Asset {
         self.0
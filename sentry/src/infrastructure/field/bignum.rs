@@ -1,12 +1,25 @@
+use std::convert::TryFrom;
 use std::error::Error;
 
+use tokio_postgres::to_sql_checked;
 use tokio_postgres::types::{FromSql, IsNull, ToSql, Type};
 
 use domain::BigNum;
 
+/// The `NUMERIC` binary format uses base 10 000 digits, i.e. 4 decimal digits per NUMERIC digit
+const NUMERIC_DIGIT_LENGTH: usize = 4;
+const NUMERIC_POSITIVE: u16 = 0x0000;
+const NUMERIC_HEADER_LENGTH: usize = 8;
+
 #[derive(Debug)]
 pub(crate) struct BigNumPg(BigNum);
 
+impl From<BigNum> for BigNumPg {
+    fn from(big_num: BigNum) -> Self {
+        Self(big_num)
+    }
+}
+
 impl Into<BigNum> for BigNumPg {
     fn into(self) -> BigNum {
         self.0
@@ -15,18 +28,17 @@ impl Into<BigNum> for BigNumPg {
 
 impl<'a> FromSql<'a> for BigNumPg {
     fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<BigNumPg, Box<dyn Error + Sync + Send>> {
-        use std::convert::TryInto;
-
-        let str_slice = <&str as FromSql>::from_sql(ty, raw)?;
-
-        let big_num = str_slice.try_into()?;
+        let big_num = match *ty {
+            Type::NUMERIC => BigNum::try_from(numeric_to_decimal(raw)?.as_str())?,
+            _ => BigNum::try_from(<&str as FromSql>::from_sql(ty, raw)?)?,
+        };
 
         Ok(BigNumPg(big_num))
     }
 
     fn accepts(ty: &Type) -> bool {
         match *ty {
-            Type::TEXT | Type::VARCHAR => true,
+            Type::NUMERIC | Type::TEXT | Type::VARCHAR => true,
             _ => false,
         }
     }
@@ -34,21 +46,181 @@ impl<'a> FromSql<'a> for BigNumPg {
 
 impl ToSql for BigNumPg {
     fn to_sql(&self, ty: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-        <String as ToSql>::to_sql(&self.0.to_string(), ty, w)
+        match *ty {
+            Type::NUMERIC => {
+                w.extend(decimal_to_numeric(&self.0.to_string()));
+
+                Ok(IsNull::No)
+            }
+            _ => <String as ToSql>::to_sql(&self.0.to_string(), ty, w),
+        }
     }
 
     fn accepts(ty: &Type) -> bool {
         match *ty {
-            Type::TEXT | Type::VARCHAR => true,
+            Type::NUMERIC | Type::TEXT | Type::VARCHAR => true,
             _ => false,
         }
     }
 
-    fn to_sql_checked(
-        &self,
-        ty: &Type,
-        out: &mut Vec<u8>,
-    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-        <String as ToSql>::to_sql_checked(&self.0.to_string(), ty, out)
+    to_sql_checked!();
+}
+
+/// Encodes a non-negative integer in decimal notation to the `NUMERIC` binary format:
+/// `ndigits`, `weight`, `sign` & `dscale` (2 bytes each), followed by `ndigits` base 10 000 digits.
+fn decimal_to_numeric(decimal: &str) -> Vec<u8> {
+    let decimal = decimal.trim_start_matches('0');
+
+    // left pad the number with zeros, so that it can be split into whole NUMERIC digits
+    let padding =
+        (NUMERIC_DIGIT_LENGTH - decimal.len() % NUMERIC_DIGIT_LENGTH) % NUMERIC_DIGIT_LENGTH;
+    let padded = format!("{}{}", "0".repeat(padding), decimal);
+
+    let mut digits: Vec<i16> = padded
+        .as_bytes()
+        .chunks(NUMERIC_DIGIT_LENGTH)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .fold(0, |digit, byte| digit * 10 + i16::from(byte - b'0'))
+        })
+        .collect();
+
+    // the weight is the exponent of the first digit, before trimming the trailing zero digits
+    let weight = digits.len() as i16 - 1;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    let weight = if digits.is_empty() { 0 } else { weight };
+
+    let mut numeric = Vec::with_capacity(NUMERIC_HEADER_LENGTH + digits.len() * 2);
+    numeric.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+    numeric.extend_from_slice(&weight.to_be_bytes());
+    numeric.extend_from_slice(&NUMERIC_POSITIVE.to_be_bytes());
+    // dscale
+    numeric.extend_from_slice(&0_u16.to_be_bytes());
+    for digit in digits {
+        numeric.extend_from_slice(&digit.to_be_bytes());
+    }
+
+    numeric
+}
+
+/// Decodes the `NUMERIC` binary format to a non-negative integer in decimal notation.
+/// Negative, `NaN` and fractional numbers are rejected, since they are not valid `BigNum`s.
+fn numeric_to_decimal(raw: &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    if raw.len() < NUMERIC_HEADER_LENGTH {
+        return Err("Invalid NUMERIC: the header is too short".into());
+    }
+
+    let read_u16 = |index: usize| u16::from_be_bytes([raw[index], raw[index + 1]]);
+
+    let ndigits = read_u16(0) as usize;
+    let weight = read_u16(2) as i16;
+    let sign = read_u16(4);
+
+    if sign != NUMERIC_POSITIVE {
+        return Err("Invalid NUMERIC: only positive numbers are allowed".into());
+    }
+
+    if raw.len() != NUMERIC_HEADER_LENGTH + ndigits * 2 {
+        return Err("Invalid NUMERIC: the digits do not match the length".into());
+    }
+
+    if ndigits == 0 {
+        return Ok("0".to_string());
+    }
+
+    // the exponent of the last digit should not be negative, otherwise we have a fraction
+    if i32::from(weight) < ndigits as i32 - 1 {
+        return Err("Invalid NUMERIC: only integers are allowed".into());
+    }
+
+    let mut decimal = String::with_capacity((weight as usize + 1) * NUMERIC_DIGIT_LENGTH);
+    for index in 0..ndigits {
+        let digit = read_u16(NUMERIC_HEADER_LENGTH + index * 2);
+        if digit > 9999 {
+            return Err("Invalid NUMERIC: digit is out of range".into());
+        }
+
+        if index == 0 {
+            decimal.push_str(&digit.to_string());
+        } else {
+            decimal.push_str(&format!("{:04}", digit));
+        }
+    }
+    // the trailing zero digits are not included in the binary format
+    let trailing_digits = weight as usize + 1 - ndigits;
+    decimal.push_str(&"0".repeat(trailing_digits * NUMERIC_DIGIT_LENGTH));
+
+    Ok(decimal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn numeric(ndigits: i16, weight: i16, digits: &[i16]) -> Vec<u8> {
+        let mut numeric = Vec::new();
+        numeric.extend_from_slice(&ndigits.to_be_bytes());
+        numeric.extend_from_slice(&weight.to_be_bytes());
+        numeric.extend_from_slice(&[0, 0, 0, 0]);
+        for digit in digits {
+            numeric.extend_from_slice(&digit.to_be_bytes());
+        }
+
+        numeric
+    }
+
+    #[test]
+    fn encodes_decimal_to_numeric() {
+        assert_eq!(numeric(0, 0, &[]), decimal_to_numeric("0"));
+        assert_eq!(numeric(1, 0, &[42]), decimal_to_numeric("42"));
+        assert_eq!(numeric(2, 1, &[1, 2345]), decimal_to_numeric("12345"));
+        // 1 0000 0000 - the trailing zero digits are trimmed
+        assert_eq!(numeric(1, 2, &[1]), decimal_to_numeric("100000000"));
+        assert_eq!(numeric(3, 2, &[1, 0, 1]), decimal_to_numeric("100000001"));
+    }
+
+    #[test]
+    fn decodes_numeric_to_decimal() {
+        assert_eq!("0", numeric_to_decimal(&numeric(0, 0, &[])).unwrap());
+        assert_eq!("42", numeric_to_decimal(&numeric(1, 0, &[42])).unwrap());
+        assert_eq!("12345", numeric_to_decimal(&numeric(2, 1, &[1, 2345])).unwrap());
+        assert_eq!("100000000", numeric_to_decimal(&numeric(1, 2, &[1])).unwrap());
+        assert_eq!("100000001", numeric_to_decimal(&numeric(3, 2, &[1, 0, 1])).unwrap());
+    }
+
+    #[test]
+    fn round_trips_uint256_max() {
+        let uint256_max =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+        assert_eq!(
+            uint256_max,
+            numeric_to_decimal(&decimal_to_numeric(uint256_max)).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_numerics() {
+        // negative
+        let mut negative = numeric(1, 0, &[42]);
+        negative[4] = 0x40;
+        assert!(numeric_to_decimal(&negative).is_err());
+
+        // NaN
+        let mut nan = numeric(0, 0, &[]);
+        nan[4] = 0xC0;
+        assert!(numeric_to_decimal(&nan).is_err());
+
+        // 0.5
+        assert!(numeric_to_decimal(&numeric(1, -1, &[5000])).is_err());
+        // 1.5
+        assert!(numeric_to_decimal(&numeric(2, 0, &[1, 5000])).is_err());
+        // wrong length
+        assert!(numeric_to_decimal(&numeric(2, 1, &[1])).is_err());
+        // out of range digit
+        assert!(numeric_to_decimal(&numeric(1, 0, &[10000])).is_err());
     }
 }
@@ -7,6 +7,12 @@ use domain::ChannelId;
 #[derive(Debug)]
 pub(crate) struct ChannelIdPg(ChannelId);
 
+impl From<ChannelId> for ChannelIdPg {
+    fn from(channel_id: ChannelId) -> Self {
+        Self(channel_id)
+    }
+}
+
 impl Into<ChannelId> for ChannelIdPg {
     fn into(self) -> ChannelId {
         self.0
@@ -1,11 +1,148 @@
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use futures::compat::Future01CompatExt;
+use serde::Deserialize;
+
+use domain::DomainError;
+
+use crate::domain::channel::ChannelRepository;
+use crate::domain::event::EventRepository;
+use crate::domain::event_aggregate::EventAggregateRepository;
+use crate::domain::validator_message::ValidatorMessageRepository;
+use crate::infrastructure::persistence::channel::{
+    MemoryChannelRepository, PostgresChannelRepository,
+};
+use crate::infrastructure::persistence::event::{MemoryEventRepository, PostgresEventRepository};
+use crate::infrastructure::persistence::event_aggregate::{
+    MemoryEventAggregateRepository, PostgresEventAggregateRepository,
+};
+use crate::infrastructure::persistence::validator_message::{
+    MemoryValidatorMessageRepository, PostgresValidatorMessageRepository,
+};
+
 pub mod channel;
+pub mod event;
+pub mod event_aggregate;
 pub mod memory;
 pub mod postgres;
+pub mod rate_limit;
+pub mod validator_message;
 
 pub type DbPool = bb8::Pool<bb8_postgres::PostgresConnectionManager<tokio_postgres::NoTls>>;
 
+pub async fn database_pool(
+    database_url: String,
+    max_size: u32,
+) -> Result<DbPool, tokio_postgres::Error> {
+    let postgres_connection =
+        bb8_postgres::PostgresConnectionManager::new(database_url, tokio_postgres::NoTls);
+
+    await!(bb8::Pool::builder()
+        .max_size(max_size)
+        .build(postgres_connection)
+        .compat())
+}
+
 // @TODO: Find a way to define the repository in `ChannelResource::new()` and use it inside the `impl_web!` macro
 pub enum Persistence {
     Memory,
     Postgres(DbPool),
 }
+
+impl Persistence {
+    /// Every call creates a new repository, for `Memory` this means a new empty one,
+    /// so it should be called once and the repository shared.
+    pub fn channel_repository(&self) -> Arc<dyn ChannelRepository> {
+        match self {
+            Persistence::Memory => Arc::new(MemoryChannelRepository::new(None)),
+            Persistence::Postgres(db_pool) => {
+                Arc::new(PostgresChannelRepository::new(db_pool.clone()))
+            }
+        }
+    }
+
+    pub fn event_aggregate_repository(&self) -> Arc<dyn EventAggregateRepository> {
+        match self {
+            Persistence::Memory => Arc::new(MemoryEventAggregateRepository::new(None)),
+            Persistence::Postgres(db_pool) => {
+                Arc::new(PostgresEventAggregateRepository::new(db_pool.clone()))
+            }
+        }
+    }
+
+    /// For `Memory` the events are aggregated into `event_aggregate_repository`,
+    /// Postgres aggregates them into its own table in the same transaction.
+    pub fn event_repository(
+        &self,
+        event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    ) -> Arc<dyn EventRepository> {
+        match self {
+            Persistence::Memory => {
+                Arc::new(MemoryEventRepository::new(None, event_aggregate_repository))
+            }
+            Persistence::Postgres(db_pool) => {
+                Arc::new(PostgresEventRepository::new(db_pool.clone()))
+            }
+        }
+    }
+
+    pub fn validator_message_repository(&self) -> Arc<dyn ValidatorMessageRepository> {
+        match self {
+            Persistence::Memory => Arc::new(MemoryValidatorMessageRepository::new(None)),
+            Persistence::Postgres(db_pool) => {
+                Arc::new(PostgresValidatorMessageRepository::new(db_pool.clone()))
+            }
+        }
+    }
+}
+
+/// The storage backend selected for the repositories
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Storage {
+    Memory,
+    Postgres,
+}
+
+impl Storage {
+    pub const VALUES: [&'static str; 2] = ["memory", "postgres"];
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage::Memory
+    }
+}
+
+impl TryFrom<&str> for Storage {
+    type Error = DomainError;
+
+    fn try_from(storage: &str) -> Result<Self, Self::Error> {
+        match storage {
+            "memory" => Ok(Storage::Memory),
+            "postgres" => Ok(Storage::Postgres),
+            _ => Err(DomainError::InvalidArgument(format!(
+                "Unknown storage '{}', expected one of: {}",
+                storage,
+                Storage::VALUES.join(", ")
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn storage_from_str() {
+        assert_eq!(Ok(Storage::Memory), Storage::try_from("memory"));
+        assert_eq!(Ok(Storage::Postgres), Storage::try_from("postgres"));
+        assert!(Storage::try_from("mysql").is_err());
+
+        for value in Storage::VALUES.iter() {
+            assert!(Storage::try_from(*value).is_ok());
+        }
+    }
+}
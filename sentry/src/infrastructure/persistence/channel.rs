@@ -3,3 +3,6 @@ pub use self::postgres::PostgresChannelRepository;
 
 pub mod memory;
 pub mod postgres;
+
+#[cfg(test)]
+mod conformance;
@@ -0,0 +1,245 @@
+//! Shared test suite, which every `ChannelRepository` implementation should pass.
+//! Every function expects an empty repository and adds the channels it needs through `add()`.
+use chrono::Utc;
+use time::Duration;
+
+use domain::fixtures::*;
+use domain::{BigNum, Channel, ChannelId, RepositoryError, SpecValidators};
+
+use crate::domain::channel::{ChannelListOrder, ChannelListParams, ChannelRepository};
+
+pub(crate) async fn adds_and_finds_channel<'a>(repository: &'a dyn ChannelRepository) {
+    let channel = get_channel("channel 1", &None, None);
+
+    await!(repository.add(channel.clone())).expect("Saving the channel failed");
+
+    let found = await!(repository.find(&channel.id))
+        .expect("Finding the channel failed")
+        .expect("The channel should exist");
+
+    assert_eq!(channel.id, found.id);
+    assert_eq!(channel.creator, found.creator);
+    assert_eq!(channel.deposit_asset, found.deposit_asset);
+    assert_eq!(channel.deposit_amount, found.deposit_amount);
+
+    let not_found = await!(repository.find(&get_channel_id("channel 2")))
+        .expect("Finding a non-existing channel failed");
+    assert!(not_found.is_none(), "A non-existing channel was found");
+}
+
+pub(crate) async fn adding_the_same_channel_id_errors<'a>(repository: &'a dyn ChannelRepository) {
+    await!(repository.add(get_channel("ABC", &None, None))).expect("Saving the channel failed");
+
+    let error = await!(repository.add(get_channel("ABC", &None, None)))
+        .expect_err("It shouldn't be possible to save the same channel_id");
+
+    match error {
+        RepositoryError::AlreadyExists => {}
+        _ => panic!("Expected AlreadyExists error"),
+    }
+}
+
+pub(crate) async fn lists_channels_with_page_and_limit<'a>(
+    repository: &'a dyn ChannelRepository,
+) {
+    let valid_until_ge = Utc::now() - Duration::days(1);
+
+    // using Utc::now() will assure that the channels always have >= valid_until_ge DateTime
+    for channel in get_channels(6, Some(Utc::now())) {
+        await!(repository.add(channel)).expect("Saving the channel failed");
+    }
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, None).unwrap();
+    let all_channels = await!(repository.list(&params)).expect("Should list all channels");
+    assert_eq!(6, all_channels.len());
+    assert_eq!(&get_channel_id("channel 6"), &all_channels[5].id);
+    assert_eq!(1, await!(repository.list_count(&params)).unwrap());
+
+    let params = ChannelListParams::new(valid_until_ge, 4, 1, None).unwrap();
+    let first_page = await!(repository.list(&params)).unwrap();
+    assert_eq!(4, first_page.len());
+    assert_eq!(&get_channel_id("channel 4"), &first_page[3].id);
+    assert_eq!(2, await!(repository.list_count(&params)).unwrap());
+
+    // if we have 5 per page & we are on page 2, one is left
+    let params = ChannelListParams::new(valid_until_ge, 5, 2, None).unwrap();
+    let one_channel_on_page = await!(repository.list(&params)).unwrap();
+    assert_eq!(1, one_channel_on_page.len());
+    assert_eq!(&get_channel_id("channel 6"), &one_channel_on_page[0].id);
+
+    // we should get 0 channels if we are out of bound
+    let params = ChannelListParams::new(valid_until_ge, 6, 2, None).unwrap();
+    assert_eq!(0, await!(repository.list(&params)).unwrap().len());
+
+    let params = ChannelListParams::new(valid_until_ge, 2, 2, None).unwrap();
+    let two_channels_on_page = await!(repository.list(&params)).unwrap();
+    assert_eq!(2, two_channels_on_page.len());
+    assert_eq!(&get_channel_id("channel 3"), &two_channels_on_page[0].id);
+    assert_eq!(&get_channel_id("channel 4"), &two_channels_on_page[1].id);
+    assert_eq!(3, await!(repository.list_count(&params)).unwrap());
+}
+
+pub(crate) async fn lists_channels_filtered_by_valid_until<'a>(
+    repository: &'a dyn ChannelRepository,
+) {
+    let valid_until_yesterday = Some(Utc::now() - Duration::days(1));
+    // create the valid_until_ge, before creating the channels,
+    // as they might otherwise have valid_until < valid_until_ge
+    let valid_until_ge = Utc::now();
+
+    let channels = vec![
+        get_channel("channel 1", &None, None),
+        get_channel("channel 2 yesterday", &valid_until_yesterday, None),
+        get_channel("channel 3", &None, None),
+        get_channel("channel 4 yesterday", &valid_until_yesterday, None),
+        get_channel("channel 5", &None, None),
+    ];
+    for channel in channels {
+        await!(repository.add(channel)).expect("Saving the channel failed");
+    }
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, None).unwrap();
+    let list_channels = await!(repository.list(&params)).expect("Should list all channels");
+
+    assert_eq!(3, list_channels.len());
+    assert_eq!(&get_channel_id("channel 1"), &list_channels[0].id);
+    assert_eq!(&get_channel_id("channel 3"), &list_channels[1].id);
+    assert_eq!(&get_channel_id("channel 5"), &list_channels[2].id);
+
+    let params = ChannelListParams::new(valid_until_ge, 2, 1, None).unwrap();
+    assert_eq!(2, await!(repository.list_count(&params)).unwrap());
+}
+
+pub(crate) async fn lists_channels_filtered_by_validator<'a>(
+    repository: &'a dyn ChannelRepository,
+) {
+    let valid_until_yesterday = Some(Utc::now() - Duration::days(1));
+    let valid_until_ge = Utc::now();
+
+    let validators: SpecValidators = [
+        get_validator("validator-1", None),
+        get_validator("validator-2", None),
+    ]
+    .into();
+    let channel_2_spec = get_channel_spec(ValidatorsOption::SpecValidators(validators.clone()));
+    let channel_5_spec = get_channel_spec(ValidatorsOption::SpecValidators(validators));
+
+    let channels = vec![
+        get_channel("channel 1", &None, None),
+        get_channel(
+            "channel 2 yesterday",
+            &valid_until_yesterday,
+            Some(channel_2_spec),
+        ),
+        get_channel("channel 3", &None, None),
+        get_channel("channel 5", &None, Some(channel_5_spec)),
+    ];
+    for channel in channels {
+        await!(repository.add(channel)).expect("Saving the channel failed");
+    }
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, Some("validator-1")).unwrap();
+    let list_channels = await!(repository.list(&params)).expect("Should list all channels");
+
+    assert_eq!(1, list_channels.len());
+    assert_eq!(&get_channel_id("channel 5"), &list_channels[0].id);
+    assert_eq!(1, await!(repository.list_count(&params)).unwrap());
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, Some("validator-3")).unwrap();
+    assert_eq!(0, await!(repository.list(&params)).unwrap().len());
+    assert_eq!(0, await!(repository.list_count(&params)).unwrap());
+}
+
+pub(crate) async fn lists_channels_filtered_by_deposit_amount<'a>(
+    repository: &'a dyn ChannelRepository,
+) {
+    let valid_until_ge = Utc::now();
+
+    for (id, deposit_amount) in &[("channel 1", 100), ("channel 2", 300), ("channel 3", 200)] {
+        await!(repository.add(get_channel_with_deposit(id, *deposit_amount)))
+            .expect("Saving the channel failed");
+    }
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, None)
+        .unwrap()
+        .with_deposit_amount_ge(Some(BigNum::from(200)));
+    let list_channels = await!(repository.list(&params)).expect("Should list the channels");
+
+    assert_eq!(2, list_channels.len());
+    assert_eq!(&get_channel_id("channel 2"), &list_channels[0].id);
+    assert_eq!(&get_channel_id("channel 3"), &list_channels[1].id);
+    assert_eq!(1, await!(repository.list_count(&params)).unwrap());
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, None)
+        .unwrap()
+        .with_deposit_amount_ge(Some(BigNum::from(301)));
+    assert_eq!(0, await!(repository.list(&params)).unwrap().len());
+    assert_eq!(0, await!(repository.list_count(&params)).unwrap());
+}
+
+pub(crate) async fn lists_channels_ordered_by_deposit_amount<'a>(
+    repository: &'a dyn ChannelRepository,
+) {
+    let valid_until_ge = Utc::now();
+
+    let deposits = &[
+        ("channel 1", 300),
+        ("channel 2", 100),
+        ("channel 3", 200),
+        ("channel 4", 100),
+    ];
+    for (id, deposit_amount) in deposits {
+        await!(repository.add(get_channel_with_deposit(id, *deposit_amount)))
+            .expect("Saving the channel failed");
+    }
+
+    let ids = |channels: Vec<Channel>| -> Vec<ChannelId> {
+        channels.into_iter().map(|channel| channel.id).collect()
+    };
+
+    // channels with the same deposit amount keep the order in which they were added
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, None)
+        .unwrap()
+        .with_order(ChannelListOrder::DepositAmountAsc);
+    let ascending = await!(repository.list(&params)).expect("Should list the channels");
+    assert_eq!(
+        vec![
+            get_channel_id("channel 2"),
+            get_channel_id("channel 4"),
+            get_channel_id("channel 3"),
+            get_channel_id("channel 1"),
+        ],
+        ids(ascending)
+    );
+
+    let params = ChannelListParams::new(valid_until_ge, 10, 1, None)
+        .unwrap()
+        .with_order(ChannelListOrder::DepositAmountDesc);
+    let descending = await!(repository.list(&params)).expect("Should list the channels");
+    assert_eq!(
+        vec![
+            get_channel_id("channel 1"),
+            get_channel_id("channel 3"),
+            get_channel_id("channel 2"),
+            get_channel_id("channel 4"),
+        ],
+        ids(descending)
+    );
+
+    // the order is applied before the pagination
+    let params = ChannelListParams::new(valid_until_ge, 2, 2, None)
+        .unwrap()
+        .with_order(ChannelListOrder::DepositAmountDesc);
+    let second_page = await!(repository.list(&params)).expect("Should list the channels");
+    assert_eq!(
+        vec![get_channel_id("channel 2"), get_channel_id("channel 4")],
+        ids(second_page)
+    );
+}
+
+fn get_channel_with_deposit(id: &str, deposit_amount: u64) -> Channel {
+    let mut channel = get_channel(id, &None, None);
+    channel.deposit_amount = BigNum::from(deposit_amount);
+
+    channel
+}
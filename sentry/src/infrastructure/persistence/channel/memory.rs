@@ -3,7 +3,8 @@ use futures::future::{ready, FutureExt};
 use domain::{Channel, ChannelId, RepositoryFuture};
 use memory_repository::MemoryRepository;
 
-use crate::domain::channel::{ChannelListParams, ChannelRepository};
+use crate::domain::channel::{ChannelListOrder, ChannelListParams, ChannelRepository};
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 #[cfg(test)]
@@ -30,8 +31,16 @@ impl ChannelRepository for MemoryChannelRepository {
     fn list(&self, params: &ChannelListParams) -> RepositoryFuture<Vec<Channel>> {
         let result = self
             .inner
-            .list(params.limit, params.page, |channel| {
-                list_filter(&params, channel)
+            .list_all(|channel| list_filter(&params, channel))
+            .map(|mut channels| {
+                sort_channels(&mut channels, params.order);
+
+                let skip = usize::try_from(params.offset).unwrap_or(usize::MAX);
+                channels
+                    .into_iter()
+                    .skip(skip)
+                    .take(params.limit as usize)
+                    .collect()
             })
             .map_err(Into::into);
 
@@ -78,8 +87,26 @@ fn list_filter(params: &ChannelListParams, channel: &Channel) -> Option<Channel>
         None => true,
     };
 
-    match (valid_until_filter, validator_filter_passed) {
-        (true, true) => Some(channel.clone()),
-        (_, _) => None,
+    let deposit_amount_filter = match &params.deposit_amount_ge {
+        Some(deposit_amount_ge) => &channel.deposit_amount >= deposit_amount_ge,
+        None => true,
+    };
+
+    match (valid_until_filter, validator_filter_passed, deposit_amount_filter) {
+        (true, true, true) => Some(channel.clone()),
+        (_, _, _) => None,
+    }
+}
+
+/// The sort is stable, so channels with equal order keep their insertion order
+fn sort_channels(channels: &mut [Channel], order: ChannelListOrder) {
+    match order {
+        ChannelListOrder::Insertion => {}
+        ChannelListOrder::DepositAmountAsc => {
+            channels.sort_by(|left, right| left.deposit_amount.cmp(&right.deposit_amount))
+        }
+        ChannelListOrder::DepositAmountDesc => {
+            channels.sort_by(|left, right| right.deposit_amount.cmp(&left.deposit_amount))
+        }
     }
 }
@@ -92,6 +92,20 @@ fn listing_channels_can_handle_page_and_limit() {
     })
 }
 
+#[test]
+fn listing_channels_errors_when_the_page_offset_overflows() {
+    let valid_until_ge = Utc::now() - Duration::days(1);
+
+    assert!(ChannelListParams::new(valid_until_ge, 10, u64::max_value(), None).is_err());
+    // the offset should fit into the `i64` of the postgres `OFFSET`
+    let page = i64::max_value() as u64 / 10 + 2;
+    assert!(ChannelListParams::new(valid_until_ge, 10, page, None).is_err());
+
+    let params = ChannelListParams::new(valid_until_ge, 10, page - 1, None)
+        .expect("The offset fits into an i64");
+    assert_eq!(i64::max_value() / 10 * 10, params.offset);
+}
+
 #[test]
 fn listing_channels_can_handle_valid_until_filtration() {
     futures::executor::block_on(async {
@@ -216,8 +230,63 @@ fn saving_the_same_channel_id_should_error() {
             .expect_err("It shouldn't be possible to save the same channel_id");
 
         match error {
-            RepositoryError::User => {}
-            _ => panic!("Expected UserError"),
+            RepositoryError::AlreadyExists => {}
+            _ => panic!("Expected AlreadyExists error"),
         }
     })
 }
+
+mod conformance {
+    use futures::executor::block_on;
+
+    use crate::infrastructure::persistence::channel::conformance;
+
+    use super::MemoryChannelRepository;
+
+    #[test]
+    fn adds_and_finds_channel() {
+        block_on(conformance::adds_and_finds_channel(&MemoryChannelRepository::new(None)))
+    }
+
+    #[test]
+    fn adding_the_same_channel_id_errors() {
+        block_on(conformance::adding_the_same_channel_id_errors(
+            &MemoryChannelRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_channels_with_page_and_limit() {
+        block_on(conformance::lists_channels_with_page_and_limit(
+            &MemoryChannelRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_channels_filtered_by_valid_until() {
+        block_on(conformance::lists_channels_filtered_by_valid_until(
+            &MemoryChannelRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_channels_filtered_by_validator() {
+        block_on(conformance::lists_channels_filtered_by_validator(
+            &MemoryChannelRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_channels_filtered_by_deposit_amount() {
+        block_on(conformance::lists_channels_filtered_by_deposit_amount(
+            &MemoryChannelRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_channels_ordered_by_deposit_amount() {
+        block_on(conformance::lists_channels_ordered_by_deposit_amount(
+            &MemoryChannelRepository::new(None),
+        ))
+    }
+}
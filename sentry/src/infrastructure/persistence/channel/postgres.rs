@@ -1,17 +1,23 @@
+use chrono::{DateTime, Utc};
 use futures::compat::Future01CompatExt;
 use futures::future::FutureExt;
 use futures_legacy::Future as OldFuture;
+use serde_json::{json, Value};
 use tokio_postgres::types::Json;
 use tokio_postgres::Row;
 
 use domain::{Channel, ChannelId, ChannelSpec, RepositoryFuture};
 use try_future::try_future;
 
-use crate::domain::channel::{ChannelListParams, ChannelRepository};
+use crate::domain::channel::{ChannelListOrder, ChannelListParams, ChannelRepository};
 use crate::infrastructure::field::{asset::AssetPg, bignum::BigNumPg, channel_id::ChannelIdPg};
 use crate::infrastructure::persistence::postgres::PostgresPersistenceError;
 use crate::infrastructure::persistence::DbPool;
-use crate::infrastructure::util::bb8::query_result;
+use crate::infrastructure::util::bb8::{execute_result, query_result};
+
+#[cfg(test)]
+#[path = "./postgres_test.rs"]
+mod postgres_test;
 
 #[derive(Debug)]
 pub struct PostgresChannelRepository {
@@ -25,17 +31,32 @@ impl PostgresChannelRepository {
 }
 
 impl ChannelRepository for PostgresChannelRepository {
-    // @TODO: use params to filter channels accordingly
-    fn list(&self, _params: &ChannelListParams) -> RepositoryFuture<Vec<Channel>> {
-        let fut = self.db_pool
+    fn list(&self, params: &ChannelListParams) -> RepositoryFuture<Vec<Channel>> {
+        let (valid_until_ge, validator, deposit_amount_ge) = list_filter_params(params);
+        let limit = i64::from(params.limit);
+        let offset = params.offset;
+        let query = format!(
+            "SELECT channel_id, creator, deposit_asset, deposit_amount, valid_until, spec FROM channels WHERE {} ORDER BY {} LIMIT $4 OFFSET $5",
+            LIST_FILTER,
+            list_order_by(params.order)
+        );
+
+        let fut = self
+            .db_pool
             .run(move |mut conn| {
-                conn.prepare("SELECT channel_id, creator, deposit_asset, deposit_amount, valid_until, spec FROM channels")
+                conn.prepare(&query)
                     .then(move |res| match res {
-                        Ok(stmt) => query_result(conn.query(&stmt, &[]), conn),
+                        Ok(stmt) => query_result(
+                            conn.query(
+                                &stmt,
+                                &[&valid_until_ge, &validator, &deposit_amount_ge, &limit, &offset],
+                            ),
+                            conn,
+                        ),
                         Err(err) => try_future!(Err((err, conn))),
                     })
                     .and_then(|(rows, conn)| {
-                        let channels = rows.iter().map(|row| channel_map(row)).collect();
+                        let channels = rows.iter().map(channel_map).collect();
 
                         Ok((channels, conn))
                     })
@@ -45,22 +66,30 @@ impl ChannelRepository for PostgresChannelRepository {
         fut.compat().boxed()
     }
 
-    fn list_count(&self, _params: &ChannelListParams) -> RepositoryFuture<u64> {
+    fn list_count(&self, params: &ChannelListParams) -> RepositoryFuture<u64> {
+        let (valid_until_ge, validator, deposit_amount_ge) = list_filter_params(params);
+        let limit = params.limit;
+        let query = format!("SELECT COUNT(channel_id)::TEXT FROM channels WHERE {}", LIST_FILTER);
+
         let fut = self
             .db_pool
             .run(move |mut conn| {
-                conn.prepare("SELECT COUNT(channel_id)::TEXT FROM channels")
+                conn.prepare(&query)
                     .then(move |res| match res {
-                        Ok(stmt) => query_result(conn.query(&stmt, &[]), conn),
+                        Ok(stmt) => query_result(
+                            conn.query(&stmt, &[&valid_until_ge, &validator, &deposit_amount_ge]),
+                            conn,
+                        ),
                         Err(err) => try_future!(Err((err, conn))),
                     })
-                    .and_then(|(rows, conn)| {
+                    .and_then(move |(rows, conn)| {
                         let count = rows[0]
                             .get::<_, &str>(0)
                             .parse::<u64>()
                             .expect("Not possible to have that many rows");
+                        let pages = (count as f64 / f64::from(limit)).ceil() as u64;
 
-                        Ok((count, conn))
+                        Ok((pages, conn))
                     })
             })
             .map_err(|err| PostgresPersistenceError::from(err).into());
@@ -68,12 +97,87 @@ impl ChannelRepository for PostgresChannelRepository {
         fut.compat().boxed()
     }
 
-    fn find(&self, _channel_id: &ChannelId) -> RepositoryFuture<Option<Channel>> {
-        unimplemented!("find() for Postgres still needs to be implemented")
+    fn find(&self, channel_id: &ChannelId) -> RepositoryFuture<Option<Channel>> {
+        let channel_id = ChannelIdPg::from(*channel_id);
+
+        let fut = self.db_pool
+            .run(move |mut conn| {
+                conn.prepare("SELECT channel_id, creator, deposit_asset, deposit_amount, valid_until, spec FROM channels WHERE channel_id = $1 LIMIT 1")
+                    .then(move |res| match res {
+                        Ok(stmt) => query_result(conn.query(&stmt, &[&channel_id]), conn),
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(rows, conn)| {
+                        let channel = rows.get(0).map(channel_map);
+
+                        Ok((channel, conn))
+                    })
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+
+    fn add(&self, channel: Channel) -> RepositoryFuture<()> {
+        let fut = self.db_pool
+            .run(move |mut conn| {
+                conn.prepare("INSERT INTO channels (channel_id, creator, deposit_asset, deposit_amount, valid_until, spec) VALUES ($1, $2, $3, $4, $5, $6)")
+                    .then(move |res| match res {
+                        Ok(stmt) => {
+                            let channel_id = ChannelIdPg::from(channel.id);
+                            let deposit_asset = AssetPg::from(channel.deposit_asset);
+                            let deposit_amount = BigNumPg::from(channel.deposit_amount);
+                            let spec = Json(&channel.spec);
+
+                            execute_result(
+                                conn.execute(
+                                    &stmt,
+                                    &[
+                                        &channel_id,
+                                        &channel.creator,
+                                        &deposit_asset,
+                                        &deposit_amount,
+                                        &channel.valid_until,
+                                        &spec,
+                                    ],
+                                ),
+                                conn,
+                            )
+                        }
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(_, conn)| Ok(((), conn)))
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
     }
+}
+
+/// The `WHERE` clause used for listing channels, the optional filters are skipped when `NULL`.
+/// The `validator` is matched against the `spec.validators` JSONB array, by using the
+/// containment operator (`@>`) with an array holding an object with only the validator `id`.
+const LIST_FILTER: &str = "valid_until >= $1 AND ($2::JSONB IS NULL OR spec->'validators' @> $2::JSONB) AND ($3::NUMERIC IS NULL OR deposit_amount >= $3::NUMERIC)";
+
+/// Returns the `valid_until_ge`, `validator` and `deposit_amount_ge` filter values.
+fn list_filter_params(
+    params: &ChannelListParams,
+) -> (DateTime<Utc>, Option<Json<Value>>, Option<BigNumPg>) {
+    let validator = params
+        .validator
+        .as_ref()
+        .map(|validator_id| Json(json!([{ "id": validator_id }])));
+    let deposit_amount_ge = params.deposit_amount_ge.clone().map(BigNumPg::from);
+
+    (params.valid_until_ge, validator, deposit_amount_ge)
+}
 
-    fn add(&self, _channel: Channel) -> RepositoryFuture<()> {
-        unimplemented!("create() for Postgres still needs to be implemented")
+/// Channels with equal order are always ordered by their `insertion_order`
+fn list_order_by(order: ChannelListOrder) -> &'static str {
+    match order {
+        ChannelListOrder::Insertion => "insertion_order ASC",
+        ChannelListOrder::DepositAmountAsc => "deposit_amount ASC, insertion_order ASC",
+        ChannelListOrder::DepositAmountDesc => "deposit_amount DESC, insertion_order ASC",
     }
 }
 
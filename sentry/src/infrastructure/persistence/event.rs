@@ -0,0 +1,8 @@
+pub use self::memory::MemoryEventRepository;
+pub use self::postgres::PostgresEventRepository;
+
+pub mod memory;
+pub mod postgres;
+
+#[cfg(test)]
+mod conformance;
@@ -0,0 +1,96 @@
+//! Shared test suite, which every `EventRepository` implementation should pass.
+//! Every function expects an empty repository and adds the events it needs through `add()`.
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use futures::future::{ready, FutureExt};
+use time::Duration;
+
+use domain::fixtures::get_channel_id;
+use domain::{Event, EventAggregate, RepositoryError};
+
+use crate::domain::event::{ChannelEvent, EventFold, EventRepository};
+
+fn get_channel_event(channel: &str, publisher: &str, created: DateTime<Utc>) -> ChannelEvent {
+    ChannelEvent {
+        channel_id: get_channel_id(channel),
+        created,
+        event: Event::Impression {
+            publisher: publisher.to_string(),
+            ad_unit: None,
+        },
+    }
+}
+
+fn publishers(channel_events: Vec<ChannelEvent>) -> Vec<String> {
+    channel_events
+        .into_iter()
+        .map(|channel_event| match channel_event.event {
+            Event::Impression { publisher, .. } => publisher,
+            _ => panic!("Only IMPRESSION events are added"),
+        })
+        .collect()
+}
+
+/// Folds the events into one aggregate per event, keeping the publishers of the folded events
+pub(crate) fn recording_fold(folded: Arc<Mutex<Vec<String>>>) -> EventFold {
+    Box::new(move |channel_events| {
+        let aggregates = channel_events
+            .iter()
+            .map(|channel_event| EventAggregate::new(channel_event.channel_id, Utc::now()))
+            .collect();
+        folded
+            .lock()
+            .expect("Locking the folded publishers failed")
+            .extend(publishers(channel_events));
+
+        ready(Ok(aggregates)).boxed()
+    })
+}
+
+fn failing_fold() -> EventFold {
+    Box::new(|_channel_events| ready(Err(RepositoryError::User)).boxed())
+}
+
+pub(crate) async fn aggregates_the_events_created_until<'a>(repository: &'a dyn EventRepository) {
+    let until = Utc::now() - Duration::minutes(1);
+    let events = vec![
+        get_channel_event("channel 1", "first", until - Duration::minutes(1)),
+        get_channel_event("channel 1", "later", until + Duration::seconds(1)),
+        get_channel_event("channel 2", "second", until),
+    ];
+    await!(repository.add(events)).expect("Adding the events failed");
+
+    let folded = Arc::new(Mutex::new(Vec::new()));
+    let count = await!(repository.aggregate(until, recording_fold(folded.clone())))
+        .expect("Aggregating the events failed");
+    assert_eq!(2, count);
+    assert_eq!(vec!["first", "second"], *folded.lock().unwrap());
+
+    let count_again = await!(repository.aggregate(until, failing_fold()))
+        .expect("Aggregating without events should not fold");
+    assert_eq!(0, count_again, "The events should be aggregated only once");
+
+    let remaining = Arc::new(Mutex::new(Vec::new()));
+    await!(repository.aggregate(Utc::now(), recording_fold(remaining.clone())))
+        .expect("Aggregating the events failed");
+    assert_eq!(vec!["later"], *remaining.lock().unwrap());
+}
+
+pub(crate) async fn keeps_the_events_when_the_fold_fails<'a>(repository: &'a dyn EventRepository) {
+    let now = Utc::now();
+    let events = vec![
+        get_channel_event("channel 1", "first", now - Duration::minutes(1)),
+        get_channel_event("channel 2", "second", now),
+    ];
+    await!(repository.add(events)).expect("Adding the events failed");
+
+    await!(repository.aggregate(now, failing_fold()))
+        .expect_err("The failed fold should fail the aggregation");
+
+    let folded = Arc::new(Mutex::new(Vec::new()));
+    let count = await!(repository.aggregate(now, recording_fold(folded.clone())))
+        .expect("Aggregating the events failed");
+    assert_eq!(2, count);
+    assert_eq!(vec!["first", "second"], *folded.lock().unwrap());
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::{ready, FutureExt};
+
+use domain::{ChannelId, RepositoryError, RepositoryFuture};
+use memory_repository::MemoryRepository;
+
+use crate::domain::event::{ChannelEvent, EventFold, EventRepository};
+use crate::domain::event_aggregate::EventAggregateRepository;
+
+#[cfg(test)]
+#[path = "./memory_test.rs"]
+mod memory_test;
+
+pub struct MemoryEventRepository {
+    inner: MemoryRepository<ChannelEvent, ChannelId>,
+    event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+}
+
+impl MemoryEventRepository {
+    /// The events are aggregated into `event_aggregate_repository`
+    pub fn new(
+        initial_events: Option<&[ChannelEvent]>,
+        event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    ) -> Self {
+        let initial_events = initial_events.unwrap_or(&[]).to_vec();
+        // the events don't have an id of their own, they are keyed by their channel
+        // and added with `extend`, which doesn't check for the existing ones
+        let cmp: Arc<dyn Fn(&ChannelEvent, &ChannelId) -> bool + Send + Sync> =
+            Arc::new(|channel_event, channel_id| &channel_event.channel_id == channel_id);
+
+        Self {
+            inner: MemoryRepository::new(&initial_events, cmp),
+            event_aggregate_repository,
+        }
+    }
+}
+
+impl EventRepository for MemoryEventRepository {
+    fn add(&self, events: Vec<ChannelEvent>) -> RepositoryFuture<()> {
+        let result = self.inner.extend(events).map_err(Into::into);
+
+        ready(result).boxed()
+    }
+
+    /// There are no transactions in memory, so the events are added back if the aggregation fails
+    fn aggregate(&self, until: DateTime<Utc>, fold: EventFold) -> RepositoryFuture<usize> {
+        let taken = self
+            .inner
+            .remove_all(|channel_event| channel_event.created <= until)
+            .map_err(Into::into);
+
+        aggregate_events(
+            self.inner.clone(),
+            self.event_aggregate_repository.clone(),
+            taken,
+            fold,
+        )
+        .boxed()
+    }
+}
+
+async fn aggregate_events(
+    inner: MemoryRepository<ChannelEvent, ChannelId>,
+    event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    taken: Result<Vec<ChannelEvent>, RepositoryError>,
+    fold: EventFold,
+) -> Result<usize, RepositoryError> {
+    let channel_events = taken?;
+    if channel_events.is_empty() {
+        return Ok(0);
+    }
+
+    let added = match await!(fold(channel_events.clone())) {
+        Ok(aggregates) => {
+            let count = aggregates.len();
+
+            await!(event_aggregate_repository.add(aggregates)).map(|_| count)
+        }
+        Err(error) => Err(error),
+    };
+    if added.is_err() {
+        inner
+            .extend(channel_events)
+            .map_err(Into::<RepositoryError>::into)?;
+    }
+
+    added
+}
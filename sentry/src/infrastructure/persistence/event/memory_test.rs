@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use domain::fixtures::get_channel_id;
+use domain::Event;
+
+use crate::domain::event::{ChannelEvent, EventRepository};
+use crate::infrastructure::persistence::event_aggregate::MemoryEventAggregateRepository;
+
+use super::MemoryEventRepository;
+
+fn aggregate_repository() -> Arc<MemoryEventAggregateRepository> {
+    Arc::new(MemoryEventAggregateRepository::new(None))
+}
+
+fn get_channel_event(channel: &str, publisher: &str) -> ChannelEvent {
+    ChannelEvent {
+        channel_id: get_channel_id(channel),
+        created: Utc::now(),
+        event: Event::Impression {
+            publisher: publisher.to_string(),
+            ad_unit: None,
+        },
+    }
+}
+
+#[test]
+fn adds_all_the_events_in_order() {
+    futures::executor::block_on(async {
+        let initial = [get_channel_event("channel 1", "initial")];
+        let repository = MemoryEventRepository::new(Some(&initial), aggregate_repository());
+
+        let events = vec![
+            get_channel_event("channel 1", "first"),
+            get_channel_event("channel 2", "second"),
+        ];
+        await!(repository.add(events)).expect("Adding the events failed");
+
+        let publishers: Vec<String> = repository
+            .inner
+            .list_all(|channel_event| Some(channel_event.clone()))
+            .expect("Listing the events failed")
+            .into_iter()
+            .map(|channel_event| match channel_event.event {
+                Event::Impression { publisher, .. } => publisher,
+                _ => panic!("Only IMPRESSION events were added"),
+            })
+            .collect();
+
+        assert_eq!(vec!["initial", "first", "second"], publishers);
+    })
+}
+
+mod conformance {
+    use futures::executor::block_on;
+
+    use crate::infrastructure::persistence::event::conformance;
+
+    use super::{aggregate_repository, MemoryEventRepository};
+
+    #[test]
+    fn aggregates_the_events_created_until() {
+        block_on(conformance::aggregates_the_events_created_until(
+            &MemoryEventRepository::new(None, aggregate_repository()),
+        ))
+    }
+
+    #[test]
+    fn keeps_the_events_when_the_fold_fails() {
+        block_on(conformance::keeps_the_events_when_the_fold_fails(
+            &MemoryEventRepository::new(None, aggregate_repository()),
+        ))
+    }
+}
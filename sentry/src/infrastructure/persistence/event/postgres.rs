@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use futures::compat::Future01CompatExt;
+use futures::future::FutureExt;
+use futures_legacy::future::Either;
+use futures_legacy::Future as OldFuture;
+use tokio_postgres::types::Json;
+use tokio_postgres::Row;
+
+use domain::{Event, RepositoryError, RepositoryFuture};
+use try_future::try_future;
+
+use crate::domain::event::{ChannelEvent, EventFold, EventRepository};
+use crate::infrastructure::field::channel_id::ChannelIdPg;
+use crate::infrastructure::persistence::event_aggregate::postgres::INSERT_AGGREGATES;
+use crate::infrastructure::persistence::postgres::PostgresPersistenceError;
+use crate::infrastructure::persistence::DbPool;
+use crate::infrastructure::util::bb8::{execute_result, future_result, query_result};
+
+#[cfg(test)]
+#[path = "./postgres_test.rs"]
+mod postgres_test;
+
+/// Inserts all the events of the JSON array with a single statement, so either all of them
+/// or none of them are added.
+const INSERT_EVENTS: &str = r#"INSERT INTO events (channel_id, created, event) SELECT "channelId", created, event FROM JSONB_TO_RECORDSET($1::JSONB) AS events("channelId" VARCHAR(66), created TIMESTAMP WITH TIME ZONE, event JSONB)"#;
+
+/// The events are listed with their ids, so exactly the folded ones get deleted.
+const LIST_EVENTS: &str =
+    r#"SELECT id, channel_id, created, event FROM events WHERE created <= $1 ORDER BY id"#;
+
+const DELETE_EVENTS: &str = r#"DELETE FROM events WHERE id = ANY($1)"#;
+
+#[derive(Debug)]
+pub struct PostgresEventRepository {
+    db_pool: DbPool,
+}
+
+impl PostgresEventRepository {
+    pub fn new(db_pool: DbPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+impl EventRepository for PostgresEventRepository {
+    fn add(&self, events: Vec<ChannelEvent>) -> RepositoryFuture<()> {
+        let fut = self
+            .db_pool
+            .run(move |mut conn| {
+                conn.prepare(INSERT_EVENTS)
+                    .then(move |res| match res {
+                        Ok(stmt) => execute_result(conn.execute(&stmt, &[&Json(&events)]), conn),
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(_, conn)| Ok(((), conn)))
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+
+    /// The events are folded outside of the transaction, which then deletes them and inserts
+    /// the aggregates. If some of them were taken by a concurrent aggregation in the meantime,
+    /// the transaction is rolled back and no aggregates are added.
+    fn aggregate(&self, until: DateTime<Utc>, fold: EventFold) -> RepositoryFuture<usize> {
+        aggregate_events(self.db_pool.clone(), until, fold).boxed()
+    }
+}
+
+async fn aggregate_events(
+    db_pool: DbPool,
+    until: DateTime<Utc>,
+    fold: EventFold,
+) -> Result<usize, RepositoryError> {
+    let listed = db_pool
+        .run(move |mut conn| {
+            conn.prepare(LIST_EVENTS)
+                .then(move |res| match res {
+                    Ok(stmt) => query_result(conn.query(&stmt, &[&until]), conn),
+                    Err(err) => try_future!(Err((err, conn))),
+                })
+                .and_then(|(rows, conn)| {
+                    let ids: Vec<i64> = rows.iter().map(|row| row.get("id")).collect();
+                    let channel_events: Vec<ChannelEvent> =
+                        rows.iter().map(channel_event_map).collect();
+
+                    Ok(((ids, channel_events), conn))
+                })
+        })
+        .map_err(PostgresPersistenceError::from)
+        .compat();
+    let (ids, channel_events) = await!(listed).map_err(Into::<RepositoryError>::into)?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let aggregates = await!(fold(channel_events))?;
+    let count = aggregates.len();
+    let expected = ids.len() as u64;
+
+    let taken = db_pool
+        .run(move |mut conn| {
+            future_result(conn.batch_execute("BEGIN"), conn)
+                .and_then(move |(_, mut conn)| {
+                    conn.prepare(DELETE_EVENTS)
+                        .then(move |res| match res {
+                            Ok(stmt) => execute_result(conn.execute(&stmt, &[&ids]), conn),
+                            Err(err) => try_future!(Err((err, conn))),
+                        })
+                        .map(move |(deleted, conn)| (deleted == expected, conn))
+                })
+                .and_then(move |(all_deleted, mut conn)| {
+                    if !all_deleted {
+                        return Either::A(
+                            future_result(conn.batch_execute("ROLLBACK"), conn)
+                                .map(|(_, conn)| (false, conn)),
+                        );
+                    }
+
+                    let insert = conn.prepare(INSERT_AGGREGATES).then(move |res| match res {
+                        Ok(stmt) => {
+                            execute_result(conn.execute(&stmt, &[&Json(&aggregates)]), conn)
+                        }
+                        Err(err) => try_future!(Err((err, conn))),
+                    });
+
+                    Either::B(insert.and_then(|(_, mut conn)| {
+                        future_result(conn.batch_execute("COMMIT"), conn)
+                            .map(|(_, conn)| (true, conn))
+                    }))
+                })
+                .or_else(|(err, mut conn)| {
+                    // the connection goes back to the pool, so it can't stay in the transaction
+                    future_result(conn.batch_execute("ROLLBACK"), conn).then(|res| match res {
+                        Ok((_, conn)) | Err((_, conn)) => Err((err, conn)),
+                    })
+                })
+        })
+        .map_err(PostgresPersistenceError::from)
+        .compat();
+
+    if await!(taken).map_err(Into::<RepositoryError>::into)? {
+        Ok(count)
+    } else {
+        Ok(0)
+    }
+}
+
+fn channel_event_map(row: &Row) -> ChannelEvent {
+    ChannelEvent {
+        channel_id: row.get::<_, ChannelIdPg>("channel_id").into(),
+        created: row.get("created"),
+        event: row.get::<_, Json<Event>>("event").0,
+    }
+}
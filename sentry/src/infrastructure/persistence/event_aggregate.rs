@@ -0,0 +1,8 @@
+pub use self::memory::MemoryEventAggregateRepository;
+pub use self::postgres::PostgresEventAggregateRepository;
+
+pub mod memory;
+pub mod postgres;
+
+#[cfg(test)]
+mod conformance;
@@ -0,0 +1,85 @@
+//! Shared test suite, which every `EventAggregateRepository` implementation should pass.
+//! Every function expects an empty repository and adds the aggregates it needs through `add()`.
+use chrono::{DateTime, Utc};
+use time::Duration;
+
+use domain::fixtures::{get_channel, get_channel_id};
+use domain::{Event, EventAggregate};
+
+use crate::domain::event_aggregate::EventAggregateRepository;
+
+fn get_event_aggregate(channel: &str, publisher: &str, created: DateTime<Utc>) -> EventAggregate {
+    let channel = get_channel(channel, &None, None);
+    let mut aggregate = EventAggregate::new(channel.id, created);
+    let impression = Event::Impression {
+        publisher: publisher.to_string(),
+        ad_unit: None,
+    };
+    aggregate.add(&channel, &impression);
+
+    aggregate
+}
+
+fn publishers(aggregates: Vec<EventAggregate>) -> Vec<String> {
+    aggregates
+        .into_iter()
+        .flat_map(|aggregate| {
+            aggregate.events["IMPRESSION"]
+                .event_counts
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub(crate) async fn adds_and_lists_the_oldest_first<'a>(
+    repository: &'a dyn EventAggregateRepository,
+) {
+    let now = Utc::now();
+    let aggregates = vec![
+        get_event_aggregate("channel 1", "second", now - Duration::minutes(1)),
+        get_event_aggregate("channel 2", "other channel", now - Duration::minutes(3)),
+        get_event_aggregate("channel 1", "first", now - Duration::minutes(2)),
+    ];
+    await!(repository.add(aggregates)).expect("Adding the aggregates failed");
+    await!(repository.add(vec![get_event_aggregate("channel 1", "third", now)]))
+        .expect("Adding the aggregates failed");
+
+    let channel_id = get_channel_id("channel 1");
+    let aggregates =
+        await!(repository.list(&channel_id, None, 10)).expect("Listing the aggregates failed");
+    assert_eq!(
+        vec!["first", "second", "third"],
+        publishers(aggregates.clone())
+    );
+    assert!(aggregates
+        .iter()
+        .all(|aggregate| aggregate.channel_id == channel_id));
+
+    let limited =
+        await!(repository.list(&channel_id, None, 2)).expect("Listing the aggregates failed");
+    assert_eq!(vec!["first", "second"], publishers(limited));
+}
+
+pub(crate) async fn lists_the_aggregates_created_after<'a>(
+    repository: &'a dyn EventAggregateRepository,
+) {
+    let now = Utc::now();
+    let aggregates = (1..=3)
+        .map(|minutes| {
+            let created = now - Duration::minutes(minutes);
+
+            get_event_aggregate("channel 1", &minutes.to_string(), created)
+        })
+        .collect();
+    await!(repository.add(aggregates)).expect("Adding the aggregates failed");
+
+    let channel_id = get_channel_id("channel 1");
+    let after = Some(now - Duration::minutes(2));
+    let aggregates =
+        await!(repository.list(&channel_id, after, 10)).expect("Listing the aggregates failed");
+
+    // the aggregate created exactly at `after` is excluded, as it was on the previous page
+    assert_eq!(vec!["1"], publishers(aggregates));
+}
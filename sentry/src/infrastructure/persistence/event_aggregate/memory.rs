@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::{ready, FutureExt};
+
+use domain::{ChannelId, EventAggregate, RepositoryFuture};
+use memory_repository::MemoryRepository;
+
+use crate::domain::event_aggregate::EventAggregateRepository;
+
+#[cfg(test)]
+#[path = "./memory_test.rs"]
+mod memory_test;
+
+pub struct MemoryEventAggregateRepository {
+    inner: MemoryRepository<EventAggregate, ChannelId>,
+}
+
+impl MemoryEventAggregateRepository {
+    pub fn new(initial_aggregates: Option<&[EventAggregate]>) -> Self {
+        let initial_aggregates = initial_aggregates.unwrap_or(&[]).to_vec();
+        // the aggregates don't have an id of their own, they are keyed by their channel
+        // and added with `extend`, which doesn't check for the existing ones
+        let cmp: Arc<dyn Fn(&EventAggregate, &ChannelId) -> bool + Send + Sync> =
+            Arc::new(|aggregate, channel_id| &aggregate.channel_id == channel_id);
+
+        Self {
+            inner: MemoryRepository::new(&initial_aggregates, cmp),
+        }
+    }
+}
+
+impl EventAggregateRepository for MemoryEventAggregateRepository {
+    fn list(
+        &self,
+        channel_id: &ChannelId,
+        after: Option<DateTime<Utc>>,
+        limit: u32,
+    ) -> RepositoryFuture<Vec<EventAggregate>> {
+        let result = self
+            .inner
+            .find_all(channel_id)
+            .map(|mut aggregates| {
+                aggregates
+                    .retain(|aggregate| after.map_or(true, |after| aggregate.created > after));
+                // the sort is stable, so the aggregates created at the same time keep their order
+                aggregates.sort_by_key(|aggregate| aggregate.created);
+                aggregates.truncate(limit as usize);
+
+                aggregates
+            })
+            .map_err(Into::into);
+
+        ready(result).boxed()
+    }
+
+    fn add(&self, aggregates: Vec<EventAggregate>) -> RepositoryFuture<()> {
+        let result = self.inner.extend(aggregates).map_err(Into::into);
+
+        ready(result).boxed()
+    }
+}
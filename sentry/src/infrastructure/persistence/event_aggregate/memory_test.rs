@@ -0,0 +1,21 @@
+mod conformance {
+    use futures::executor::block_on;
+
+    use crate::infrastructure::persistence::event_aggregate::conformance;
+
+    use super::super::MemoryEventAggregateRepository;
+
+    #[test]
+    fn adds_and_lists_the_oldest_first() {
+        block_on(conformance::adds_and_lists_the_oldest_first(
+            &MemoryEventAggregateRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_the_aggregates_created_after() {
+        block_on(conformance::lists_the_aggregates_created_after(
+            &MemoryEventAggregateRepository::new(None),
+        ))
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use futures::compat::Future01CompatExt;
+use futures::future::FutureExt;
+use futures_legacy::Future as OldFuture;
+use tokio_postgres::types::Json;
+use tokio_postgres::Row;
+
+use domain::{AggregateEvents, ChannelId, EventAggregate, RepositoryFuture};
+use try_future::try_future;
+
+use crate::domain::event_aggregate::EventAggregateRepository;
+use crate::infrastructure::field::channel_id::ChannelIdPg;
+use crate::infrastructure::persistence::postgres::PostgresPersistenceError;
+use crate::infrastructure::persistence::DbPool;
+use crate::infrastructure::util::bb8::{execute_result, query_result};
+
+#[cfg(test)]
+#[path = "./postgres_test.rs"]
+mod postgres_test;
+
+/// Inserts all the aggregates of the JSON array with a single statement, so either all of them
+/// or none of them are added.
+pub(crate) const INSERT_AGGREGATES: &str = r#"INSERT INTO event_aggregates (channel_id, created, events) SELECT "channelId", created, events FROM JSONB_TO_RECORDSET($1::JSONB) AS aggregates("channelId" VARCHAR(66), created TIMESTAMP WITH TIME ZONE, events JSONB)"#;
+
+/// The `after` filter is skipped when `NULL`, the aggregates created at the same time
+/// are ordered by their insertion.
+const LIST_AGGREGATES: &str = r#"SELECT channel_id, created, events FROM event_aggregates WHERE channel_id = $1 AND ($2::TIMESTAMP WITH TIME ZONE IS NULL OR created > $2::TIMESTAMP WITH TIME ZONE) ORDER BY created ASC, id ASC LIMIT $3"#;
+
+#[derive(Debug)]
+pub struct PostgresEventAggregateRepository {
+    db_pool: DbPool,
+}
+
+impl PostgresEventAggregateRepository {
+    pub fn new(db_pool: DbPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+impl EventAggregateRepository for PostgresEventAggregateRepository {
+    fn list(
+        &self,
+        channel_id: &ChannelId,
+        after: Option<DateTime<Utc>>,
+        limit: u32,
+    ) -> RepositoryFuture<Vec<EventAggregate>> {
+        let channel_id = ChannelIdPg::from(*channel_id);
+        let limit = i64::from(limit);
+
+        let fut = self
+            .db_pool
+            .run(move |mut conn| {
+                conn.prepare(LIST_AGGREGATES)
+                    .then(move |res| match res {
+                        Ok(stmt) => {
+                            query_result(conn.query(&stmt, &[&channel_id, &after, &limit]), conn)
+                        }
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(rows, conn)| {
+                        let aggregates = rows.iter().map(event_aggregate_map).collect();
+
+                        Ok((aggregates, conn))
+                    })
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+
+    fn add(&self, aggregates: Vec<EventAggregate>) -> RepositoryFuture<()> {
+        let fut = self
+            .db_pool
+            .run(move |mut conn| {
+                conn.prepare(INSERT_AGGREGATES)
+                    .then(move |res| match res {
+                        Ok(stmt) => {
+                            execute_result(conn.execute(&stmt, &[&Json(&aggregates)]), conn)
+                        }
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(_, conn)| Ok(((), conn)))
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+}
+
+fn event_aggregate_map(row: &Row) -> EventAggregate {
+    EventAggregate {
+        channel_id: row.get::<_, ChannelIdPg>("channel_id").into(),
+        created: row.get("created"),
+        events: row
+            .get::<_, Json<BTreeMap<String, AggregateEvents>>>("events")
+            .0,
+    }
+}
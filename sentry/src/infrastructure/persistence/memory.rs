@@ -8,6 +8,8 @@ use domain::{IOError, RepositoryError};
 pub enum MemoryPersistenceError {
     Reading,
     Writing,
+    /// A computed time is out of the range of `DateTime`
+    TimeOverflow,
 }
 
 impl error::Error for MemoryPersistenceError {}
@@ -18,6 +20,9 @@ impl fmt::Display for MemoryPersistenceError {
         let error_type = match *self {
             MemoryPersistenceError::Reading => "reading",
             MemoryPersistenceError::Writing => "writing",
+            MemoryPersistenceError::TimeOverflow => {
+                return write!(f, "The computed time is out of range")
+            }
         };
 
         write!(
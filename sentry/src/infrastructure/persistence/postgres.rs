@@ -2,9 +2,12 @@ use std::error;
 use std::fmt;
 
 use bb8::RunError;
+use tokio_postgres::error::SqlState;
 
 use domain::{IOError, RepositoryError};
 
+pub mod migrations;
+
 #[derive(Debug)]
 pub enum PostgresPersistenceError {
     UserError(tokio_postgres::Error),
@@ -38,6 +41,13 @@ impl From<RunError<tokio_postgres::Error>> for PostgresPersistenceError {
 
 impl Into<RepositoryError> for PostgresPersistenceError {
     fn into(self) -> RepositoryError {
-        RepositoryError::IO(Box::new(self))
+        match &self {
+            PostgresPersistenceError::UserError(error)
+                if error.code() == Some(&SqlState::UNIQUE_VIOLATION) =>
+            {
+                RepositoryError::AlreadyExists
+            }
+            _ => RepositoryError::IO(Box::new(self)),
+        }
     }
 }
@@ -0,0 +1,211 @@
+//! Versioned SQL migrations, embedded in the binary and tracked in the `schema_migrations` table.
+//!
+//! Every migration is executed as a single simple query together with its `schema_migrations`
+//! record, which Postgres runs in one implicit transaction. This means that the migration files
+//! should **not** contain any transaction control commands (`BEGIN`, `COMMIT` & etc.).
+use futures::compat::Future01CompatExt;
+use futures_legacy::stream;
+use futures_legacy::{Future as OldFuture, Stream};
+use tokio_postgres::Client;
+use try_future::try_future;
+
+use crate::infrastructure::persistence::postgres::PostgresPersistenceError;
+use crate::infrastructure::persistence::DbPool;
+use crate::infrastructure::util::bb8::{future_result, query_result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// Should be unique and bigger than the version of the previous migration
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// The migration SQL followed by the insert of its `schema_migrations` record.
+    fn batch(&self) -> String {
+        format!(
+            "{}\nINSERT INTO schema_migrations (version, name) VALUES ({}, '{}');",
+            self.sql, self.version, self.name
+        )
+    }
+}
+
+/// All the migrations, ordered by their version.
+/// Once a migration is released it should never be changed, add a new one instead.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_channels",
+        sql: include_str!("../../../../migrations/0001_create_channels.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "numeric_deposit_amount",
+        sql: include_str!("../../../../migrations/0002_numeric_deposit_amount.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "create_validator_messages",
+        sql: include_str!("../../../../migrations/0003_create_validator_messages.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "create_events",
+        sql: include_str!("../../../../migrations/0004_create_events.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "create_event_aggregates",
+        sql: include_str!("../../../../migrations/0005_create_event_aggregates.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "channels_insertion_order",
+        sql: include_str!("../../../../migrations/0006_channels_insertion_order.sql"),
+    },
+];
+
+/// The key of the advisory lock, which is held while migrating
+const MIGRATIONS_LOCK_KEY: i64 = 0x5345_4e54_5259;
+
+const CREATE_SCHEMA_MIGRATIONS: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (version BIGINT NOT NULL, name VARCHAR(255) NOT NULL, applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(), PRIMARY KEY (version))";
+
+/// Applies all the `MIGRATIONS` which are not yet recorded in the `schema_migrations` table.
+/// Returns the newly applied migrations.
+///
+/// The migrations are applied on a single connection holding the `MIGRATIONS_LOCK_KEY` advisory lock,
+/// so the concurrently started Sentries wait for each other instead of applying the same migrations.
+/// The `schema_migrations` table is created while holding the lock as well.
+pub async fn migrate(db_pool: DbPool) -> Result<Vec<Migration>, PostgresPersistenceError> {
+    let applied = db_pool
+        .run(|mut conn| {
+            let lock = format!("SELECT pg_advisory_lock({})", MIGRATIONS_LOCK_KEY);
+
+            future_result(conn.batch_execute(&lock), conn)
+                .and_then(|(_, mut conn)| {
+                    future_result(conn.batch_execute(CREATE_SCHEMA_MIGRATIONS), conn)
+                })
+                .and_then(|(_, conn)| apply_pending_migrations(conn))
+                .then(|result| {
+                    let (result, mut conn) = match result {
+                        Ok((applied, conn)) => (Ok(applied), conn),
+                        Err((error, conn)) => (Err(error), conn),
+                    };
+                    let unlock = format!("SELECT pg_advisory_unlock({})", MIGRATIONS_LOCK_KEY);
+
+                    // the lock is released even if a migration failed
+                    future_result(conn.batch_execute(&unlock), conn).then(move |unlocked| {
+                        match (result, unlocked) {
+                            (Ok(applied), Ok((_, conn))) => Ok((applied, conn)),
+                            (Err(error), Ok((_, conn))) | (Err(error), Err((_, conn))) => {
+                                Err((error, conn))
+                            }
+                            (Ok(_), Err((error, conn))) => Err((error, conn)),
+                        }
+                    })
+                })
+        })
+        .map_err(PostgresPersistenceError::from)
+        .compat();
+
+    await!(applied)
+}
+
+fn pending_migrations<'a>(
+    migrations: &'a [Migration],
+    applied_versions: &[i64],
+) -> Vec<&'a Migration> {
+    migrations
+        .iter()
+        .filter(|migration| !applied_versions.contains(&migration.version))
+        .collect()
+}
+
+/// Applies the migrations, which are not recorded in the `schema_migrations` table, one by one
+fn apply_pending_migrations(
+    mut conn: Client,
+) -> impl OldFuture<Item = (Vec<Migration>, Client), Error = (tokio_postgres::Error, Client)> {
+    conn.prepare("SELECT version FROM schema_migrations ORDER BY version ASC")
+        .then(move |res| match res {
+            Ok(stmt) => query_result(conn.query(&stmt, &[]), conn),
+            Err(err) => try_future!(Err((err, conn))),
+        })
+        .and_then(|(rows, conn)| {
+            let versions: Vec<i64> = rows.iter().map(|row| row.get(0)).collect();
+            let pending: Vec<Migration> = pending_migrations(MIGRATIONS, &versions)
+                .into_iter()
+                .cloned()
+                .collect();
+
+            stream::iter_ok(pending).fold(
+                (Vec::new(), conn),
+                |(mut applied, mut conn), migration| {
+                    future_result(conn.batch_execute(&migration.batch()), conn).map(
+                        move |(_, conn)| {
+                            applied.push(migration);
+
+                            (applied, conn)
+                        },
+                    )
+                },
+            )
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn migration_versions_are_unique_and_increasing() {
+        let versions: Vec<i64> = MIGRATIONS.iter().map(|migration| migration.version).collect();
+
+        assert!(!versions.is_empty());
+        assert!(
+            versions.windows(2).all(|pair| pair[0] < pair[1]),
+            "Migration versions should be unique and in increasing order"
+        );
+    }
+
+    #[test]
+    fn pending_migrations_skips_the_applied_ones() {
+        let migrations = [
+            Migration {
+                version: 1,
+                name: "first",
+                sql: "",
+            },
+            Migration {
+                version: 2,
+                name: "second",
+                sql: "",
+            },
+            Migration {
+                version: 3,
+                name: "third",
+                sql: "",
+            },
+        ];
+
+        let pending = pending_migrations(&migrations, &[1, 3]);
+        assert_eq!(vec![&migrations[1]], pending);
+
+        assert_eq!(3, pending_migrations(&migrations, &[]).len());
+        assert!(pending_migrations(&migrations, &[1, 2, 3]).is_empty());
+    }
+
+    #[test]
+    fn migration_batch_records_the_migration() {
+        let migration = Migration {
+            version: 4,
+            name: "create_events",
+            sql: "CREATE TABLE events (id BIGSERIAL NOT NULL);",
+        };
+
+        assert_eq!(
+            "CREATE TABLE events (id BIGSERIAL NOT NULL);\nINSERT INTO schema_migrations (version, name) VALUES (4, 'create_events');",
+            migration.batch()
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub use self::memory::MemoryRateLimiter;
+
+pub mod memory;
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Duration, Utc};
+use futures::future::{ready, FutureExt};
+
+use domain::RepositoryFuture;
+
+use crate::domain::rate_limit::RateLimiter;
+use crate::infrastructure::persistence::memory::MemoryPersistenceError;
+
+#[cfg(test)]
+#[path = "./memory_test.rs"]
+mod memory_test;
+
+/// Keeps the acquired keys in memory, so the limits are not shared between Sentry instances.
+/// The expired keys are not removed when acquiring, call `remove_expired()` periodically for that.
+#[derive(Default)]
+pub struct MemoryRateLimiter {
+    /// The time until which every key is acquired
+    acquired_until: RwLock<HashMap<String, DateTime<Utc>>>,
+}
+
+impl MemoryRateLimiter {
+    /// Removes the expired keys, so they don't pile up
+    pub fn remove_expired(&self, now: DateTime<Utc>) -> Result<(), MemoryPersistenceError> {
+        self.acquired_until.write()?.retain(|_, until| *until > now);
+
+        Ok(())
+    }
+
+    fn acquire_at(
+        &self,
+        key: String,
+        time_frame: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<bool, MemoryPersistenceError> {
+        // computed before taking the lock, so an overflow can't poison it
+        let until = now
+            .checked_add_signed(time_frame)
+            .ok_or(MemoryPersistenceError::TimeOverflow)?;
+
+        let mut acquired_until = self.acquired_until.write()?;
+        match acquired_until.get(&key) {
+            Some(acquired) if *acquired > now => Ok(false),
+            _ => {
+                acquired_until.insert(key, until);
+
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl RateLimiter for MemoryRateLimiter {
+    fn acquire(&self, key: String, time_frame: Duration) -> RepositoryFuture<bool> {
+        let result = self
+            .acquire_at(key, time_frame, Utc::now())
+            .map_err(Into::into);
+
+        ready(result).boxed()
+    }
+}
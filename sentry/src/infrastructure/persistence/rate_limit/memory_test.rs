@@ -0,0 +1,79 @@
+use chrono::{Duration, Utc};
+
+use crate::domain::rate_limit::RateLimiter;
+use crate::infrastructure::persistence::memory::MemoryPersistenceError;
+
+use super::MemoryRateLimiter;
+
+#[test]
+fn limits_the_acquired_keys_for_the_time_frame() {
+    let rate_limiter = MemoryRateLimiter::default();
+    let time_frame = Duration::seconds(20);
+    let now = Utc::now();
+
+    let acquire = |key: &str, at| {
+        rate_limiter
+            .acquire_at(key.to_string(), time_frame, at)
+            .expect("Acquiring the key failed")
+    };
+
+    assert!(acquire("channel:127.0.0.1", now));
+    assert!(!acquire("channel:127.0.0.1", now + Duration::seconds(19)));
+    assert!(acquire("channel:127.0.0.2", now + Duration::seconds(19)));
+
+    assert!(acquire("channel:127.0.0.1", now + Duration::seconds(20)));
+}
+
+#[test]
+fn errors_without_poisoning_the_lock_when_the_time_frame_overflows() {
+    let rate_limiter = MemoryRateLimiter::default();
+    let now = Utc::now();
+
+    match rate_limiter.acquire_at("key".to_string(), Duration::max_value(), now) {
+        Err(MemoryPersistenceError::TimeOverflow) => {}
+        result => panic!("Expected a TimeOverflow error, got {:?}", result),
+    }
+
+    let acquired = rate_limiter
+        .acquire_at("key".to_string(), Duration::seconds(20), now)
+        .expect("The lock should not be poisoned");
+    assert!(acquired);
+}
+
+#[test]
+fn removes_only_the_expired_keys() {
+    let rate_limiter = MemoryRateLimiter::default();
+    let now = Utc::now();
+
+    let acquire = |key: &str, time_frame| {
+        rate_limiter
+            .acquire_at(key.to_string(), time_frame, now)
+            .expect("Acquiring the key failed")
+    };
+    assert!(acquire("short", Duration::seconds(10)));
+    assert!(acquire("long", Duration::seconds(30)));
+
+    rate_limiter
+        .remove_expired(now + Duration::seconds(20))
+        .expect("Removing the expired keys failed");
+
+    let acquired_until = rate_limiter.acquired_until.read().unwrap();
+    assert!(!acquired_until.contains_key("short"));
+    assert!(acquired_until.contains_key("long"));
+}
+
+#[test]
+fn acquires_with_the_current_time() {
+    futures::executor::block_on(async {
+        let rate_limiter = MemoryRateLimiter::default();
+        let time_frame = Duration::seconds(20);
+
+        let acquired = await!(rate_limiter.acquire("key".to_string(), time_frame))
+            .expect("Acquiring the key failed");
+        assert!(acquired);
+
+        let acquired = await!(rate_limiter.acquire("key".to_string(), time_frame))
+            .expect("Acquiring the key failed");
+        assert!(!acquired);
+    })
+}
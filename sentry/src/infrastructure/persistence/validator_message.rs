@@ -0,0 +1,8 @@
+pub use self::memory::MemoryValidatorMessageRepository;
+pub use self::postgres::PostgresValidatorMessageRepository;
+
+pub mod memory;
+pub mod postgres;
+
+#[cfg(test)]
+mod conformance;
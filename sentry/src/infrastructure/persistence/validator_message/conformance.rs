@@ -0,0 +1,248 @@
+//! Shared test suite, which every `ValidatorMessageRepository` implementation should pass.
+//! Every function expects an empty repository and adds the messages it needs through `add()`.
+use std::convert::TryFrom;
+
+use chrono::{DateTime, Utc};
+use time::Duration;
+
+use domain::fixtures::get_channel_id;
+use domain::validator::message::fixtures::{
+    get_approve_state, get_heartbeat, get_new_state, get_reject_state,
+};
+use domain::validator::message::Message;
+use domain::{BalancesMap, ValidatorId};
+
+use crate::domain::validator_message::{
+    SentryState, ValidatorMessage, ValidatorMessageListParams, ValidatorMessageRepository,
+};
+
+fn get_validator_message(
+    channel: &str,
+    from: &str,
+    received: DateTime<Utc>,
+    msg: Message<SentryState>,
+) -> ValidatorMessage {
+    ValidatorMessage {
+        channel_id: get_channel_id(channel),
+        from: ValidatorId::try_from(from).expect("ValidatorId failed"),
+        received,
+        msg,
+    }
+}
+
+fn reject_state(reason: &str) -> Message<SentryState> {
+    Message::RejectState(get_reject_state(
+        "root".to_string(),
+        Some(reason.to_string()),
+    ))
+}
+
+fn heartbeat(state_root: &str) -> Message<SentryState> {
+    Message::Heartbeat(get_heartbeat(state_root.to_string(), "signature".to_string()))
+}
+
+fn new_state(state_root: &str) -> Message<SentryState> {
+    let signature = format!("{} leader signature", state_root);
+
+    Message::NewState(get_new_state(state_root.to_string(), signature, BalancesMap::default()))
+}
+
+fn approve_state(state_root: &str) -> Message<SentryState> {
+    let signature = format!("{} follower signature", state_root);
+
+    Message::ApproveState(get_approve_state(state_root.to_string(), signature, true))
+}
+
+fn unhealthy_approve_state(state_root: &str) -> Message<SentryState> {
+    let signature = format!("{} follower signature", state_root);
+
+    Message::ApproveState(get_approve_state(state_root.to_string(), signature, false))
+}
+
+fn reasons(messages: Vec<ValidatorMessage>) -> Vec<String> {
+    messages
+        .into_iter()
+        .map(|message| match message.msg {
+            Message::RejectState(reject_state) => reject_state.reason,
+            Message::Heartbeat(heartbeat) => heartbeat.state_root,
+            _ => panic!("Only RejectState and Heartbeat messages are added"),
+        })
+        .collect()
+}
+
+fn list_params(from: Option<&str>, types: Option<&str>, limit: u32) -> ValidatorMessageListParams {
+    ValidatorMessageListParams::new(get_channel_id("channel 1"), from, types, limit)
+        .expect("The params should be valid")
+}
+
+pub(crate) async fn adds_and_lists_the_newest_first<'a>(
+    repository: &'a dyn ValidatorMessageRepository,
+) {
+    let older_received = Utc::now() - Duration::minutes(2);
+    let newer_received = Utc::now() - Duration::minutes(1);
+    let older = vec![
+        get_validator_message("channel 1", "leader", older_received, reject_state("first")),
+        get_validator_message("channel 1", "leader", older_received, reject_state("second")),
+    ];
+    let newer = vec![get_validator_message(
+        "channel 1",
+        "follower",
+        newer_received,
+        reject_state("third"),
+    )];
+
+    await!(repository.add(older)).expect("Adding the messages failed");
+    await!(repository.add(newer)).expect("Adding the messages failed");
+
+    let messages = await!(repository.list(&list_params(None, None, 10)))
+        .expect("Listing the messages failed");
+    // the messages received at the same time keep the reversed order in which they were added
+    assert_eq!(vec!["third", "second", "first"], reasons(messages));
+}
+
+pub(crate) async fn lists_messages_filtered_by_validator_and_types<'a>(
+    repository: &'a dyn ValidatorMessageRepository,
+) {
+    let now = Utc::now();
+    let messages = vec![
+        get_validator_message("channel 1", "leader", now, reject_state("leader reject")),
+        get_validator_message("channel 1", "leader", now, heartbeat("leader heartbeat")),
+        get_validator_message("channel 1", "follower", now, heartbeat("follower heartbeat")),
+        get_validator_message("channel 2", "leader", now, heartbeat("other channel")),
+    ];
+    await!(repository.add(messages)).expect("Adding the messages failed");
+
+    let all = await!(repository.list(&list_params(None, None, 10)))
+        .expect("Listing the messages failed");
+    assert_eq!(
+        vec!["follower heartbeat", "leader heartbeat", "leader reject"],
+        reasons(all)
+    );
+
+    let from_leader = await!(repository.list(&list_params(Some("leader"), None, 10)))
+        .expect("Listing the messages failed");
+    assert_eq!(vec!["leader heartbeat", "leader reject"], reasons(from_leader));
+
+    let heartbeats = await!(repository.list(&list_params(Some("leader"), Some("heartbeat"), 10)))
+        .expect("Listing the messages failed");
+    assert_eq!(vec!["leader heartbeat"], reasons(heartbeats));
+
+    let rejects_and_heartbeats =
+        await!(repository.list(&list_params(None, Some("reject+heartbeat"), 10)))
+            .expect("Listing the messages failed");
+    assert_eq!(3, rejects_and_heartbeats.len());
+
+    let approve_states = await!(repository.list(&list_params(None, Some("approve"), 10)))
+        .expect("Listing the messages failed");
+    assert!(approve_states.is_empty());
+}
+
+pub(crate) async fn lists_messages_up_to_the_limit<'a>(
+    repository: &'a dyn ValidatorMessageRepository,
+) {
+    let now = Utc::now();
+    let messages = (1..=5)
+        .map(|minutes| {
+            let received = now - Duration::minutes(minutes);
+            let msg = reject_state(&minutes.to_string());
+
+            get_validator_message("channel 1", "leader", received, msg)
+        })
+        .collect();
+    await!(repository.add(messages)).expect("Adding the messages failed");
+
+    let latest = await!(repository.list(&list_params(None, None, 2)))
+        .expect("Listing the messages failed");
+    assert_eq!(vec!["1", "2"], reasons(latest));
+}
+
+pub(crate) async fn pairs_the_last_approved_new_state<'a>(
+    repository: &'a dyn ValidatorMessageRepository,
+) {
+    let channel_id = get_channel_id("channel 1");
+    let leader = ValidatorId::try_from("leader").expect("ValidatorId failed");
+    let follower = ValidatorId::try_from("follower").expect("ValidatorId failed");
+
+    let received = |minutes| Utc::now() - Duration::minutes(minutes);
+    let messages = vec![
+        get_validator_message("channel 1", "leader", received(5), new_state("first")),
+        get_validator_message("channel 1", "follower", received(4), approve_state("first")),
+        get_validator_message("channel 1", "leader", received(3), new_state("second")),
+        // only the ApproveState of the follower counts
+        get_validator_message("channel 1", "leader", received(2), approve_state("second")),
+        get_validator_message("channel 2", "follower", received(2), approve_state("second")),
+        get_validator_message("channel 1", "leader", received(1), new_state("third")),
+    ];
+    await!(repository.add(messages)).expect("Adding the messages failed");
+
+    let last_approved = await!(repository.last_approved(&channel_id, &leader, &follower))
+        .expect("Finding the last approved failed")
+        .expect("There should be a last approved NewState");
+
+    assert_eq!("first", last_approved.new_state.state_root);
+    assert_eq!("first leader signature", last_approved.new_state.signature);
+    assert_eq!("first", last_approved.approve_state.state_root);
+    assert_eq!("first follower signature", last_approved.approve_state.signature);
+
+    let approve = vec![get_validator_message(
+        "channel 1",
+        "follower",
+        Utc::now(),
+        approve_state("third"),
+    )];
+    await!(repository.add(approve)).expect("Adding the messages failed");
+
+    let last_approved = await!(repository.last_approved(&channel_id, &leader, &follower))
+        .expect("Finding the last approved failed")
+        .expect("There should be a last approved NewState");
+    assert_eq!("third", last_approved.new_state.state_root);
+}
+
+pub(crate) async fn no_last_approved_without_an_approve_state<'a>(
+    repository: &'a dyn ValidatorMessageRepository,
+) {
+    let channel_id = get_channel_id("channel 1");
+    let leader = ValidatorId::try_from("leader").expect("ValidatorId failed");
+    let follower = ValidatorId::try_from("follower").expect("ValidatorId failed");
+
+    let messages = vec![get_validator_message(
+        "channel 1",
+        "leader",
+        Utc::now(),
+        new_state("first"),
+    )];
+    await!(repository.add(messages)).expect("Adding the messages failed");
+
+    let last_approved = await!(repository.last_approved(&channel_id, &leader, &follower))
+        .expect("Finding the last approved failed");
+    assert!(last_approved.is_none());
+}
+
+pub(crate) async fn skips_the_unhealthy_approvals<'a>(
+    repository: &'a dyn ValidatorMessageRepository,
+) {
+    let channel_id = get_channel_id("channel 1");
+    let leader = ValidatorId::try_from("leader").expect("ValidatorId failed");
+    let follower = ValidatorId::try_from("follower").expect("ValidatorId failed");
+
+    let received = |minutes| Utc::now() - Duration::minutes(minutes);
+    let messages = vec![
+        get_validator_message("channel 1", "leader", received(3), new_state("first")),
+        get_validator_message("channel 1", "follower", received(2), approve_state("first")),
+        get_validator_message("channel 1", "leader", received(1), new_state("second")),
+        get_validator_message(
+            "channel 1",
+            "follower",
+            Utc::now(),
+            unhealthy_approve_state("second"),
+        ),
+    ];
+    await!(repository.add(messages)).expect("Adding the messages failed");
+
+    let last_approved = await!(repository.last_approved(&channel_id, &leader, &follower))
+        .expect("Finding the last approved failed")
+        .expect("There should be a last approved NewState");
+
+    assert_eq!("first", last_approved.new_state.state_root);
+    assert!(last_approved.approve_state.is_healthy);
+}
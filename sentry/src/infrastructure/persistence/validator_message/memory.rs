@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use futures::future::{ready, FutureExt};
+
+use domain::validator::message::Message;
+use domain::{ChannelId, RepositoryFuture, ValidatorId};
+use memory_repository::MemoryRepository;
+
+use crate::domain::validator_message::{
+    LastApproved, ValidatorMessage, ValidatorMessageListParams, ValidatorMessageRepository,
+};
+
+#[cfg(test)]
+#[path = "./memory_test.rs"]
+mod memory_test;
+
+pub struct MemoryValidatorMessageRepository {
+    inner: MemoryRepository<ValidatorMessage, ChannelId>,
+}
+
+impl MemoryValidatorMessageRepository {
+    pub fn new(initial_messages: Option<&[ValidatorMessage]>) -> Self {
+        let initial_messages = initial_messages.unwrap_or(&[]).to_vec();
+        // the messages don't have an id of their own, they are keyed by their channel
+        // and added with `extend`, which doesn't check for the existing ones
+        let cmp: Arc<dyn Fn(&ValidatorMessage, &ChannelId) -> bool + Send + Sync> =
+            Arc::new(|message, channel_id| &message.channel_id == channel_id);
+
+        Self {
+            inner: MemoryRepository::new(&initial_messages, cmp),
+        }
+    }
+}
+
+impl ValidatorMessageRepository for MemoryValidatorMessageRepository {
+    fn list(&self, params: &ValidatorMessageListParams) -> RepositoryFuture<Vec<ValidatorMessage>> {
+        let result = self
+            .inner
+            .list_all(|message| list_filter(&params, message))
+            .map(|mut messages| {
+                sort_newest_first(&mut messages);
+                messages.truncate(params.limit as usize);
+
+                messages
+            })
+            .map_err(Into::into);
+
+        ready(result).boxed()
+    }
+
+    fn last_approved(
+        &self,
+        channel_id: &ChannelId,
+        leader: &ValidatorId,
+        follower: &ValidatorId,
+    ) -> RepositoryFuture<Option<LastApproved>> {
+        let result = self
+            .inner
+            .find_all(channel_id)
+            .map(|mut messages| {
+                sort_newest_first(&mut messages);
+
+                let approve_states: Vec<_> = messages
+                    .iter()
+                    .filter(|message| &message.from == follower)
+                    .filter_map(|message| match &message.msg {
+                        Message::ApproveState(approve_state) if approve_state.is_healthy => {
+                            Some(approve_state)
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                messages
+                    .iter()
+                    .filter(|message| &message.from == leader)
+                    .filter_map(|message| match &message.msg {
+                        Message::NewState(new_state) => Some(new_state),
+                        _ => None,
+                    })
+                    .find_map(|new_state| {
+                        approve_states
+                            .iter()
+                            .find(|approve_state| approve_state.state_root == new_state.state_root)
+                            .map(|&approve_state| LastApproved {
+                                new_state: new_state.clone(),
+                                approve_state: approve_state.clone(),
+                            })
+                    })
+            })
+            .map_err(Into::into);
+
+        ready(result).boxed()
+    }
+
+    fn add(&self, messages: Vec<ValidatorMessage>) -> RepositoryFuture<()> {
+        let result = self.inner.extend(messages).map_err(Into::into);
+
+        ready(result).boxed()
+    }
+}
+
+/// The messages received at the same time are reversed as well, as the sort is stable
+fn sort_newest_first(messages: &mut Vec<ValidatorMessage>) {
+    messages.reverse();
+    messages.sort_by(|left, right| right.received.cmp(&left.received));
+}
+
+fn list_filter(
+    params: &ValidatorMessageListParams,
+    message: &ValidatorMessage,
+) -> Option<ValidatorMessage> {
+    let channel_filter = message.channel_id == params.channel_id;
+
+    let from_filter = match &params.from {
+        Some(from) => &message.from == from,
+        None => true,
+    };
+
+    let types: Vec<_> = params.types.iter().collect();
+    // if there are no types provided, it should match every type
+    let types_filter = types.is_empty() || message.msg.is_types(&types);
+
+    match (channel_filter, from_filter, types_filter) {
+        (true, true, true) => Some(message.clone()),
+        (_, _, _) => None,
+    }
+}
@@ -0,0 +1,101 @@
+use std::convert::TryFrom;
+
+use chrono::Utc;
+
+use domain::fixtures::get_channel_id;
+use domain::validator::message::fixtures::get_reject_state;
+use domain::validator::message::Message;
+use domain::ValidatorId;
+
+use crate::domain::validator_message::{ValidatorMessage, ValidatorMessageRepository};
+
+use super::MemoryValidatorMessageRepository;
+
+fn get_validator_message(channel: &str, from: &str, reason: &str) -> ValidatorMessage {
+    ValidatorMessage {
+        channel_id: get_channel_id(channel),
+        from: ValidatorId::try_from(from).expect("ValidatorId failed"),
+        received: Utc::now(),
+        msg: Message::RejectState(get_reject_state(
+            "root".to_string(),
+            Some(reason.to_string()),
+        )),
+    }
+}
+
+#[test]
+fn adds_all_the_messages_in_order() {
+    futures::executor::block_on(async {
+        let initial = [get_validator_message("channel 1", "leader", "initial")];
+        let repository = MemoryValidatorMessageRepository::new(Some(&initial));
+
+        let messages = vec![
+            get_validator_message("channel 1", "follower", "first"),
+            get_validator_message("channel 2", "leader", "second"),
+        ];
+        await!(repository.add(messages)).expect("Adding the messages failed");
+
+        let reasons: Vec<String> = repository
+            .inner
+            .list_all(|message| Some(message.clone()))
+            .expect("Listing the messages failed")
+            .into_iter()
+            .map(|message| match message.msg {
+                Message::RejectState(reject_state) => reject_state.reason,
+                _ => panic!("Only RejectState messages were added"),
+            })
+            .collect();
+
+        assert_eq!(vec!["initial", "first", "second"], reasons);
+    })
+}
+
+mod conformance {
+    use futures::executor::block_on;
+
+    use crate::infrastructure::persistence::validator_message::conformance;
+
+    use super::MemoryValidatorMessageRepository;
+
+    #[test]
+    fn adds_and_lists_the_newest_first() {
+        block_on(conformance::adds_and_lists_the_newest_first(
+            &MemoryValidatorMessageRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_messages_filtered_by_validator_and_types() {
+        block_on(conformance::lists_messages_filtered_by_validator_and_types(
+            &MemoryValidatorMessageRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn lists_messages_up_to_the_limit() {
+        block_on(conformance::lists_messages_up_to_the_limit(
+            &MemoryValidatorMessageRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn pairs_the_last_approved_new_state() {
+        block_on(conformance::pairs_the_last_approved_new_state(
+            &MemoryValidatorMessageRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn no_last_approved_without_an_approve_state() {
+        block_on(conformance::no_last_approved_without_an_approve_state(
+            &MemoryValidatorMessageRepository::new(None),
+        ))
+    }
+
+    #[test]
+    fn skips_the_unhealthy_approvals() {
+        block_on(conformance::skips_the_unhealthy_approvals(
+            &MemoryValidatorMessageRepository::new(None),
+        ))
+    }
+}
@@ -0,0 +1,152 @@
+use std::convert::TryFrom;
+
+use futures::compat::Future01CompatExt;
+use futures::future::FutureExt;
+use futures_legacy::Future as OldFuture;
+use tokio_postgres::types::Json;
+use tokio_postgres::Row;
+
+use domain::validator::message::{ApproveState, Message, NewState};
+use domain::{ChannelId, RepositoryFuture, ValidatorId};
+use try_future::try_future;
+
+use crate::domain::validator_message::{
+    LastApproved, SentryState, ValidatorMessage, ValidatorMessageListParams,
+    ValidatorMessageRepository,
+};
+use crate::infrastructure::field::channel_id::ChannelIdPg;
+use crate::infrastructure::persistence::postgres::PostgresPersistenceError;
+use crate::infrastructure::persistence::DbPool;
+use crate::infrastructure::util::bb8::{execute_result, query_result};
+
+#[cfg(test)]
+#[path = "./postgres_test.rs"]
+mod postgres_test;
+
+/// Inserts all the messages of the JSON array with a single statement, so either all of them
+/// or none of them are added.
+const INSERT_MESSAGES: &str = r#"INSERT INTO validator_messages (channel_id, "from", received, msg) SELECT "channelId", "from", received, msg FROM JSONB_TO_RECORDSET($1::JSONB) AS messages("channelId" VARCHAR(66), "from" VARCHAR(255), received TIMESTAMP WITH TIME ZONE, msg JSONB)"#;
+
+/// The optional filters are skipped when `NULL` or empty, the messages received at the same time
+/// are ordered by their insertion, the newest first.
+const LIST_MESSAGES: &str = r#"SELECT channel_id, "from", received, msg FROM validator_messages WHERE channel_id = $1 AND ($2::VARCHAR IS NULL OR "from" = $2::VARCHAR) AND (CARDINALITY($3::VARCHAR[]) = 0 OR msg->>'type' = ANY($3::VARCHAR[])) ORDER BY received DESC, id DESC LIMIT $4"#;
+
+/// Joins the `NewState` messages of the leader with the `ApproveState` messages of the follower
+/// by their `stateRoot`, the latest `NewState` first.
+const LAST_APPROVED: &str = r#"SELECT new_state.msg AS new_state, approve_state.msg AS approve_state FROM validator_messages AS new_state INNER JOIN validator_messages AS approve_state ON approve_state.channel_id = new_state.channel_id AND approve_state.msg->>'stateRoot' = new_state.msg->>'stateRoot' WHERE new_state.channel_id = $1 AND new_state."from" = $2 AND new_state.msg->>'type' = 'NewState' AND approve_state."from" = $3 AND approve_state.msg->>'type' = 'ApproveState' AND approve_state.msg->>'isHealthy' = 'true' ORDER BY new_state.received DESC, new_state.id DESC, approve_state.received DESC, approve_state.id DESC LIMIT 1"#;
+
+#[derive(Debug)]
+pub struct PostgresValidatorMessageRepository {
+    db_pool: DbPool,
+}
+
+impl PostgresValidatorMessageRepository {
+    pub fn new(db_pool: DbPool) -> Self {
+        Self { db_pool }
+    }
+}
+
+impl ValidatorMessageRepository for PostgresValidatorMessageRepository {
+    fn list(&self, params: &ValidatorMessageListParams) -> RepositoryFuture<Vec<ValidatorMessage>> {
+        let channel_id = ChannelIdPg::from(params.channel_id);
+        let from: Option<String> = params.from.clone().map(Into::into);
+        let types: Vec<&str> = params
+            .types
+            .iter()
+            .map(|message_type| message_type.message_tag())
+            .collect();
+        let limit = i64::from(params.limit);
+
+        let fut = self
+            .db_pool
+            .run(move |mut conn| {
+                conn.prepare(LIST_MESSAGES)
+                    .then(move |res| match res {
+                        Ok(stmt) => query_result(
+                            conn.query(&stmt, &[&channel_id, &from, &types, &limit]),
+                            conn,
+                        ),
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(rows, conn)| {
+                        let messages = rows.iter().map(validator_message_map).collect();
+
+                        Ok((messages, conn))
+                    })
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+
+    fn last_approved(
+        &self,
+        channel_id: &ChannelId,
+        leader: &ValidatorId,
+        follower: &ValidatorId,
+    ) -> RepositoryFuture<Option<LastApproved>> {
+        let channel_id = ChannelIdPg::from(*channel_id);
+        let leader = leader.to_string();
+        let follower = follower.to_string();
+
+        let fut = self
+            .db_pool
+            .run(move |mut conn| {
+                conn.prepare(LAST_APPROVED)
+                    .then(move |res| match res {
+                        Ok(stmt) => query_result(
+                            conn.query(&stmt, &[&channel_id, &leader, &follower]),
+                            conn,
+                        ),
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(rows, conn)| {
+                        let last_approved = rows.get(0).map(last_approved_map);
+
+                        Ok((last_approved, conn))
+                    })
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+
+    fn add(&self, messages: Vec<ValidatorMessage>) -> RepositoryFuture<()> {
+        let fut = self
+            .db_pool
+            .run(move |mut conn| {
+                conn.prepare(INSERT_MESSAGES)
+                    .then(move |res| match res {
+                        Ok(stmt) => execute_result(conn.execute(&stmt, &[&Json(&messages)]), conn),
+                        Err(err) => try_future!(Err((err, conn))),
+                    })
+                    .and_then(|(_, conn)| Ok(((), conn)))
+            })
+            .map_err(|err| PostgresPersistenceError::from(err).into());
+
+        fut.compat().boxed()
+    }
+}
+
+fn validator_message_map(row: &Row) -> ValidatorMessage {
+    let from = ValidatorId::try_from(row.get::<_, &str>("from"))
+        .expect("The stored validator id should be valid");
+
+    ValidatorMessage {
+        channel_id: row.get::<_, ChannelIdPg>("channel_id").into(),
+        from,
+        received: row.get("received"),
+        msg: row.get::<_, Json<Message<SentryState>>>("msg").0,
+    }
+}
+
+/// The `type` tag of the messages is ignored, as the query only returns the matching types
+fn last_approved_map(row: &Row) -> LastApproved {
+    let new_state: Json<NewState<SentryState>> = row.get("new_state");
+    let approve_state: Json<ApproveState<SentryState>> = row.get("approve_state");
+
+    LastApproved {
+        new_state: new_state.0,
+        approve_state: approve_state.0,
+    }
+}
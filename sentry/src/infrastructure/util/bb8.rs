@@ -1,7 +1,7 @@
 use futures_legacy::future::IntoFuture;
 use futures_legacy::stream::Stream;
 use futures_legacy::Future;
-use tokio_postgres::impls::Query;
+use tokio_postgres::impls::{Execute, Query};
 use tokio_postgres::{Client, Row};
 use try_future::TryFuture;
 
@@ -19,3 +19,32 @@ pub(crate) fn query_result(
         .into_future()
         .into()
 }
+
+pub(crate) fn execute_result(
+    execute: Execute,
+    client: Client,
+) -> TryFuture<impl Future<Item = (u64, Client), Error = (tokio_postgres::Error, Client)>> {
+    execute
+        .then(|res| match res {
+            Ok(affected_rows) => Ok((affected_rows, client)),
+            Err(err) => Err((err, client)),
+        })
+        .into_future()
+        .into()
+}
+
+pub(crate) fn future_result<F>(
+    future: F,
+    client: Client,
+) -> TryFuture<impl Future<Item = (F::Item, Client), Error = (tokio_postgres::Error, Client)>>
+where
+    F: Future<Error = tokio_postgres::Error>,
+{
+    future
+        .then(|res| match res {
+            Ok(item) => Ok((item, client)),
+            Err(err) => Err((err, client)),
+        })
+        .into_future()
+        .into()
+}
@@ -1,106 +1,347 @@
 #![feature(async_await, await_macro)]
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::env::Vars;
-use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use chrono::Utc;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use futures::compat::Future01CompatExt;
 use futures::future::{FutureExt, TryFutureExt};
 use tokio::await;
+use tokio::timer::Delay;
 use tokio_tcp::TcpListener;
 use tower_web::ServiceBuilder;
 
+use adapter::dummy::{DummyAdapter, DummyParticipant};
+#[cfg(feature = "ethereum-adapter")]
+use adapter::ethereum::EthereumAdapter;
+use adapter::ConfigBuilder;
 use domain::DomainError;
-use lazy_static::lazy_static;
+use sentry::application::config::{AdapterKind, AdapterSettings, Config, PartialConfig, Setting};
+use sentry::application::event_aggregator::EventAggregator;
+use sentry::application::middleware::auth::AuthMiddleware;
 use sentry::application::resource::channel::ChannelResource;
-use sentry::infrastructure::persistence::channel::{
-    MemoryChannelRepository, PostgresChannelRepository,
-};
-use sentry::infrastructure::persistence::DbPool;
-use std::sync::Arc;
+use sentry::application::resource::event::EventResource;
+use sentry::application::resource::validator_message::ValidatorMessageResource;
+use sentry::domain::adapter::SentryAdapter;
+use sentry::domain::rate_limit::RateLimiter;
+use sentry::infrastructure::persistence::postgres::migrations::migrate;
+use sentry::infrastructure::persistence::rate_limit::MemoryRateLimiter;
+use sentry::infrastructure::persistence::{database_pool, DbPool, Persistence, Storage};
 
-const DEFAULT_PORT: u16 = 8005;
+/// How often the expired keys of the rate limiter are removed
+const RATE_LIMIT_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
 
-lazy_static! {
-    static ref CONFIG: Config = {
-        dotenv::dotenv().ok();
-        Config::try_from(std::env::vars()).expect("Config failed")
-    };
+pub fn main() {
+    let matches = App::new("Sentry")
+        .version("0.1")
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .help("TOML config file, overridden by the env. variables and the CLI arguments")
+                .takes_value(true),
+        )
+        .arg(setting_arg(Setting::Port, "port", "The port to listen on"))
+        .arg(setting_arg(
+            Setting::BindAddress,
+            "bind-address",
+            "The IP address to listen on",
+        ))
+        .arg(setting_arg(
+            Setting::DatabaseUrl,
+            "database-url",
+            "The url of the Postgres database",
+        ))
+        .arg(setting_arg(
+            Setting::DatabasePoolSize,
+            "database-pool-size",
+            "The max connections of the database pool",
+        ))
+        .arg(setting_arg(
+            Setting::ChannelListLimit,
+            "channel-list-limit",
+            "The channels per page of `/channel/list`",
+        ))
+        .arg(setting_arg(
+            Setting::EventAggregateInterval,
+            "event-aggregate-interval",
+            "How often (in milliseconds) the submitted events are folded into aggregates",
+        ))
+        .arg(
+            setting_arg(
+                Setting::Storage,
+                "storage",
+                "The storage for the repositories, `postgres` requires a database url",
+            )
+            .possible_values(&Storage::VALUES),
+        )
+        .arg(
+            setting_arg(
+                Setting::Adapter,
+                "adapter",
+                "The adapter used for signing and verifying",
+            )
+            .possible_values(&AdapterKind::VALUES),
+        )
+        .arg(setting_arg(
+            Setting::Identity,
+            "identity",
+            "The identity used with the adapter",
+        ))
+        .arg(setting_arg(
+            Setting::Keystore,
+            "keystore",
+            "The JSON keystore of the validator key, required for the `ethereum` adapter",
+        ))
+        .arg(setting_arg(
+            Setting::CoreAddress,
+            "core-address",
+            "The address of the AdEx Core contract, the created channel ids are checked against it",
+        ))
+        .arg(setting_arg(
+            Setting::TrustedProxies,
+            "trusted-proxies",
+            "Comma separated IP addresses of the proxies, which are trusted to set `X-Forwarded-For`",
+        ))
+        .subcommand(
+            SubCommand::with_name("migrate")
+                .about("Applies the pending database migrations and exits"),
+        )
+        .get_matches();
+
+    let config = load_config(&matches).unwrap_or_else(|error| {
+        eprintln!("Configuration error: {}", error);
+        std::process::exit(1)
+    });
+
+    if matches.subcommand_matches("migrate").is_some() {
+        if config.database_url.is_none() {
+            eprintln!("Configuration error: The database url is required for migrating");
+            std::process::exit(1)
+        }
+
+        tokio::run(migrate_database(config).unit_error().boxed().compat());
+
+        return;
+    }
+
+    let adapter_settings = config.adapter_settings().unwrap_or_else(|error| {
+        eprintln!("Configuration error: {}", error);
+        std::process::exit(1)
+    });
+
+    println!("Listening on http://{}", config.socket_address());
+    if config.core_address.is_none() {
+        println!(
+            "Warning: The core address is not set, the ids of the created channels are not checked"
+        );
+    }
+
+    tokio::run(
+        bootstrap(config, adapter_settings)
+            .unit_error()
+            .boxed()
+            .compat(),
+    )
 }
 
-pub fn main() {
-    // @TODO: Define and use a CLI for setting sentry options
+fn setting_arg<'a>(setting: Setting, long: &'a str, help: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(setting.name())
+        .long(long)
+        .help(help)
+        .takes_value(true)
+}
 
-    let port: u16 = std::env::var("PORT")
-        .unwrap_or_else(|_| format!("{}", DEFAULT_PORT))
-        .parse()
-        .expect("Failed to parse port");
-    let database_url = std::env::var("DATABASE_URL").expect("Missing DATABASE_URL");
+/// Layers the config file, the env. variables (including the `.env` file) and the CLI arguments
+fn load_config(matches: &ArgMatches<'_>) -> Result<Config, DomainError> {
+    dotenv::dotenv().ok();
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let file_config = match matches.value_of("config") {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).map_err(|error| {
+                DomainError::InvalidArgument(format!("Reading config file '{}': {}", path, error))
+            })?;
 
-    println!("Listening on http://{}", addr);
+            PartialConfig::from_toml(&content)?
+        }
+        None => PartialConfig::default(),
+    };
+    let env_config = PartialConfig::from_env(std::env::vars())?;
+    let cli_config = PartialConfig::from_values(
+        |setting| matches.value_of(setting.name()),
+        |setting| format!("--{}", setting.name().replace('_', "-")),
+    )?;
 
-    tokio::run(bootstrap(database_url, addr).unit_error().boxed().compat())
+    Config::try_from(file_config.merge(env_config).merge(cli_config))
 }
 
-async fn bootstrap(database_url: String, addr: SocketAddr) {
+async fn bootstrap(config: Config, adapter_settings: AdapterSettings) {
     // @TODO: Error handling
-    let db_pool = await!(database_pool(database_url)).expect("Database connection failed");
+    let persistence = match config.storage {
+        Storage::Memory => Persistence::Memory,
+        Storage::Postgres => {
+            let db_pool = await!(connect_database(&config));
+            await!(run_migrations(db_pool.clone()));
+
+            Persistence::Postgres(db_pool)
+        }
+    };
 
-    let listener = TcpListener::bind(&addr).expect("Wrong address provided");
+    let listener = TcpListener::bind(&config.socket_address()).expect("Wrong address provided");
 
-    let channel_repository = Arc::new(MemoryChannelRepository::new(None));
-    let _channel_repository = Arc::new(PostgresChannelRepository::new(db_pool.clone()));
+    let channel_repository = persistence.channel_repository();
+    let event_aggregate_repository = persistence.event_aggregate_repository();
+    let event_repository = persistence.event_repository(event_aggregate_repository.clone());
+    let validator_message_repository = persistence.validator_message_repository();
+    let memory_rate_limiter = Arc::new(MemoryRateLimiter::default());
+    let rate_limiter: Arc<dyn RateLimiter> = memory_rate_limiter.clone();
+    let adapter = adapter(&adapter_settings);
+
+    let event_aggregator =
+        EventAggregator::new(channel_repository.clone(), event_repository.clone());
+    let aggregate_interval = Duration::from_millis(config.event_aggregate_interval);
+    tokio::spawn(
+        aggregate_events(event_aggregator, aggregate_interval)
+            .unit_error()
+            .boxed()
+            .compat(),
+    );
+
+    tokio::spawn(
+        remove_expired_rate_limits(memory_rate_limiter, RATE_LIMIT_CLEANUP_INTERVAL)
+            .unit_error()
+            .boxed()
+            .compat(),
+    );
 
     // A service builder is used to configure our service.
     let server = ServiceBuilder::new()
         .resource(ChannelResource {
-            channel_list_limit: CONFIG.channel_list_limit,
+            channel_list_limit: config.channel_list_limit,
             channel_repository: channel_repository.clone(),
+            validator_message_repository: validator_message_repository.clone(),
+            adapter: adapter.clone(),
+            core_address: config.core_address.clone(),
+        })
+        .resource(EventResource {
+            channel_repository: channel_repository.clone(),
+            event_repository,
+            event_aggregate_repository,
+            rate_limiter,
+            trusted_proxies: config.trusted_proxies.clone(),
+        })
+        .resource(ValidatorMessageResource {
+            channel_repository,
+            validator_message_repository,
         })
+        .middleware(AuthMiddleware::new(adapter))
         .serve(listener.incoming());
 
     await!(server).expect("Server error");
 }
 
-async fn database_pool(database_url: String) -> Result<DbPool, tokio_postgres::Error> {
-    let postgres_connection =
-        bb8_postgres::PostgresConnectionManager::new(database_url, tokio_postgres::NoTls);
+/// Folds the submitted events into aggregates every `interval`, while the server is running
+async fn aggregate_events(event_aggregator: EventAggregator, interval: Duration) {
+    loop {
+        if let Err(error) = await!(Delay::new(Instant::now() + interval).compat()) {
+            eprintln!("The event aggregation timer failed: {}", error);
+            return;
+        }
 
-    await!(bb8::Pool::builder().build(postgres_connection).compat())
+        if let Err(error) = await!(event_aggregator.aggregate(Utc::now())) {
+            eprintln!("Aggregating the events failed: {:?}", error);
+        }
+    }
+}
+
+/// Removes the expired rate limit keys every `interval`, while the server is running
+async fn remove_expired_rate_limits(rate_limiter: Arc<MemoryRateLimiter>, interval: Duration) {
+    loop {
+        if let Err(error) = await!(Delay::new(Instant::now() + interval).compat()) {
+            eprintln!("The rate limit cleanup timer failed: {}", error);
+            return;
+        }
+
+        if let Err(error) = rate_limiter.remove_expired(Utc::now()) {
+            eprintln!("Removing the expired rate limits failed: {}", error);
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Config {
-    pub channel_list_limit: u32,
+fn adapter(settings: &AdapterSettings) -> Arc<dyn SentryAdapter> {
+    match settings.adapter {
+        AdapterKind::Dummy => Arc::new(DummyAdapter {
+            config: ConfigBuilder::new(&settings.identity).build(),
+            participants: dummy_participants(),
+        }),
+        #[cfg(feature = "ethereum-adapter")]
+        AdapterKind::Ethereum => Arc::new(ethereum_adapter(settings)),
+    }
+}
+
+/// Decrypts the keystore, which should be of the configured identity
+#[cfg(feature = "ethereum-adapter")]
+fn ethereum_adapter(settings: &AdapterSettings) -> EthereumAdapter {
+    let (keystore, password) = settings
+        .keystore
+        .as_ref()
+        .expect("The keystore and its password are required");
+
+    let adapter = EthereumAdapter::from_keystore(keystore, password).unwrap_or_else(|error| {
+        eprintln!("Configuration error: {}", error);
+        std::process::exit(1)
+    });
+
+    let address = &adapter.config.identity;
+    if !address.eq_ignore_ascii_case(&settings.identity) {
+        eprintln!(
+            "Configuration error: The identity should be the keystore address {}",
+            address
+        );
+        std::process::exit(1)
+    }
+
+    adapter
 }
 
-impl TryFrom<Vars> for Config {
-    type Error = domain::DomainError;
-
-    fn try_from(mut vars: Vars) -> Result<Self, Self::Error> {
-        let limit = vars
-            .find_map(|(key, value)| {
-                if key == "SENTRY_CHANNEL_LIST_LIMIT" {
-                    Some(value)
-                } else {
-                    None
-                }
-            })
-            .ok_or(DomainError::InvalidArgument(
-                "SENTRY_CHANNEL_LIST_LIMIT evn. variable was not passed".to_string(),
-            ))
-            .and_then(|value| {
-                value.parse::<u32>().map_err(|_| {
-                    DomainError::InvalidArgument(
-                        "SENTRY_CHANNEL_LIST_LIMIT is not a u32 value".to_string(),
-                    )
-                })
-            });
-
-        Ok(Self {
-            channel_list_limit: limit?,
+/// The auth. tokens of the dummy validators, used only for development
+fn dummy_participants() -> HashMap<&'static str, DummyParticipant> {
+    ["awesomeLeader", "awesomeFollower"]
+        .iter()
+        .map(|&identity| {
+            let participant = DummyParticipant {
+                identity: identity.to_string(),
+                token: format!("AUTH_{}", identity),
+            };
+
+            (identity, participant)
         })
+        .collect()
+}
+
+#[allow(clippy::needless_lifetimes)]
+async fn connect_database<'a>(config: &'a Config) -> DbPool {
+    let database_url = config
+        .database_url
+        .clone()
+        .expect("The database url is required");
+
+    await!(database_pool(database_url, config.database_pool_size))
+        .expect("Database connection failed")
+}
+
+async fn migrate_database(config: Config) {
+    let db_pool = await!(connect_database(&config));
+
+    await!(run_migrations(db_pool));
+}
+
+async fn run_migrations(db_pool: DbPool) {
+    let applied = await!(migrate(db_pool)).expect("Database migrations failed");
+
+    for migration in applied {
+        println!("Applied migration {} {}", migration.version, migration.name);
     }
 }
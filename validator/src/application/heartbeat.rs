@@ -3,10 +3,11 @@ use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 
-use adapter::{Adapter, AdapterError, BalanceRoot, ChannelId as AdapterChannelId};
-use domain::validator::message::{Heartbeat, Message, State, TYPE_HEARTBEAT};
-use domain::{Channel, ChannelId, RepositoryError, ValidatorId};
+use adapter::{Adapter, AdapterError};
+use domain::validator::message::{Heartbeat, Message, State, TYPE_ACCOUNTING, TYPE_HEARTBEAT};
+use domain::{BalancesMap, Channel, ChannelId, DomainError, RepositoryError, ValidatorId};
 
+use crate::application::validator::state_root;
 use crate::application::MessagePropagator;
 use crate::domain::MessageRepository;
 
@@ -18,6 +19,7 @@ pub struct HeartbeatFactory<A: Adapter> {
 pub enum HeartbeatError {
     Adapter(AdapterError),
     Repository(RepositoryError),
+    Domain(DomainError),
     /// When the Channel deposit has been exhausted
     ChannelExhausted(ChannelId),
     /// When the required time for the Heartbeat delay hasn't passed
@@ -32,6 +34,7 @@ impl fmt::Display for HeartbeatError {
         match self {
             HeartbeatError::Adapter(error) => write!(f, "Adapter error: {}", error),
             HeartbeatError::Repository(error) => write!(f, "Repository error: {}", error),
+            HeartbeatError::Domain(error) => write!(f, "Domain error: {}", error),
             HeartbeatError::ChannelExhausted(channel_id) => {
                 write!(f, "Channel {} exhausted", channel_id)
             }
@@ -89,12 +92,22 @@ impl<A: Adapter> HeartbeatSender<A> {
         // @TODO: Figure out where the channel `is_exhausted` should be located and handled.
         // check if channel is not exhausted
 
-        let adapter_channel_id = AdapterChannelId(channel.id.bytes);
-        // @TODO: Use the appropriate BalanceRoot
-        let adapter_balance_root = BalanceRoot(*b"12345678901234567890123456789012");
-        let signable_state_root = A::signable_state_root(adapter_channel_id, adapter_balance_root);
+        // sign the balances of our latest Accounting
+        let latest_future =
+            self.message_repository
+                .latest(&channel.id, &validator, Some(&[&TYPE_ACCOUNTING]));
+        let balances = match await!(latest_future).map_err(HeartbeatError::Repository)? {
+            Some(Message::Accounting(accounting)) => accounting.balances,
+            Some(_) => {
+                return Err(HeartbeatError::User(
+                    "The repository returned a non-Accounting message".to_string(),
+                ))
+            }
+            None => BalancesMap::default(),
+        };
+        let state_root = state_root::<A>(&channel, &balances).map_err(HeartbeatError::Domain)?;
         // call the HeartbeatFactory and create the new Heartbeat
-        let heartbeat = await!(self.factory.create(signable_state_root.0))?;
+        let heartbeat = await!(self.factory.create(state_root))?;
 
         // Propagate the message to all Validators
         // @TODO: Log errors
@@ -1,13 +1,15 @@
 use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
 
 use domain::validator::message::{Message, State};
-use domain::{Channel, RepositoryError};
+use domain::{Channel, RepositoryError, ValidatorDesc};
 
+use crate::domain::validator::ValidatorError;
 use crate::domain::MessageRepository;
 
 pub struct MessagePropagator<S: State> {
-    pub message_repository: Box<dyn MessageRepository<S>>,
+    pub message_repository: Arc<dyn MessageRepository<S>>,
 }
 
 #[derive(Debug)]
@@ -44,20 +46,41 @@ impl From<RepositoryError> for PropagationError {
     }
 }
 
+impl From<PropagationError> for ValidatorError {
+    fn from(error: PropagationError) -> Self {
+        match error.kind {
+            PropagationErrorKind::Repository(error) => ValidatorError::Repository(error),
+        }
+    }
+}
+
 impl<S: State> MessagePropagator<S> {
     // @TODO: Make sure we have information for logging the results for particular Validator
     pub async fn propagate<'a>(
         &'a self,
         channel: &'a Channel,
         message: Message<S>,
+    ) -> Vec<Result<(), PropagationError>> {
+        let validators: Vec<_> = channel.spec.validators.into_iter().collect();
+
+        await!(self.propagate_to(channel, &validators, message))
+    }
+
+    /// Propagates the message only to the passed validators of the Channel,
+    /// the results are in the order of the validators
+    pub async fn propagate_to<'a>(
+        &'a self,
+        channel: &'a Channel,
+        validators: &'a [&'a ValidatorDesc],
+        message: Message<S>,
     ) -> Vec<Result<(), PropagationError>> {
         let mut results = Vec::default();
 
-        for validator in channel.spec.validators.into_iter() {
+        for validator in validators {
             let add_result =
                 await!(self
                     .message_repository
-                    .add(&channel.id, &validator, message.clone()))
+                    .add(&channel.id, validator, message.clone()))
                 .map_err(Into::into);
             results.push(add_result);
         }
@@ -68,29 +91,32 @@ impl<S: State> MessagePropagator<S> {
 
 #[cfg(test)]
 mod test {
-    use std::cell::RefCell;
+    use std::sync::{Arc, Mutex};
 
     use futures::future::{ready, FutureExt};
 
     use domain::channel::fixtures::get_channel;
     use domain::validator::message::fixtures::{get_reject_state, DummyState};
-    use domain::validator::message::{Message, MessageType};
+    use domain::validator::message::{Message, MessageType, NewState};
     use domain::{ChannelId, ValidatorDesc, ValidatorId};
     use domain::{RepositoryError, RepositoryFuture};
 
     use crate::application::MessagePropagator;
+    use crate::domain::validator::ValidatorError;
     use crate::domain::MessageRepository;
 
+    use super::PropagationError;
+
     struct MockMessageRepository<I>
     where
         I: Iterator<Item = Result<(), RepositoryError>>,
     {
-        add_results: RefCell<I>,
+        add_results: Mutex<I>,
     }
 
     impl<I> MessageRepository<DummyState> for MockMessageRepository<I>
     where
-        I: Iterator<Item = Result<(), RepositoryError>>,
+        I: Iterator<Item = Result<(), RepositoryError>> + Send,
     {
         fn add(
             &self,
@@ -100,7 +126,8 @@ mod test {
         ) -> RepositoryFuture<()> {
             let result = self
                 .add_results
-                .borrow_mut()
+                .lock()
+                .expect("The add results lock is poisoned")
                 .next()
                 .expect("Whoops, you called add() more than the provided results");
             ready(result).boxed()
@@ -114,6 +141,15 @@ mod test {
         ) -> RepositoryFuture<Option<Message<DummyState>>> {
             unimplemented!("No need for latest in this Mock")
         }
+
+        fn last_approved(
+            &self,
+            _channel: &ChannelId,
+            _leader: &ValidatorId,
+            _follower: &ValidatorId,
+        ) -> RepositoryFuture<Option<NewState<DummyState>>> {
+            unimplemented!("No need for last_approved in this Mock")
+        }
     }
 
     #[test]
@@ -123,13 +159,13 @@ mod test {
 
             let iterator = vec![Ok(()), Err(add_error)].into_iter();
             let message_repository = MockMessageRepository {
-                add_results: RefCell::new(iterator),
+                add_results: Mutex::new(iterator),
             };
             let propagator = MessagePropagator {
-                message_repository: Box::new(message_repository),
+                message_repository: Arc::new(message_repository),
             };
 
-            let message = get_reject_state(None);
+            let message = get_reject_state("root".to_string(), None);
             let channel = get_channel("id", &None, None);
 
             let result = await!(propagator.propagate(&channel, Message::RejectState(message)));
@@ -145,4 +181,14 @@ mod test {
             }
         })
     }
+
+    #[test]
+    fn the_failed_propagation_is_a_repository_error_of_the_validator() {
+        let error = PropagationError::from(RepositoryError::User);
+
+        match ValidatorError::from(error) {
+            ValidatorError::Repository(RepositoryError::User) => {}
+            error => panic!("Expected a Repository error, got {:?}", error),
+        }
+    }
 }
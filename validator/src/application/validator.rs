@@ -1,5 +1,430 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use adapter::{Adapter, BalanceRoot, ChannelId as AdapterChannelId};
+use domain::channel::status::LatestMessages;
+use domain::validator::message::{
+    Accounting, ApproveState, Heartbeat, Message, MessageType, NewState, State, TYPE_ACCOUNTING,
+    TYPE_APPROVE, TYPE_HEARTBEAT, TYPE_NEW,
+};
+use domain::{BalancesMap, Channel, ChannelId, DomainError, ValidatorDesc, ValidatorId};
+
+use crate::application::MessagePropagator;
+use crate::domain::validator::ValidatorError;
+use crate::domain::{EventAggregateRepository, MessageRepository};
+
 pub use self::follower::Follower;
 pub use self::leader::Leader;
 
 pub mod follower;
 pub mod leader;
+
+/// The StateRoot of the Channel balances, which is signed for the `NewState` & `ApproveState`.
+/// The balance root is the root of the balances Merkle tree.
+pub fn state_root<A: Adapter>(
+    channel: &Channel,
+    balances: &BalancesMap,
+) -> Result<<A::State as State>::StateRoot, DomainError> {
+    let adapter_channel_id = AdapterChannelId(channel.id.bytes);
+    let balance_root = BalanceRoot(balances.merkle_tree()?.root());
+
+    Ok(A::signable_state_root(adapter_channel_id, balance_root).0)
+}
+
+/// Folds the event aggregates created since the latest `Accounting` of the validator
+/// into its balances and propagates the new `Accounting`, if there are any such aggregates.
+/// Returns the balances (after fees) of the latest `Accounting`.
+pub async fn produce_accounting<'a, S: State>(
+    identity: &'a ValidatorId,
+    channel: &'a Channel,
+    message_repository: &'a dyn MessageRepository<S>,
+    event_aggregate_repository: &'a dyn EventAggregateRepository,
+    propagator: &'a MessagePropagator<S>,
+) -> Result<BalancesMap, ValidatorError> {
+    let latest_future = message_repository.latest(&channel.id, identity, Some(&[&TYPE_ACCOUNTING]));
+    let accounting = match await!(latest_future).map_err(ValidatorError::Repository)? {
+        Some(Message::Accounting(accounting)) => Some(accounting),
+        Some(_) => {
+            return Err(ValidatorError::User(
+                "The repository returned a non-Accounting message".to_string(),
+            ))
+        }
+        None => None,
+    };
+
+    let after = accounting
+        .as_ref()
+        .map(|accounting| accounting.last_event_aggregate);
+    let aggregates = await!(event_aggregate_repository.list(&channel.id, after))
+        .map_err(ValidatorError::Repository)?;
+
+    let (previous_pre_fees, previous_balances) = match accounting {
+        Some(accounting) => (accounting.pre_fees, accounting.balances),
+        None => (BalancesMap::default(), BalancesMap::default()),
+    };
+    let last_event_aggregate = match aggregates.last() {
+        Some(aggregate) => aggregate.created,
+        None => return Ok(previous_balances),
+    };
+
+    let pre_fees = previous_pre_fees.merge_aggregates(&aggregates, channel);
+    let balances = pre_fees
+        .apply_fees(channel)
+        .map_err(ValidatorError::Domain)?;
+
+    let new_accounting = Accounting {
+        last_event_aggregate,
+        pre_fees,
+        balances: balances.clone(),
+    };
+    await!(propagate(
+        propagator,
+        channel,
+        Message::Accounting(new_accounting)
+    ))?;
+
+    Ok(balances)
+}
+
+/// Propagates the message to all the Channel validators, then returns the first failure if any,
+/// so the tick fails and the message is produced again on the next one
+pub async fn propagate<'a, S: State>(
+    propagator: &'a MessagePropagator<S>,
+    channel: &'a Channel,
+    message: Message<S>,
+) -> Result<(), ValidatorError> {
+    for result in await!(propagator.propagate(channel, message)) {
+        result?;
+    }
+
+    Ok(())
+}
+
+/// Tracks the validators, which the latest `NewState`, `ApproveState` or `RejectState`
+/// of every Channel failed to reach, by the `state_root` of the message.
+/// Our own Sentry only shows that we sent the message, not whether the others received it.
+pub struct Undelivered<S: State> {
+    channels: Mutex<HashMap<ChannelId, (S::StateRoot, Vec<ValidatorId>)>>,
+}
+
+// `#[derive(Default)]` would require the State to be `Default` as well
+impl<S: State> Default for Undelivered<S> {
+    fn default() -> Self {
+        Self {
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S: State> Undelivered<S> {
+    /// The validators, which the message of the `state_root` should be propagated again to.
+    /// A message which isn't tracked, e.g. after a restart, is propagated again
+    /// to all the Channel validators except us.
+    pub fn validators<'a>(
+        &self,
+        channel: &'a Channel,
+        state_root: &S::StateRoot,
+        identity: &ValidatorId,
+    ) -> Vec<&'a ValidatorDesc> {
+        // the map is consistent even if another tick panicked while holding the lock
+        let channels = self.channels.lock().unwrap_or_else(PoisonError::into_inner);
+        let tracked = channels
+            .get(&channel.id)
+            .filter(|(tracked_root, _)| tracked_root == state_root);
+
+        channel
+            .spec
+            .validators
+            .into_iter()
+            .filter(|validator| match tracked {
+                Some((_, undelivered)) => undelivered.contains(&validator.id),
+                None => &validator.id != identity,
+            })
+            .collect()
+    }
+
+    fn track(&self, channel: &ChannelId, state_root: S::StateRoot, undelivered: Vec<ValidatorId>) {
+        self.channels
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(*channel, (state_root, undelivered));
+    }
+}
+
+/// Propagates the message of the `state_root` to the validators and tracks the ones
+/// it failed to reach, then returns the first failure if any, so the tick fails
+pub async fn propagate_tracked<'a, S: State>(
+    propagator: &'a MessagePropagator<S>,
+    undelivered: &'a Undelivered<S>,
+    channel: &'a Channel,
+    validators: &'a [&'a ValidatorDesc],
+    state_root: S::StateRoot,
+    message: Message<S>,
+) -> Result<(), ValidatorError> {
+    let results = await!(propagator.propagate_to(channel, validators, message));
+
+    let failed = validators
+        .iter()
+        .zip(results.iter())
+        .filter(|(_, result)| result.is_err())
+        .map(|(validator, _)| validator.id.clone())
+        .collect();
+    undelivered.track(&channel.id, state_root, failed);
+
+    for result in results {
+        result?;
+    }
+
+    Ok(())
+}
+
+/// The `state_root` of the latest `ApproveState` or `RejectState` of a validator
+pub fn responded_root<S: State>(
+    response: Option<&Message<S>>,
+) -> Result<Option<&S::StateRoot>, ValidatorError> {
+    match response {
+        Some(Message::ApproveState(approve_state)) => Ok(Some(&approve_state.state_root)),
+        Some(Message::RejectState(reject_state)) => Ok(Some(&reject_state.state_root)),
+        None => Ok(None),
+        Some(_) => Err(ValidatorError::User(
+            "The repository returned a message of an unexpected type".to_string(),
+        )),
+    }
+}
+
+/// The latest `Heartbeat`s of the Channel validators, the latest `NewState` of the leader
+/// and the latest `ApproveState` of the follower, which the Channel status is computed from
+pub async fn latest_messages<'a, S: State>(
+    channel: &'a Channel,
+    message_repository: &'a dyn MessageRepository<S>,
+) -> Result<LatestMessages, ValidatorError> {
+    let leader = &channel.spec.validators.leader().id;
+    let follower = &channel.spec.validators.follower().id;
+    let latest = |from: &ValidatorId, message_type: &MessageType| {
+        message_repository.latest(&channel.id, from, Some(&[message_type]))
+    };
+
+    let leader_heartbeat =
+        await!(latest(leader, &TYPE_HEARTBEAT)).map_err(ValidatorError::Repository)?;
+    let follower_heartbeat =
+        await!(latest(follower, &TYPE_HEARTBEAT)).map_err(ValidatorError::Repository)?;
+    let new_state = await!(latest(leader, &TYPE_NEW)).map_err(ValidatorError::Repository)?;
+    let approve_state =
+        await!(latest(follower, &TYPE_APPROVE)).map_err(ValidatorError::Repository)?;
+
+    Ok(LatestMessages::default()
+        .with_heartbeats(
+            heartbeat_of(leader_heartbeat).as_ref(),
+            heartbeat_of(follower_heartbeat).as_ref(),
+        )
+        .with_new_state(new_state_of(new_state).as_ref())
+        .with_approve_state(approve_state_of(approve_state).as_ref()))
+}
+
+fn heartbeat_of<S: State>(message: Option<Message<S>>) -> Option<Heartbeat<S>> {
+    match message {
+        Some(Message::Heartbeat(heartbeat)) => Some(heartbeat),
+        _ => None,
+    }
+}
+
+fn new_state_of<S: State>(message: Option<Message<S>>) -> Option<NewState<S>> {
+    match message {
+        Some(Message::NewState(new_state)) => Some(new_state),
+        _ => None,
+    }
+}
+
+fn approve_state_of<S: State>(message: Option<Message<S>>) -> Option<ApproveState<S>> {
+    match message {
+        Some(Message::ApproveState(approve_state)) => Some(approve_state),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::{ready, FutureExt};
+
+    use domain::validator::message::{Message, MessageType, NewState, State};
+    use domain::{ChannelId, RepositoryError, RepositoryFuture, ValidatorDesc, ValidatorId};
+
+    use crate::domain::MessageRepository;
+
+    /// Fails adding the messages to the `unreachable` validators and keeps which validators
+    /// the other messages were added to, otherwise it calls the inner repository
+    pub struct UnreachableMessageRepository<S: State> {
+        pub inner: Arc<dyn MessageRepository<S>>,
+        pub unreachable: Mutex<Vec<ValidatorId>>,
+        pub added: Mutex<Vec<(ValidatorId, Message<S>)>>,
+    }
+
+    impl<S: State> UnreachableMessageRepository<S> {
+        pub fn new(inner: Arc<dyn MessageRepository<S>>, unreachable: &[&ValidatorId]) -> Self {
+            Self {
+                inner,
+                unreachable: Mutex::new(unreachable.iter().map(|&id| id.clone()).collect()),
+                added: Mutex::new(Vec::new()),
+            }
+        }
+
+        pub fn set_unreachable(&self, unreachable: &[&ValidatorId]) {
+            *self.unreachable.lock().unwrap() = unreachable.iter().map(|&id| id.clone()).collect();
+        }
+
+        /// The messages added to the validator
+        pub fn added_to(&self, validator: &ValidatorId) -> Vec<Message<S>> {
+            self.added
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(to, _)| to == validator)
+                .map(|(_, message)| message.clone())
+                .collect()
+        }
+    }
+
+    impl<S: State> MessageRepository<S> for UnreachableMessageRepository<S> {
+        fn add(
+            &self,
+            for_channel: &ChannelId,
+            to_validator: &ValidatorDesc,
+            message: Message<S>,
+        ) -> RepositoryFuture<()> {
+            if self.unreachable.lock().unwrap().contains(&to_validator.id) {
+                return ready(Err(RepositoryError::User)).boxed();
+            }
+
+            self.added
+                .lock()
+                .unwrap()
+                .push((to_validator.id.clone(), message.clone()));
+
+            self.inner.add(for_channel, to_validator, message)
+        }
+
+        fn latest(
+            &self,
+            channel: &ChannelId,
+            from: &ValidatorId,
+            types: Option<&[&MessageType]>,
+        ) -> RepositoryFuture<Option<Message<S>>> {
+            self.inner.latest(channel, from, types)
+        }
+
+        fn last_approved(
+            &self,
+            channel: &ChannelId,
+            leader: &ValidatorId,
+            follower: &ValidatorId,
+        ) -> RepositoryFuture<Option<NewState<S>>> {
+            self.inner.last_approved(channel, leader, follower)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use adapter::dummy::DummyState;
+    use domain::fixtures::{get_channel, get_channel_spec, get_validator, ValidatorsOption};
+    use domain::validator::message::fixtures::get_approve_state;
+    use domain::BigNum;
+
+    use crate::infrastructure::persistence::validator::memory::MemoryMessageRepository;
+
+    use super::*;
+
+    fn channel() -> Channel {
+        let leader = get_validator("leader", None);
+        let follower = get_validator("follower", None);
+        let spec = get_channel_spec(ValidatorsOption::Pair { leader, follower });
+
+        get_channel("channel 1", &None, Some(spec))
+    }
+
+    /// The propagator of the validator, which shares the messages with the other validators
+    fn propagator(
+        repository: &MemoryMessageRepository<DummyState>,
+        identity: &ValidatorId,
+    ) -> MessagePropagator<DummyState> {
+        MessagePropagator {
+            message_repository: Arc::new(repository.sent_by(identity.clone())),
+        }
+    }
+
+    async fn send<'a>(
+        propagator: &'a MessagePropagator<DummyState>,
+        channel: &'a Channel,
+        message: Message<DummyState>,
+    ) {
+        for result in await!(propagator.propagate(channel, message)) {
+            result.expect("Propagating the message failed");
+        }
+    }
+
+    #[test]
+    fn the_latest_messages_of_the_leader_and_the_follower() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let leader = &channel.spec.validators.leader().id;
+            let follower = &channel.spec.validators.follower().id;
+            let mut balances = BTreeMap::new();
+            balances.insert("publisher".to_string(), BigNum::from(5));
+            let balances = BalancesMap::from(balances);
+
+            let repository = MemoryMessageRepository::new(leader.clone(), &[]);
+            let leader_propagator = propagator(&repository, leader);
+            let follower_propagator = propagator(&repository, follower);
+
+            let heartbeat =
+                || Message::Heartbeat(Heartbeat::new("sig".to_string(), "root".to_string()));
+            let new_state = NewState {
+                state_root: "root".to_string(),
+                signature: "sig".to_string(),
+                balances: balances.clone(),
+            };
+            let approve_state = get_approve_state("root".to_string(), "sig".to_string(), false);
+
+            // every message is propagated to both validators
+            await!(send(&leader_propagator, &channel, heartbeat()));
+            await!(send(
+                &leader_propagator,
+                &channel,
+                Message::NewState(new_state)
+            ));
+            await!(send(
+                &follower_propagator,
+                &channel,
+                Message::ApproveState(approve_state)
+            ));
+
+            let messages = await!(latest_messages(&channel, &repository))
+                .expect("Getting the latest messages failed");
+
+            assert!(messages.leader_heartbeat.is_some());
+            assert!(
+                messages.follower_heartbeat.is_none(),
+                "The Heartbeat of the leader, which was sent to the follower, isn't the follower's"
+            );
+            assert_eq!(Some(balances), messages.new_state_balances);
+            assert_eq!(Some(false), messages.approve_state_is_healthy);
+
+            await!(send(&follower_propagator, &channel, heartbeat()));
+            let messages = await!(latest_messages(&channel, &repository))
+                .expect("Getting the latest messages failed");
+            assert!(messages.follower_heartbeat.is_some());
+
+            let empty_repository: MemoryMessageRepository<DummyState> =
+                MemoryMessageRepository::new(leader.clone(), &[]);
+            let empty = await!(latest_messages(&channel, &empty_repository))
+                .expect("Getting the latest messages failed");
+            assert_eq!(None, empty.leader_heartbeat);
+            assert_eq!(None, empty.approve_state_is_healthy);
+        })
+    }
+}
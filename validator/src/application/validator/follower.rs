@@ -1,13 +1,535 @@
-use domain::Channel;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
-use crate::domain::validator::{Validator, ValidatorFuture};
 use futures::future::FutureExt;
 
-#[derive(Clone)]
-pub struct Follower {}
+use adapter::Adapter;
+use domain::validator::message::{
+    ApproveState, Message, NewState, RejectState, TYPE_APPROVE, TYPE_NEW, TYPE_REJECT,
+};
+use domain::{Channel, ValidatorId};
 
-impl Validator for Follower {
-    fn tick(&self, _channel: Channel) -> ValidatorFuture<()> {
-        futures::future::ok(()).boxed()
+use crate::application::validator::{
+    produce_accounting, propagate_tracked, responded_root, state_root, Undelivered,
+};
+use crate::application::MessagePropagator;
+use crate::domain::validator::{RejectReason, Validator, ValidatorError, ValidatorFuture};
+use crate::domain::{EventAggregateRepository, MessageRepository};
+
+pub struct Follower<A: Adapter> {
+    pub adapter: Arc<A>,
+    pub message_repository: Arc<dyn MessageRepository<A::State>>,
+    pub event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    pub propagator: Arc<MessagePropagator<A::State>>,
+    /// The minimum health (in promilles) of the leader's balances compared to our own accounting,
+    /// for the `ApproveState` to be healthy
+    pub health_threshold_promilles: u64,
+    /// The validators, which our latest `ApproveState` or `RejectState` failed to reach
+    pub undelivered: Arc<Undelivered<A::State>>,
+}
+
+// `#[derive(Clone)]` would require the Adapter to be `Clone` as well
+impl<A: Adapter> Clone for Follower<A> {
+    fn clone(&self) -> Self {
+        Self {
+            adapter: self.adapter.clone(),
+            message_repository: self.message_repository.clone(),
+            event_aggregate_repository: self.event_aggregate_repository.clone(),
+            propagator: self.propagator.clone(),
+            health_threshold_promilles: self.health_threshold_promilles,
+            undelivered: self.undelivered.clone(),
+        }
+    }
+}
+
+impl<A: Adapter + Send + Sync + 'static> Follower<A> {
+    /// Produces our own `Accounting` of the new event aggregates, then propagates
+    /// an `ApproveState` or a `RejectState` for the latest `NewState` of the leader.
+    /// If it has already been approved or rejected, our response is propagated again
+    /// only to the validators it failed to reach.
+    async fn validate(self, channel: Channel) -> Result<(), ValidatorError> {
+        let identity = ValidatorId::try_from(self.adapter.config().identity.as_str())
+            .map_err(ValidatorError::Domain)?;
+
+        let our_balances = await!(produce_accounting(
+            &identity,
+            &channel,
+            self.message_repository.as_ref(),
+            self.event_aggregate_repository.as_ref(),
+            &self.propagator
+        ))?;
+
+        let leader = &channel.spec.validators.leader().id;
+        let latest_future = self
+            .message_repository
+            .latest(&channel.id, leader, Some(&[&TYPE_NEW]));
+        let new_state = match await!(latest_future).map_err(ValidatorError::Repository)? {
+            Some(Message::NewState(new_state)) => new_state,
+            // there is no NewState yet
+            None => return Ok(()),
+            Some(_) => {
+                return Err(ValidatorError::User(
+                    "The repository returned a non-NewState message".to_string(),
+                ))
+            }
+        };
+
+        let types = [&TYPE_APPROVE, &TYPE_REJECT];
+        let latest_future = self
+            .message_repository
+            .latest(&channel.id, &identity, Some(&types));
+        let response = await!(latest_future).map_err(ValidatorError::Repository)?;
+        // the latest NewState has already been approved or rejected
+        if responded_root(response.as_ref())? == Some(&new_state.state_root) {
+            let validators =
+                self.undelivered
+                    .validators(&channel, &new_state.state_root, &identity);
+
+            return match response {
+                Some(response) if !validators.is_empty() => await!(propagate_tracked(
+                    &self.propagator,
+                    &self.undelivered,
+                    &channel,
+                    &validators,
+                    new_state.state_root,
+                    response
+                )),
+                _ => Ok(()),
+            };
+        }
+
+        let message = match await!(self.reject_reason(&channel, &identity, &new_state))? {
+            Some(reason) => Message::RejectState(RejectState {
+                reason: reason.to_string(),
+                state_root: new_state.state_root.clone(),
+            }),
+            None => {
+                let signature = await!(self.adapter.sign(&new_state.state_root))
+                    .map_err(ValidatorError::Adapter)?;
+
+                let is_healthy =
+                    our_balances.is_healthy(&new_state.balances, self.health_threshold_promilles);
+
+                Message::ApproveState(ApproveState {
+                    state_root: new_state.state_root.clone(),
+                    signature,
+                    is_healthy,
+                })
+            }
+        };
+        let validators: Vec<_> = channel.spec.validators.into_iter().collect();
+        await!(propagate_tracked(
+            &self.propagator,
+            &self.undelivered,
+            &channel,
+            &validators,
+            new_state.state_root,
+            message
+        ))
+    }
+
+    /// Checks the leader's signature, the `state_root` and the OUTPACE transition
+    /// from the last approved state, in this order.
+    /// Returns the reason to reject the `NewState` with, if any.
+    async fn reject_reason<'a>(
+        &'a self,
+        channel: &'a Channel,
+        identity: &'a ValidatorId,
+        new_state: &'a NewState<A::State>,
+    ) -> Result<Option<RejectReason>, ValidatorError> {
+        let leader = channel.spec.validators.leader();
+        let is_signed_by_leader = await!(self.adapter.verify(
+            leader.id.as_ref(),
+            &new_state.state_root,
+            &new_state.signature
+        ))
+        .map_err(ValidatorError::Adapter)?;
+        if !is_signed_by_leader {
+            return Ok(Some(RejectReason::InvalidSignature));
+        }
+
+        // the balances which can't be hashed are rejected as well
+        match state_root::<A>(channel, &new_state.balances) {
+            Ok(state_root) if state_root == new_state.state_root => {}
+            _ => return Ok(Some(RejectReason::InvalidRootHash)),
+        }
+
+        let last_approved_future =
+            self.message_repository
+                .last_approved(&channel.id, &leader.id, identity);
+        let last_approved = await!(last_approved_future).map_err(ValidatorError::Repository)?;
+        let approved_balances = last_approved
+            .map(|approved| approved.balances)
+            .unwrap_or_default();
+        if !approved_balances.is_valid_transition(&new_state.balances, channel) {
+            return Ok(Some(RejectReason::InvalidTransition));
+        }
+
+        Ok(None)
+    }
+}
+
+impl<A: Adapter + Send + Sync + 'static> Validator for Follower<A> {
+    fn tick(&self, channel: Channel) -> ValidatorFuture<()> {
+        self.clone().validate(channel).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use chrono::{Duration, Utc};
+
+    use adapter::dummy::{DummyAdapter, DummyState, DummyStateRoot};
+    use adapter::ConfigBuilder;
+    use domain::fixtures::{
+        get_balances_map, get_channel, get_channel_spec, get_validator, ValidatorsOption,
+    };
+    use domain::{BalancesMap, EventAggregate};
+
+    use crate::application::validator::fixtures::UnreachableMessageRepository;
+    use crate::infrastructure::persistence::event_aggregate::MemoryEventAggregateRepository;
+    use crate::infrastructure::persistence::validator::memory::MemoryMessageRepository;
+
+    use super::*;
+
+    fn channel() -> Channel {
+        let leader = get_validator("leader", Some(10.into()));
+        let follower = get_validator("follower", Some(10.into()));
+        let spec = get_channel_spec(ValidatorsOption::Pair { leader, follower });
+
+        let mut channel = get_channel("channel 1", &None, Some(spec));
+        channel.deposit_amount = 1_000.into();
+
+        channel
+    }
+
+    fn adapter(identity: &str) -> DummyAdapter<'static> {
+        DummyAdapter {
+            config: ConfigBuilder::new(identity).build(),
+            participants: HashMap::new(),
+        }
+    }
+
+    fn follower(
+        aggregates: &[EventAggregate],
+    ) -> (
+        Follower<DummyAdapter<'static>>,
+        Arc<MemoryMessageRepository<DummyState>>,
+    ) {
+        let identity = ValidatorId::try_from("follower").expect("ValidatorId failed");
+        let message_repository = Arc::new(MemoryMessageRepository::new(identity, &[]));
+
+        let follower = Follower {
+            adapter: Arc::new(adapter("follower")),
+            message_repository: message_repository.clone(),
+            event_aggregate_repository: Arc::new(MemoryEventAggregateRepository::new(aggregates)),
+            propagator: Arc::new(MessagePropagator {
+                message_repository: message_repository.clone(),
+            }),
+            health_threshold_promilles: 950,
+            undelivered: Arc::new(Undelivered::default()),
+        };
+
+        (follower, message_repository)
+    }
+
+    /// A follower, whose messages fail to reach the `unreachable` validators
+    fn unreachable_follower(
+        unreachable: &[&ValidatorId],
+    ) -> (
+        Follower<DummyAdapter<'static>>,
+        Arc<MemoryMessageRepository<DummyState>>,
+        Arc<UnreachableMessageRepository<DummyState>>,
+    ) {
+        let (follower, memory_repository) = follower(&[]);
+        let repository = Arc::new(UnreachableMessageRepository::new(
+            memory_repository.clone(),
+            unreachable,
+        ));
+
+        let follower = Follower {
+            message_repository: repository.clone(),
+            propagator: Arc::new(MessagePropagator {
+                message_repository: repository.clone(),
+            }),
+            ..follower
+        };
+
+        (follower, memory_repository, repository)
+    }
+
+    fn balances(publisher: u64) -> BalancesMap {
+        get_balances_map(&[("publisher", publisher)])
+    }
+
+    fn state_root_of(channel: &Channel, balances: &BalancesMap) -> DummyStateRoot {
+        state_root::<DummyAdapter>(channel, balances).expect("Calculating the state root failed")
+    }
+
+    /// Adds a `NewState` of the balances with the given state root, sent by the leader
+    /// and signed by the `signer`
+    async fn add_new_state<'a>(
+        repository: &'a MemoryMessageRepository<DummyState>,
+        channel: &'a Channel,
+        state_root: DummyStateRoot,
+        balances: BalancesMap,
+        signer: &'a str,
+    ) {
+        let signature = await!(adapter(signer).sign(&state_root)).expect("Signing failed");
+        let new_state = NewState {
+            state_root,
+            signature,
+            balances,
+        };
+
+        let leader = channel.spec.validators.leader();
+        let follower = channel.spec.validators.follower();
+        let sent_by_leader = repository.sent_by(leader.id.clone());
+        await!(sent_by_leader.add(&channel.id, follower, Message::NewState(new_state)))
+            .expect("Adding the NewState failed");
+    }
+
+    /// The latest `ApproveState` or `RejectState`, which the follower propagated
+    async fn latest_response<'a>(
+        repository: &'a MemoryMessageRepository<DummyState>,
+        channel: &'a Channel,
+    ) -> Option<Message<DummyState>> {
+        let identity = ValidatorId::try_from("follower").expect("ValidatorId failed");
+        let types = [&TYPE_APPROVE, &TYPE_REJECT];
+
+        await!(repository.latest(&channel.id, &identity, Some(&types)))
+            .expect("Getting the latest message failed")
+    }
+
+    async fn rejection_reason<'a>(
+        repository: &'a MemoryMessageRepository<DummyState>,
+        channel: &'a Channel,
+    ) -> String {
+        match await!(latest_response(repository, channel)) {
+            Some(Message::RejectState(reject_state)) => reject_state.reason,
+            _ => panic!("Expected a RejectState message"),
+        }
+    }
+
+    #[test]
+    fn approves_a_valid_new_state_of_the_leader() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (follower, repository) = follower(&[]);
+            let state_root = state_root_of(&channel, &balances(100));
+            await!(add_new_state(
+                &repository,
+                &channel,
+                state_root.clone(),
+                balances(100),
+                "leader"
+            ));
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            let approve_state = match await!(latest_response(&repository, &channel)) {
+                Some(Message::ApproveState(approve_state)) => approve_state,
+                _ => panic!("Expected an ApproveState message"),
+            };
+            assert_eq!(state_root, approve_state.state_root);
+            assert!(approve_state.is_healthy);
+
+            let signature = await!(follower.adapter.sign(&state_root)).expect("Signing failed");
+            assert_eq!(signature, approve_state.signature);
+        })
+    }
+
+    #[test]
+    fn propagates_the_approve_state_again_to_the_leader_it_failed_to_reach() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let leader_id = &channel.spec.validators.leader().id;
+            let follower_id = &channel.spec.validators.follower().id;
+            let (follower, memory_repository, repository) = unreachable_follower(&[leader_id]);
+            let state_root = state_root_of(&channel, &balances(100));
+            await!(add_new_state(
+                &memory_repository,
+                &channel,
+                state_root.clone(),
+                balances(100),
+                "leader"
+            ));
+
+            // our own Sentry gets the ApproveState, while the leader's doesn't
+            assert!(await!(follower.tick(channel.clone())).is_err());
+            assert_eq!(1, repository.added_to(follower_id).len());
+            assert!(repository.added_to(leader_id).is_empty());
+
+            repository.set_unreachable(&[]);
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            match repository.added_to(leader_id).as_slice() {
+                [Message::ApproveState(approve_state)] => {
+                    assert_eq!(state_root, approve_state.state_root)
+                }
+                _ => panic!("Expected only the ApproveState to be propagated to the leader"),
+            }
+            assert_eq!(
+                1,
+                repository.added_to(follower_id).len(),
+                "Our own Sentry already has the ApproveState"
+            );
+
+            // once it reached the leader, it's not propagated again
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+            assert_eq!(1, repository.added_to(leader_id).len());
+        })
+    }
+
+    #[test]
+    fn without_a_new_state_nothing_is_propagated() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (follower, repository) = follower(&[]);
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            assert!(await!(latest_response(&repository, &channel)).is_none());
+        })
+    }
+
+    #[test]
+    fn rejects_a_new_state_not_signed_by_the_leader() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (follower, repository) = follower(&[]);
+            let state_root = state_root_of(&channel, &balances(100));
+            await!(add_new_state(
+                &repository,
+                &channel,
+                state_root,
+                balances(100),
+                "impostor"
+            ));
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            let reason = await!(rejection_reason(&repository, &channel));
+            assert_eq!(RejectReason::InvalidSignature.to_string(), reason);
+        })
+    }
+
+    #[test]
+    fn rejects_a_new_state_with_an_invalid_state_root() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (follower, repository) = follower(&[]);
+            let state_root = "invalid state root".into();
+            await!(add_new_state(
+                &repository,
+                &channel,
+                state_root,
+                balances(100),
+                "leader"
+            ));
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            let reason = await!(rejection_reason(&repository, &channel));
+            assert_eq!(RejectReason::InvalidRootHash.to_string(), reason);
+        })
+    }
+
+    #[test]
+    fn rejects_balances_exceeding_the_deposit() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (follower, repository) = follower(&[]);
+            let state_root = state_root_of(&channel, &balances(1_001));
+            await!(add_new_state(
+                &repository,
+                &channel,
+                state_root,
+                balances(1_001),
+                "leader"
+            ));
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            let reason = await!(rejection_reason(&repository, &channel));
+            assert_eq!(RejectReason::InvalidTransition.to_string(), reason);
+        })
+    }
+
+    #[test]
+    fn rejects_balances_decreasing_since_the_last_approved_state() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (follower, repository) = follower(&[]);
+
+            let approved_root = state_root_of(&channel, &balances(100));
+            await!(add_new_state(
+                &repository,
+                &channel,
+                approved_root.clone(),
+                balances(100),
+                "leader"
+            ));
+            let approve_state = ApproveState {
+                state_root: approved_root.clone(),
+                signature: await!(follower.adapter.sign(&approved_root)).expect("Signing failed"),
+                is_healthy: true,
+            };
+            let to_follower = channel.spec.validators.follower();
+            await!(repository.add(
+                &channel.id,
+                to_follower,
+                Message::ApproveState(approve_state)
+            ))
+            .expect("Adding the ApproveState failed");
+
+            let state_root = state_root_of(&channel, &balances(50));
+            await!(add_new_state(
+                &repository,
+                &channel,
+                state_root,
+                balances(50),
+                "leader"
+            ));
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            let reason = await!(rejection_reason(&repository, &channel));
+            assert_eq!(RejectReason::InvalidTransition.to_string(), reason);
+        })
+    }
+
+    #[test]
+    fn the_approve_state_is_unhealthy_when_the_leader_under_reports() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let mut aggregate = EventAggregate::new(channel.id, Utc::now() - Duration::minutes(1));
+            aggregate
+                .events
+                .entry("IMPRESSION".to_string())
+                .or_default()
+                .event_payouts
+                .insert("publisher".to_string(), 1_000.into());
+            let (follower, repository) = follower(&[aggregate]);
+
+            let under_reported = get_balances_map(&[("publisher", 500), ("leader", 10)]);
+            let state_root = state_root_of(&channel, &under_reported);
+            await!(add_new_state(
+                &repository,
+                &channel,
+                state_root,
+                under_reported,
+                "leader"
+            ));
+
+            await!(follower.tick(channel.clone())).expect("The tick should succeed");
+
+            match await!(latest_response(&repository, &channel)) {
+                Some(Message::ApproveState(approve_state)) => assert!(!approve_state.is_healthy),
+                _ => panic!("Expected an ApproveState message"),
+            }
+        })
     }
 }
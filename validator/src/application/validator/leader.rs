@@ -1,13 +1,433 @@
-use domain::Channel;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
-use crate::domain::validator::{Validator, ValidatorFuture};
 use futures::future::FutureExt;
 
-#[derive(Clone)]
-pub struct Leader {}
+use adapter::Adapter;
+use domain::validator::message::{Message, NewState, TYPE_APPROVE, TYPE_NEW, TYPE_REJECT};
+use domain::{Channel, ValidatorId};
 
-impl Validator for Leader {
-    fn tick(&self, _channel: Channel) -> ValidatorFuture<()> {
-        futures::future::ok(()).boxed()
+use crate::application::validator::{
+    produce_accounting, propagate_tracked, responded_root, state_root, Undelivered,
+};
+use crate::application::MessagePropagator;
+use crate::domain::validator::{Validator, ValidatorError, ValidatorFuture};
+use crate::domain::{EventAggregateRepository, MessageRepository};
+
+pub struct Leader<A: Adapter> {
+    pub adapter: Arc<A>,
+    pub message_repository: Arc<dyn MessageRepository<A::State>>,
+    pub event_aggregate_repository: Arc<dyn EventAggregateRepository>,
+    pub propagator: Arc<MessagePropagator<A::State>>,
+    /// The validators, which our latest `NewState` failed to reach
+    pub undelivered: Arc<Undelivered<A::State>>,
+}
+
+// `#[derive(Clone)]` would require the Adapter to be `Clone` as well
+impl<A: Adapter> Clone for Leader<A> {
+    fn clone(&self) -> Self {
+        Self {
+            adapter: self.adapter.clone(),
+            message_repository: self.message_repository.clone(),
+            event_aggregate_repository: self.event_aggregate_repository.clone(),
+            propagator: self.propagator.clone(),
+            undelivered: self.undelivered.clone(),
+        }
+    }
+}
+
+impl<A: Adapter + Send + Sync + 'static> Leader<A> {
+    /// Produces the `Accounting` of the new event aggregates and if its balances differ from
+    /// the ones of our latest propagated `NewState`, propagates a signed `NewState` with them.
+    /// Otherwise our latest `NewState` is propagated again to the validators it failed to reach,
+    /// until the follower approves or rejects it.
+    async fn produce(self, channel: Channel) -> Result<(), ValidatorError> {
+        let identity = ValidatorId::try_from(self.adapter.config().identity.as_str())
+            .map_err(ValidatorError::Domain)?;
+
+        let balances = await!(produce_accounting(
+            &identity,
+            &channel,
+            self.message_repository.as_ref(),
+            self.event_aggregate_repository.as_ref(),
+            &self.propagator
+        ))?;
+
+        let latest_future =
+            self.message_repository
+                .latest(&channel.id, &identity, Some(&[&TYPE_NEW]));
+        let latest_new_state = match await!(latest_future).map_err(ValidatorError::Repository)? {
+            Some(Message::NewState(new_state)) => Some(new_state),
+            None => None,
+            Some(_) => {
+                return Err(ValidatorError::User(
+                    "The repository returned a non-NewState message".to_string(),
+                ))
+            }
+        };
+        let propagated_balances = latest_new_state
+            .as_ref()
+            .map(|new_state| new_state.balances.clone())
+            .unwrap_or_default();
+        if balances == propagated_balances {
+            return match latest_new_state {
+                Some(new_state) => await!(self.propagate_again(&identity, &channel, new_state)),
+                None => Ok(()),
+            };
+        }
+
+        let state_root = state_root::<A>(&channel, &balances).map_err(ValidatorError::Domain)?;
+        let signature = await!(self.adapter.sign(&state_root)).map_err(ValidatorError::Adapter)?;
+
+        let new_state = NewState {
+            state_root: state_root.clone(),
+            signature,
+            balances,
+        };
+        let validators: Vec<_> = channel.spec.validators.into_iter().collect();
+        await!(propagate_tracked(
+            &self.propagator,
+            &self.undelivered,
+            &channel,
+            &validators,
+            state_root,
+            Message::NewState(new_state)
+        ))
+    }
+
+    /// Propagates the `NewState` again to the validators it failed to reach,
+    /// unless the follower has already approved or rejected it
+    async fn propagate_again<'a>(
+        &'a self,
+        identity: &'a ValidatorId,
+        channel: &'a Channel,
+        new_state: NewState<A::State>,
+    ) -> Result<(), ValidatorError> {
+        let follower = &channel.spec.validators.follower().id;
+        let types = [&TYPE_APPROVE, &TYPE_REJECT];
+        let latest_future = self
+            .message_repository
+            .latest(&channel.id, follower, Some(&types));
+        let response = await!(latest_future).map_err(ValidatorError::Repository)?;
+        if responded_root(response.as_ref())? == Some(&new_state.state_root) {
+            return Ok(());
+        }
+
+        let validators = self
+            .undelivered
+            .validators(channel, &new_state.state_root, identity);
+        if validators.is_empty() {
+            return Ok(());
+        }
+
+        let state_root = new_state.state_root.clone();
+        await!(propagate_tracked(
+            &self.propagator,
+            &self.undelivered,
+            channel,
+            &validators,
+            state_root,
+            Message::NewState(new_state)
+        ))
+    }
+}
+
+impl<A: Adapter + Send + Sync + 'static> Validator for Leader<A> {
+    fn tick(&self, channel: Channel) -> ValidatorFuture<()> {
+        self.clone().produce(channel).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use chrono::{Duration, Utc};
+
+    use adapter::dummy::{DummyAdapter, DummyState};
+    use adapter::ConfigBuilder;
+    use domain::fixtures::{get_channel, get_channel_spec, get_validator, ValidatorsOption};
+    use domain::validator::message::{ApproveState, MessageType, TYPE_ACCOUNTING, TYPE_NEW};
+    use domain::{BigNum, EventAggregate};
+
+    use crate::application::validator::fixtures::UnreachableMessageRepository;
+    use crate::infrastructure::persistence::event_aggregate::MemoryEventAggregateRepository;
+    use crate::infrastructure::persistence::validator::memory::{
+        MemoryMessage, MemoryMessageRepository,
+    };
+
+    use super::*;
+
+    fn channel() -> Channel {
+        let leader = get_validator("leader", Some(10.into()));
+        let follower = get_validator("follower", Some(10.into()));
+        let spec = get_channel_spec(ValidatorsOption::Pair { leader, follower });
+
+        let mut channel = get_channel("channel 1", &None, Some(spec));
+        channel.deposit_amount = 1_000.into();
+
+        channel
+    }
+
+    fn leader(
+        aggregates: &[EventAggregate],
+        initial_messages: &[MemoryMessage<DummyState>],
+    ) -> (
+        Leader<DummyAdapter<'static>>,
+        Arc<MemoryMessageRepository<DummyState>>,
+    ) {
+        let adapter = DummyAdapter {
+            config: ConfigBuilder::new("leader").build(),
+            participants: HashMap::new(),
+        };
+        let identity = ValidatorId::try_from("leader").expect("ValidatorId failed");
+        let message_repository = Arc::new(MemoryMessageRepository::new(identity, initial_messages));
+
+        let leader = Leader {
+            adapter: Arc::new(adapter),
+            message_repository: message_repository.clone(),
+            event_aggregate_repository: Arc::new(MemoryEventAggregateRepository::new(aggregates)),
+            propagator: Arc::new(MessagePropagator {
+                message_repository: message_repository.clone(),
+            }),
+            undelivered: Arc::new(Undelivered::default()),
+        };
+
+        (leader, message_repository)
+    }
+
+    /// A leader, whose messages fail to reach the `unreachable` validators
+    fn unreachable_leader(
+        aggregates: &[EventAggregate],
+        initial_messages: &[MemoryMessage<DummyState>],
+        unreachable: &[&ValidatorId],
+    ) -> (
+        Leader<DummyAdapter<'static>>,
+        Arc<MemoryMessageRepository<DummyState>>,
+        Arc<UnreachableMessageRepository<DummyState>>,
+    ) {
+        let (leader, memory_repository) = leader(aggregates, initial_messages);
+        let repository = Arc::new(UnreachableMessageRepository::new(
+            memory_repository.clone(),
+            unreachable,
+        ));
+
+        let leader = Leader {
+            message_repository: repository.clone(),
+            propagator: Arc::new(MessagePropagator {
+                message_repository: repository.clone(),
+            }),
+            ..leader
+        };
+
+        (leader, memory_repository, repository)
+    }
+
+    /// The `Accounting` of the aggregate, already propagated by the leader,
+    /// so only the `NewState` is propagated on the next tick
+    async fn propagated_accounting<'a>(
+        channel: &'a Channel,
+        aggregate: &'a EventAggregate,
+    ) -> MemoryMessage<DummyState> {
+        let (leader, repository) = leader(&[aggregate.clone()], &[]);
+        await!(leader.tick(channel.clone())).expect("The tick should succeed");
+
+        MemoryMessage {
+            message: await!(latest(&repository, channel, &TYPE_ACCOUNTING))
+                .expect("The Accounting should be propagated"),
+            channel: channel.id,
+            from: ValidatorId::try_from("leader").expect("ValidatorId failed"),
+        }
+    }
+
+    /// The latest message of the type, which the leader propagated
+    async fn latest<'a>(
+        repository: &'a MemoryMessageRepository<DummyState>,
+        channel: &'a Channel,
+        message_type: &'a MessageType,
+    ) -> Option<Message<DummyState>> {
+        let identity = ValidatorId::try_from("leader").expect("ValidatorId failed");
+
+        await!(repository.latest(&channel.id, &identity, Some(&[message_type])))
+            .expect("Getting the latest message failed")
+    }
+
+    fn aggregate(channel: &Channel, event_type: &str, payout: u64) -> EventAggregate {
+        let mut aggregate = EventAggregate::new(channel.id, Utc::now() - Duration::minutes(1));
+        aggregate
+            .events
+            .entry(event_type.to_string())
+            .or_default()
+            .event_payouts
+            .insert("publisher".to_string(), payout.into());
+
+        aggregate
+    }
+
+    #[test]
+    fn propagates_the_accounting_and_the_signed_new_state() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let aggregate = aggregate(&channel, "IMPRESSION", 100);
+            let (leader, repository) = leader(&[aggregate.clone()], &[]);
+
+            await!(leader.tick(channel.clone())).expect("The tick should succeed");
+
+            let accounting = match await!(latest(&repository, &channel, &TYPE_ACCOUNTING)) {
+                Some(Message::Accounting(accounting)) => accounting,
+                _ => panic!("Expected an Accounting message"),
+            };
+            assert_eq!(aggregate.created, accounting.last_event_aggregate);
+            assert_eq!(BigNum::from(100), accounting.pre_fees.total());
+            assert_eq!(BigNum::from(100), accounting.balances.total());
+
+            let new_state = match await!(latest(&repository, &channel, &TYPE_NEW)) {
+                Some(Message::NewState(new_state)) => new_state,
+                _ => panic!("Expected a NewState message"),
+            };
+            assert_eq!(accounting.balances, new_state.balances);
+
+            let signature =
+                await!(leader.adapter.sign(&new_state.state_root)).expect("Signing failed");
+            assert_eq!(signature, new_state.signature);
+        })
+    }
+
+    #[test]
+    fn propagates_a_new_state_only_when_the_balances_changed() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (leader, repository) = leader(&[aggregate(&channel, "CLICK", 0)], &[]);
+
+            await!(leader.tick(channel.clone())).expect("The tick should succeed");
+
+            let accounting = await!(latest(&repository, &channel, &TYPE_ACCOUNTING));
+            assert!(accounting.is_some(), "The Accounting should be propagated");
+
+            let new_state = await!(latest(&repository, &channel, &TYPE_NEW));
+            assert!(new_state.is_none(), "The balances haven't changed");
+        })
+    }
+
+    #[test]
+    fn propagates_the_new_state_again_when_it_failed_to_propagate() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let aggregate = aggregate(&channel, "IMPRESSION", 100);
+            let (first_leader, repository) = leader(&[aggregate.clone()], &[]);
+            await!(first_leader.tick(channel.clone())).expect("The tick should succeed");
+
+            // only the Accounting got propagated, without new aggregates its balances are signed
+            let accounting = await!(latest(&repository, &channel, &TYPE_ACCOUNTING))
+                .expect("The Accounting should be propagated");
+            let accounting_only = MemoryMessage {
+                message: accounting.clone(),
+                channel: channel.id,
+                from: ValidatorId::try_from("leader").expect("ValidatorId failed"),
+            };
+            let (retrying_leader, repository) = leader(&[aggregate], &[accounting_only]);
+            await!(retrying_leader.tick(channel.clone())).expect("The tick should succeed");
+
+            let new_state = match await!(latest(&repository, &channel, &TYPE_NEW)) {
+                Some(Message::NewState(new_state)) => new_state,
+                _ => panic!("Expected a NewState message"),
+            };
+            match accounting {
+                Message::Accounting(accounting) => {
+                    assert_eq!(accounting.balances, new_state.balances)
+                }
+                _ => panic!("Expected an Accounting message"),
+            }
+        })
+    }
+
+    #[test]
+    fn propagates_the_new_state_again_to_the_follower_it_failed_to_reach() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let leader_id = &channel.spec.validators.leader().id;
+            let follower_id = &channel.spec.validators.follower().id;
+            let aggregate = aggregate(&channel, "IMPRESSION", 100);
+            let accounting = await!(propagated_accounting(&channel, &aggregate));
+            let (leader, memory_repository, repository) =
+                unreachable_leader(&[aggregate], &[accounting], &[follower_id]);
+
+            // our own Sentry gets the NewState, while the follower's doesn't
+            assert!(await!(leader.tick(channel.clone())).is_err());
+            assert_eq!(1, repository.added_to(leader_id).len());
+            assert!(repository.added_to(follower_id).is_empty());
+
+            repository.set_unreachable(&[]);
+            await!(leader.tick(channel.clone())).expect("The tick should succeed");
+
+            let new_state = match await!(latest(&memory_repository, &channel, &TYPE_NEW)) {
+                Some(Message::NewState(new_state)) => new_state,
+                _ => panic!("Expected a NewState message"),
+            };
+            match repository.added_to(follower_id).as_slice() {
+                [Message::NewState(propagated)] => {
+                    assert_eq!(new_state.state_root, propagated.state_root);
+                    assert_eq!(new_state.signature, propagated.signature);
+                }
+                _ => panic!("Expected only the NewState to be propagated to the follower"),
+            }
+            assert_eq!(
+                1,
+                repository.added_to(leader_id).len(),
+                "Our own Sentry already has the NewState"
+            );
+
+            // once it reached the follower, it's not propagated again
+            await!(leader.tick(channel.clone())).expect("The tick should succeed");
+            assert_eq!(1, repository.added_to(follower_id).len());
+        })
+    }
+
+    #[test]
+    fn does_not_propagate_the_new_state_again_once_the_follower_responded() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let follower_id = &channel.spec.validators.follower().id;
+            let aggregate = aggregate(&channel, "IMPRESSION", 100);
+            let accounting = await!(propagated_accounting(&channel, &aggregate));
+            let (leader, memory_repository, repository) =
+                unreachable_leader(&[aggregate], &[accounting], &[follower_id]);
+            assert!(await!(leader.tick(channel.clone())).is_err());
+
+            let new_state = match await!(latest(&memory_repository, &channel, &TYPE_NEW)) {
+                Some(Message::NewState(new_state)) => new_state,
+                _ => panic!("Expected a NewState message"),
+            };
+            let approve_state = Message::ApproveState(ApproveState {
+                state_root: new_state.state_root,
+                signature: "follower signature".to_string(),
+                is_healthy: true,
+            });
+            let sent_by_follower = memory_repository.sent_by(follower_id.clone());
+            await!(sent_by_follower.add(
+                &channel.id,
+                channel.spec.validators.leader(),
+                approve_state
+            ))
+            .expect("Adding the ApproveState failed");
+
+            repository.set_unreachable(&[]);
+            await!(leader.tick(channel.clone())).expect("The tick should succeed");
+
+            assert!(repository.added_to(follower_id).is_empty());
+        })
+    }
+
+    #[test]
+    fn without_new_aggregates_nothing_is_propagated() {
+        futures::executor::block_on(async {
+            let channel = channel();
+            let (leader, repository) = leader(&[], &[]);
+
+            await!(leader.tick(channel.clone())).expect("The tick should succeed");
+
+            let accounting = await!(latest(&repository, &channel, &TYPE_ACCOUNTING));
+            assert!(accounting.is_none());
+        })
     }
 }
@@ -6,20 +6,25 @@ pub mod single {
     use std::sync::Arc;
     use std::time::Duration;
 
+    use chrono::Utc;
     use futures::compat::Future01CompatExt;
     use futures::future::{FutureExt, TryFutureExt};
     use tokio::util::FutureExt as TokioFutureExt;
 
+    use domain::channel::status::{channel_status, ChannelStatus};
+    use domain::validator::message::State;
     use domain::{Channel, SpecValidator, ValidatorId};
 
-    use crate::application::validator::{Follower, Leader};
-    use crate::domain::{ChannelRepository, Validator, Worker, WorkerFuture};
+    use crate::application::validator::latest_messages;
+    use crate::domain::{ChannelRepository, MessageRepository, Validator, Worker, WorkerFuture};
 
     #[derive(Clone)]
-    pub struct TickWorker {
-        pub leader: Leader,
-        pub follower: Follower,
+    pub struct TickWorker<S: State> {
+        pub leader: Arc<dyn Validator>,
+        pub follower: Arc<dyn Validator>,
         pub channel_repository: Arc<dyn ChannelRepository>,
+        /// The latest messages of the channels are used for computing their status
+        pub message_repository: Arc<dyn MessageRepository<S>>,
         // @TODO: use the adapter(maybe?) instead of repeating the identity
         pub identity: String,
         // @TODO: Pass configuration by which this can be set
@@ -27,7 +32,7 @@ pub mod single {
     }
 
     /// Single tick worker
-    impl TickWorker {
+    impl<S: State + 'static> TickWorker<S> {
         pub async fn tick(self) -> Result<(), ()> {
             // @TODO: Update once we figure out if ValidatorId can fail from a &str
             let validator_id = ValidatorId::try_from(self.identity.as_str())
@@ -49,6 +54,21 @@ pub mod single {
         async fn handle_channel(self, channel: Channel) -> Result<(), ()> {
             let channel_id = channel.id;
 
+            let latest_future = latest_messages(&channel, self.message_repository.as_ref());
+            let messages = match await!(latest_future) {
+                Ok(messages) => messages,
+                Err(error) => {
+                    eprintln!("Channel {} latest messages failed: {:?}", channel_id, error);
+                    return Ok(());
+                }
+            };
+
+            let status = channel_status(&channel, &messages, Utc::now());
+            if status == ChannelStatus::Expired {
+                println!("Channel {} is expired, skipping it", channel_id);
+                return Ok(());
+            }
+
             // @TODO: Update once we figure out if ValidatorId can fail from a &str
             let validator_id = ValidatorId::try_from(self.identity.as_str())
                 .expect("ValidatorId doesn't have a failing case right now");
@@ -89,7 +109,7 @@ pub mod single {
         }
     }
 
-    impl Worker for TickWorker {
+    impl<S: State + 'static> Worker for TickWorker<S> {
         fn run(&self) -> WorkerFuture {
             self.clone().tick().boxed()
         }
@@ -103,17 +123,19 @@ pub mod infinite {
     use futures::future::{join, FutureExt};
     use tokio::timer::Delay;
 
+    use domain::validator::message::State;
+
     use crate::application::worker::TickWorker;
     use crate::domain::{Worker, WorkerFuture};
 
     #[derive(Clone)]
-    pub struct InfiniteWorker {
-        pub tick_worker: TickWorker,
+    pub struct InfiniteWorker<S: State> {
+        pub tick_worker: TickWorker<S>,
         pub ticks_wait_time: Duration,
     }
 
     /// Infinite tick worker
-    impl InfiniteWorker {
+    impl<S: State + 'static> InfiniteWorker<S> {
         pub async fn infinite(self) -> Result<(), ()> {
             let handle = self.clone();
             loop {
@@ -127,7 +149,7 @@ pub mod infinite {
         }
     }
 
-    impl Worker for InfiniteWorker {
+    impl<S: State + 'static> Worker for InfiniteWorker<S> {
         fn run(&self) -> WorkerFuture {
             self.clone().infinite().boxed()
         }
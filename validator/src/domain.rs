@@ -1,8 +1,10 @@
 pub use self::channel::ChannelRepository;
+pub use self::event_aggregate::EventAggregateRepository;
 pub use self::validator::MessageRepository;
 pub use self::validator::{Validator, ValidatorError, ValidatorFuture};
 pub use self::worker::{Worker, WorkerFuture};
 
 pub mod channel;
+pub mod event_aggregate;
 pub mod validator;
 pub mod worker;
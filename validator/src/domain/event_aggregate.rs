@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+
+use domain::{ChannelId, EventAggregate, RepositoryFuture};
+
+pub trait EventAggregateRepository: Send + Sync {
+    /// Returns the aggregates of the Channel created after `after` (or all of them for `None`),
+    /// the oldest first
+    fn list(
+        &self,
+        channel: &ChannelId,
+        after: Option<DateTime<Utc>>,
+    ) -> RepositoryFuture<Vec<EventAggregate>>;
+}
@@ -1,8 +1,10 @@
+use std::fmt;
 use std::pin::Pin;
 
 use futures::Future;
 
-use domain::Channel;
+use adapter::AdapterError;
+use domain::{Channel, DomainError, RepositoryError};
 
 pub use self::repository::MessageRepository;
 
@@ -11,18 +13,46 @@ pub type ValidatorFuture<T> = Pin<Box<dyn Future<Output = Result<T, ValidatorErr
 #[derive(Debug)]
 pub enum ValidatorError {
     None,
+    Adapter(AdapterError),
+    Repository(RepositoryError),
+    Domain(DomainError),
+    /// When a repository returns unexpected data, e.g. a message of another type
+    User(String),
 }
 
-pub trait Validator {
+/// The machine-readable reason of a `RejectState`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// The `NewState` isn't signed by the Channel leader
+    InvalidSignature,
+    /// The `state_root` doesn't match the one of the `NewState` balances
+    InvalidRootHash,
+    /// The balances decrease since the last approved state or exceed the Channel deposit
+    InvalidTransition,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            RejectReason::InvalidSignature => "InvalidSignature",
+            RejectReason::InvalidRootHash => "InvalidRootHash",
+            RejectReason::InvalidTransition => "InvalidTransition",
+        };
+
+        write!(f, "{}", reason)
+    }
+}
+
+pub trait Validator: Send + Sync {
     fn tick(&self, channel: Channel) -> ValidatorFuture<()>;
 }
 
 pub mod repository {
-    use domain::validator::message::{Message, MessageType, State};
+    use domain::validator::message::{Message, MessageType, NewState, State};
     use domain::{ChannelId, RepositoryFuture, ValidatorDesc, ValidatorId};
 
-    pub trait MessageRepository<S: State> {
-        /// Adds a Message to the passed Validator
+    pub trait MessageRepository<S: State>: Send + Sync {
+        /// Adds a Message, sent by our validator, to the passed Validator
         /// Accepts ValidatorDesc instead of ValidatorId, as we need to know the Validator Url as well
         fn add(
             &self,
@@ -31,11 +61,21 @@ pub mod repository {
             message: Message<S>,
         ) -> RepositoryFuture<()>;
 
+        /// The latest Message of the Channel sent by the `from` Validator
         fn latest(
             &self,
             channel: &ChannelId,
             from: &ValidatorId,
             types: Option<&[&MessageType]>,
         ) -> RepositoryFuture<Option<Message<S>>>;
+
+        /// The latest `NewState` of the Channel sent by the `leader`,
+        /// which has an `ApproveState` of the same `state_root` sent by the `follower`
+        fn last_approved(
+            &self,
+            channel: &ChannelId,
+            leader: &ValidatorId,
+            follower: &ValidatorId,
+        ) -> RepositoryFuture<Option<NewState<S>>>;
     }
 }
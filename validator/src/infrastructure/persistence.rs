@@ -1,3 +1,4 @@
 pub mod api;
 pub mod channel;
+pub mod event_aggregate;
 pub mod validator;
@@ -0,0 +1,5 @@
+pub use self::api::ApiEventAggregateRepository;
+pub use self::memory::MemoryEventAggregateRepository;
+
+pub mod api;
+pub mod memory;
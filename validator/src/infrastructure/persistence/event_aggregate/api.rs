@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::{FutureExt, TryFutureExt};
+
+use adapter::Adapter;
+use domain::{ChannelId, EventAggregate, RepositoryFuture};
+
+use crate::domain::event_aggregate::EventAggregateRepository;
+use crate::infrastructure::persistence::api::ApiPersistenceError;
+use crate::infrastructure::sentry::SentryApi;
+
+// @TODO: make pub(crate)
+pub struct ApiEventAggregateRepository<A: Adapter> {
+    pub sentry: SentryApi,
+    /// Issues the `Adapter::get_auth` token of the validator for its Sentry on every request,
+    /// since a token is valid only around the era it was issued in
+    pub adapter: Arc<A>,
+}
+
+impl<A: Adapter + Send + Sync + 'static> EventAggregateRepository
+    for ApiEventAggregateRepository<A>
+{
+    fn list(
+        &self,
+        channel: &ChannelId,
+        after: Option<DateTime<Utc>>,
+    ) -> RepositoryFuture<Vec<EventAggregate>> {
+        let sentry = self.sentry.clone();
+        let channel = *channel;
+
+        // the Sentry of the validator runs with the same identity, so the token is issued for it
+        self.adapter
+            .get_auth(&self.adapter.config().identity)
+            // @TODO: Error handling
+            .map_err(|_error| ApiPersistenceError::Reading.into())
+            .and_then(move |auth_token| {
+                sentry
+                    .event_aggregates(channel, after, auth_token)
+                    // @TODO: Error handling
+                    .map_err(|_error| ApiPersistenceError::Reading.into())
+            })
+            .boxed()
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::{ready, FutureExt};
+
+use domain::{ChannelId, EventAggregate, RepositoryFuture};
+use memory_repository::MemoryRepository;
+
+use crate::domain::event_aggregate::EventAggregateRepository;
+
+// @TODO: make pub(crate)
+pub struct MemoryEventAggregateRepository {
+    inner: MemoryRepository<EventAggregate, bool>,
+}
+
+impl MemoryEventAggregateRepository {
+    pub fn new(initial_aggregates: &[EventAggregate]) -> Self {
+        let cmp = Arc::new(|_aggregate: &EventAggregate, should_match: &bool| *should_match);
+
+        Self {
+            inner: MemoryRepository::new(&initial_aggregates, cmp),
+        }
+    }
+}
+
+impl EventAggregateRepository for MemoryEventAggregateRepository {
+    fn list(
+        &self,
+        channel: &ChannelId,
+        after: Option<DateTime<Utc>>,
+    ) -> RepositoryFuture<Vec<EventAggregate>> {
+        let list = self
+            .inner
+            .list_all(|aggregate| {
+                let is_channel = &aggregate.channel_id == channel;
+                let is_after = after.map_or(true, |after| aggregate.created > after);
+
+                match (is_channel, is_after) {
+                    (true, true) => Some(aggregate.clone()),
+                    (_, _) => None,
+                }
+            })
+            .map(|mut aggregates| {
+                aggregates.sort_by_key(|aggregate| aggregate.created);
+                aggregates
+            });
+
+        ready(list.map_err(Into::into)).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use domain::fixtures::get_channel_id;
+
+    use super::*;
+
+    #[test]
+    fn lists_the_aggregates_of_the_channel_created_after() {
+        futures::executor::block_on(async {
+            let channel = get_channel_id("channel 1");
+            let now = Utc::now();
+            let aggregates = [
+                EventAggregate::new(channel, now - Duration::minutes(1)),
+                EventAggregate::new(get_channel_id("channel 2"), now),
+                EventAggregate::new(channel, now - Duration::minutes(2)),
+            ];
+            let repository = MemoryEventAggregateRepository::new(&aggregates);
+
+            let all = await!(repository.list(&channel, None)).expect("Listing failed");
+            assert_eq!(2, all.len());
+            assert_eq!(now - Duration::minutes(2), all[0].created);
+
+            let after = await!(repository.list(&channel, Some(now - Duration::minutes(2))))
+                .expect("Listing failed");
+            assert_eq!(1, after.len());
+            assert_eq!(now - Duration::minutes(1), after[0].created);
+        })
+    }
+}
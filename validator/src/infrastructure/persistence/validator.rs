@@ -1 +1,4 @@
+pub use self::api::ApiMessageRepository;
+
+pub mod api;
 pub mod memory;
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use futures::{FutureExt, TryFutureExt};
+
+use adapter::Adapter;
+use domain::validator::message::{Message, MessageType, NewState};
+use domain::{ChannelId, RepositoryFuture, ValidatorDesc, ValidatorId};
+
+use crate::domain::MessageRepository;
+use crate::infrastructure::persistence::api::ApiPersistenceError;
+use crate::infrastructure::sentry::SentryApi;
+
+// @TODO: make pub(crate)
+/// The messages are added to the Sentry of every validator they are propagated to
+/// and are read from the Sentry of our validator, where the other validators propagate theirs
+pub struct ApiMessageRepository<A: Adapter> {
+    pub sentry: SentryApi,
+    /// Issues the `Adapter::get_auth` token for the Sentry of the validator on every request,
+    /// since a token is valid only around the era it was issued in
+    pub adapter: Arc<A>,
+}
+
+impl<A: Adapter + Send + Sync + 'static> MessageRepository<A::State> for ApiMessageRepository<A> {
+    fn add(
+        &self,
+        for_channel: &ChannelId,
+        to_validator: &ValidatorDesc,
+        message: Message<A::State>,
+    ) -> RepositoryFuture<()> {
+        let sentry = self.sentry.clone();
+        let channel = *for_channel;
+        let validator_url = to_validator.url.clone();
+
+        self.adapter
+            .get_auth(to_validator.id.as_ref())
+            // @TODO: Error handling
+            .map_err(|_error| ApiPersistenceError::Writing.into())
+            .and_then(move |auth_token| {
+                sentry
+                    .propagate(validator_url, channel, vec![message], auth_token)
+                    // @TODO: Error handling
+                    .map_err(|_error| ApiPersistenceError::Writing.into())
+            })
+            .boxed()
+    }
+
+    fn latest(
+        &self,
+        channel: &ChannelId,
+        from: &ValidatorId,
+        types: Option<&[&MessageType]>,
+    ) -> RepositoryFuture<Option<Message<A::State>>> {
+        let types = types
+            .unwrap_or(&[])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        self.sentry
+            .clone()
+            .validator_messages::<A::State>(*channel, from.clone(), types, 1)
+            .map_ok(|messages| messages.into_iter().next())
+            // @TODO: Error handling
+            .map_err(|_error| ApiPersistenceError::Reading.into())
+            .boxed()
+    }
+
+    /// The Sentry pairs the messages of the Channel leader & follower, which are the passed ones
+    fn last_approved(
+        &self,
+        channel: &ChannelId,
+        _leader: &ValidatorId,
+        _follower: &ValidatorId,
+    ) -> RepositoryFuture<Option<NewState<A::State>>> {
+        self.sentry
+            .clone()
+            .last_approved::<A::State>(*channel)
+            // @TODO: Error handling
+            .map_err(|_error| ApiPersistenceError::Reading.into())
+            .boxed()
+    }
+}
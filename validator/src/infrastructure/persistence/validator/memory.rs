@@ -2,10 +2,10 @@ use std::sync::Arc;
 
 use futures::future::{ready, FutureExt};
 
-use domain::validator::message::{MessageType, State};
+use domain::validator::message::{MessageType, NewState, State};
 use domain::validator::{Message, ValidatorId};
 use domain::{ChannelId, RepositoryFuture, ValidatorDesc};
-use memory_repository::MemoryRepository;
+use memory_repository::{MemoryRepository, MemoryRepositoryError};
 
 use crate::domain::validator::repository::MessageRepository;
 
@@ -18,38 +18,68 @@ impl State for MemoryState {
 }
 
 #[derive(Clone)]
-pub struct MemoryMessage {
-    pub message: Message<MemoryState>,
+pub struct MemoryMessage<S: State = MemoryState> {
+    pub message: Message<S>,
     pub channel: ChannelId,
-    /// As we are not dealing with any URL requests here, we can only store and use the ValidatorId
-    pub owner: ValidatorId,
+    /// The Validator that sent the message, like the `from` of the messages stored in Sentry
+    pub from: ValidatorId,
 }
 
-pub struct MemoryMessageRepository {
-    inner: MemoryRepository<MemoryMessage, bool>,
+/// Keeps the messages of all the Validators in memory, as a Sentry would.
+/// The added messages are sent by the `identity` Validator, no matter who they are added to.
+pub struct MemoryMessageRepository<S: State = MemoryState> {
+    inner: MemoryRepository<MemoryMessage<S>, bool>,
+    identity: ValidatorId,
 }
 
-impl MemoryMessageRepository {
-    pub fn new(initial_messages: &[MemoryMessage]) -> Self {
-        let cmp = Arc::new(|_message: &MemoryMessage, should_match: &bool| *should_match);
+impl<S: State> MemoryMessageRepository<S> {
+    pub fn new(identity: ValidatorId, initial_messages: &[MemoryMessage<S>]) -> Self {
+        let cmp = Arc::new(|_message: &MemoryMessage<S>, should_match: &bool| *should_match);
 
         Self {
             inner: MemoryRepository::new(&initial_messages, cmp),
+            identity,
         }
     }
+
+    /// A repository sharing the same messages, which adds them as sent by another Validator
+    pub fn sent_by(&self, identity: ValidatorId) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            identity,
+        }
+    }
+
+    /// The messages of the Channel sent by the `from` Validator, the newest are last
+    fn messages_from(
+        &self,
+        channel: &ChannelId,
+        from: &ValidatorId,
+    ) -> Result<Vec<Message<S>>, MemoryRepositoryError> {
+        self.inner
+            .list_all(|mem_msg| {
+                if &mem_msg.from == from && &mem_msg.channel == channel {
+                    Some(mem_msg.clone())
+                } else {
+                    None
+                }
+            })
+            .map(|memory_messages| memory_messages.into_iter().map(|mem| mem.message).collect())
+    }
 }
 
-impl MessageRepository<MemoryState> for MemoryMessageRepository {
+impl<S: State> MessageRepository<S> for MemoryMessageRepository<S> {
+    /// The messages are shared, so propagating one to every Validator stores a copy per Validator
     fn add(
         &self,
         for_channel: &ChannelId,
-        to_validator: &ValidatorDesc,
-        message: Message<MemoryState>,
+        _to_validator: &ValidatorDesc,
+        message: Message<S>,
     ) -> RepositoryFuture<()> {
         let message = MemoryMessage {
             message,
             channel: *for_channel,
-            owner: to_validator.id.clone(),
+            from: self.identity.clone(),
         };
         // this should never match against the new record, that's why always pass false.
         ready(self.inner.add(&false, message).map_err(Into::into)).boxed()
@@ -63,26 +93,58 @@ impl MessageRepository<MemoryState> for MemoryMessageRepository {
         channel: &ChannelId,
         from: &ValidatorId,
         types: Option<&[&MessageType]>,
-    ) -> RepositoryFuture<Option<Message<MemoryState>>> {
-        let latest = self
-            .inner
-            .list_all(|mem_msg| {
-                let is_from = &mem_msg.owner == from;
-                let is_channel = &mem_msg.channel == channel;
-                // if there are no types provided, it should match every type, i.e. default `true` for `None`
-                let is_in_types = types.map_or(true, |message_types| {
-                    mem_msg.message.is_types(message_types)
-                });
-
-                match (is_from, is_channel, is_in_types) {
-                    (true, true, true) => Some(mem_msg.clone()),
-                    (_, _, _) => None,
-                }
-            })
-            .map(|mut memory_messages| memory_messages.pop().map(|mem| mem.message));
+    ) -> RepositoryFuture<Option<Message<S>>> {
+        let latest = self.messages_from(channel, from).map(|messages| {
+            // if there are no types provided, it should match every type, i.e. `true` for `None`
+            messages
+                .into_iter()
+                .rev()
+                .find(|message| types.map_or(true, |message_types| message.is_types(message_types)))
+        });
 
         ready(latest.map_err(Into::into)).boxed()
     }
+
+    fn last_approved(
+        &self,
+        channel: &ChannelId,
+        leader: &ValidatorId,
+        follower: &ValidatorId,
+    ) -> RepositoryFuture<Option<NewState<S>>> {
+        let last_approved = self
+            .messages_from(channel, leader)
+            .and_then(|leader_messages| {
+                let follower_messages = self.messages_from(channel, follower)?;
+
+                Ok((leader_messages, follower_messages))
+            })
+            .map(|(leader_messages, follower_messages)| {
+                let approve_states: Vec<_> = follower_messages
+                    .iter()
+                    .filter_map(|message| match message {
+                        Message::ApproveState(approve_state) => Some(approve_state),
+                        _ => None,
+                    })
+                    .collect();
+
+                // the newest messages are last
+                leader_messages
+                    .iter()
+                    .rev()
+                    .filter_map(|message| match message {
+                        Message::NewState(new_state) => Some(new_state),
+                        _ => None,
+                    })
+                    .find(|new_state| {
+                        approve_states
+                            .iter()
+                            .any(|approve_state| approve_state.state_root == new_state.state_root)
+                    })
+                    .cloned()
+            });
+
+        ready(last_approved.map_err(Into::into)).boxed()
+    }
 }
 
 #[cfg(test)]
@@ -90,31 +152,38 @@ mod test {
     use std::convert::TryFrom;
 
     use domain::fixtures::get_channel_id;
-    use domain::validator::message::fixtures::{get_heartbeat, get_reject_state};
+    use domain::validator::message::fixtures::{
+        get_approve_state, get_heartbeat, get_new_state, get_reject_state,
+    };
 
     use super::*;
     use domain::validator::fixtures::get_validator;
-    use domain::validator::message::TYPE_REJECT;
+    use domain::validator::message::{TYPE_HEARTBEAT, TYPE_REJECT};
+
+    fn validator_id(identity: &str) -> ValidatorId {
+        ValidatorId::try_from(identity).expect("ValidatorId failed")
+    }
 
     fn get_reject_memory_message(
         channel: &ChannelId,
-        owner: &ValidatorId,
+        from: &ValidatorId,
         reject_reason: Option<String>,
     ) -> MemoryMessage {
         MemoryMessage {
             channel: channel.clone(),
-            owner: owner.clone(),
-            message: Message::RejectState(get_reject_state(reject_reason)),
+            from: from.clone(),
+            message: Message::RejectState(get_reject_state("root".to_string(), reject_reason)),
         }
     }
 
     #[test]
-    fn adds_message_for_validator() {
+    fn adds_message_sent_by_the_identity() {
         futures::executor::block_on(async {
-            let validator = get_validator("identity", None);
-            let repo = MemoryMessageRepository::new(&[]);
+            let identity = validator_id("identity");
+            let validator = get_validator("another validator", None);
+            let repo: MemoryMessageRepository = MemoryMessageRepository::new(identity.clone(), &[]);
 
-            let message = get_reject_state(None);
+            let message = get_reject_state("root".to_string(), None);
             let channel_id = get_channel_id("channel id");
 
             await!(repo.add(&channel_id, &validator, Message::RejectState(message)))
@@ -126,7 +195,7 @@ mod test {
                 .expect("Listing all Messages failed");
 
             assert_eq!(1, list_all.len());
-            assert_eq!(validator.id, list_all[0].owner);
+            assert_eq!(identity, list_all[0].from);
             assert_eq!(channel_id, list_all[0].channel);
         })
     }
@@ -137,14 +206,20 @@ mod test {
             let validator = get_validator("identity", None);
             let channel = get_channel_id("channel id");
 
-            let repo = MemoryMessageRepository::new(&[]);
+            let repo: MemoryMessageRepository =
+                MemoryMessageRepository::new(validator.id.clone(), &[]);
             // add an initial Reject message for checking latest ordering
-            let init_message =
-                Message::RejectState(get_reject_state(Some("Initial Message".to_string())));
+            let init_message = Message::RejectState(get_reject_state(
+                "root".to_string(),
+                Some("Initial Message".to_string()),
+            ));
             await!(repo.add(&channel, &validator, init_message))
                 .expect("Adding the initial message failed");
 
-            let new_message = Message::RejectState(get_reject_state(Some("my reason".to_string())));
+            let new_message = Message::RejectState(get_reject_state(
+                "root".to_string(),
+                Some("my reason".to_string()),
+            ));
             await!(repo.add(&channel, &validator, new_message)).expect("Adding a message failed");
 
             let latest_any = await!(repo.latest(&channel, &validator.id, None))
@@ -160,14 +235,13 @@ mod test {
     #[test]
     fn getting_latest_message_filters_by_from() {
         futures::executor::block_on(async {
-            let validator = ValidatorId::try_from("identity").expect("ValidatorId failed");
+            let validator = validator_id("identity");
             let channel = get_channel_id("channel id");
 
-            let from =
-                ValidatorId::try_from("another validator").expect("ValidatorId for form failed");
+            let from = validator_id("another validator");
             let init_message = get_reject_memory_message(&channel, &validator, None);
 
-            let repo = MemoryMessageRepository::new(&[init_message]);
+            let repo = MemoryMessageRepository::new(validator.clone(), &[init_message]);
 
             let result =
                 await!(repo.latest(&channel, &from, None)).expect("Fetching latest message failed");
@@ -179,16 +253,57 @@ mod test {
         })
     }
 
+    #[test]
+    fn the_latest_message_is_of_the_sender_not_of_the_recipient() {
+        futures::executor::block_on(async {
+            let channel = get_channel_id("channel id");
+            let leader = get_validator("leader", None);
+            let follower = get_validator("follower", None);
+
+            let leader_repo: MemoryMessageRepository =
+                MemoryMessageRepository::new(leader.id.clone(), &[]);
+            let follower_repo = leader_repo.sent_by(follower.id.clone());
+
+            // every message is propagated to every validator
+            let heartbeat = |state_root: &str| {
+                Message::Heartbeat(get_heartbeat(state_root.to_string(), "sig".to_string()))
+            };
+            for validator in &[&leader, &follower] {
+                await!(leader_repo.add(&channel, validator, heartbeat("leader root")))
+                    .expect("Adding a message failed");
+            }
+            for validator in &[&leader, &follower] {
+                await!(follower_repo.add(&channel, validator, heartbeat("follower root")))
+                    .expect("Adding a message failed");
+            }
+
+            let heartbeat_root = |message: Option<Message<MemoryState>>| match message {
+                Some(Message::Heartbeat(heartbeat)) => heartbeat.state_root,
+                _ => panic!("Expected a Heartbeat message"),
+            };
+            let types = [&TYPE_HEARTBEAT];
+
+            let leader_heartbeat = await!(leader_repo.latest(&channel, &leader.id, Some(&types)))
+                .expect("Fetching latest message failed");
+            assert_eq!("leader root", heartbeat_root(leader_heartbeat));
+
+            let follower_heartbeat =
+                await!(leader_repo.latest(&channel, &follower.id, Some(&types)))
+                    .expect("Fetching latest message failed");
+            assert_eq!("follower root", heartbeat_root(follower_heartbeat));
+        })
+    }
+
     #[test]
     fn getting_latest_message_filters_by_channel_id() {
         futures::executor::block_on(async {
-            let validator = ValidatorId::try_from("identity").expect("ValidatorId failed");
+            let validator = validator_id("identity");
             let channel = get_channel_id("channel 1");
             let from_channel = get_channel_id("channel 2");
 
             let init_message = get_reject_memory_message(&channel, &validator, None);
 
-            let repo = MemoryMessageRepository::new(&[init_message]);
+            let repo = MemoryMessageRepository::new(validator.clone(), &[init_message]);
 
             let result = await!(repo.latest(&from_channel, &validator, None))
                 .expect("Fetching latest message failed");
@@ -203,7 +318,7 @@ mod test {
     #[test]
     fn getting_latest_message_filters_by_types() {
         futures::executor::block_on(async {
-            let validator = ValidatorId::try_from("identity").expect("ValidatorId failed");
+            let validator = validator_id("identity");
             let channel = get_channel_id("channel 1");
 
             let init_messages = [
@@ -214,11 +329,11 @@ mod test {
                         "signature".to_string(),
                     )),
                     channel: channel.clone(),
-                    owner: validator.clone(),
+                    from: validator.clone(),
                 },
             ];
 
-            let repo = MemoryMessageRepository::new(&init_messages);
+            let repo = MemoryMessageRepository::new(validator.clone(), &init_messages);
 
             let result = await!(repo.latest(&channel, &validator, Some(&[&TYPE_REJECT])))
                 .expect("Fetching latest message failed");
@@ -229,4 +344,60 @@ mod test {
             }
         })
     }
+
+    #[test]
+    fn last_approved_is_the_latest_new_state_of_the_leader_approved_by_the_follower() {
+        futures::executor::block_on(async {
+            let leader = validator_id("leader");
+            let follower = validator_id("follower");
+            let channel = get_channel_id("channel 1");
+
+            let memory_message = |from: &ValidatorId, message| MemoryMessage {
+                message,
+                channel,
+                from: from.clone(),
+            };
+            let new_state = |state_root: &str| {
+                let new_state = get_new_state::<MemoryState>(
+                    state_root.to_string(),
+                    "signature".to_string(),
+                    Default::default(),
+                );
+
+                memory_message(&leader, Message::NewState(new_state))
+            };
+            let approve_state = |from: &ValidatorId, state_root: &str| {
+                let approve_state = get_approve_state::<MemoryState>(
+                    state_root.to_string(),
+                    "signature".to_string(),
+                    true,
+                );
+
+                memory_message(from, Message::ApproveState(approve_state))
+            };
+
+            let repo = MemoryMessageRepository::new(
+                leader.clone(),
+                &[
+                    new_state("root 1"),
+                    approve_state(&follower, "root 1"),
+                    new_state("root 2"),
+                    approve_state(&follower, "root 2"),
+                    new_state("root 3"),
+                    // only the follower can approve the NewState
+                    approve_state(&leader, "root 3"),
+                ],
+            );
+
+            let last_approved = await!(repo.last_approved(&channel, &leader, &follower))
+                .expect("Fetching the last approved failed")
+                .expect("There should be an approved NewState");
+            assert_eq!("root 2", last_approved.state_root);
+
+            let other_channel = get_channel_id("channel 2");
+            let result = await!(repo.last_approved(&other_channel, &leader, &follower))
+                .expect("Fetching the last approved failed");
+            assert!(result.is_none());
+        })
+    }
 }
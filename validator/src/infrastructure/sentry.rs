@@ -1,11 +1,14 @@
-use domain::{Channel, ValidatorId};
+use chrono::{DateTime, Utc};
+use domain::validator::message::{Message, NewState, State};
+use domain::{Channel, ChannelId, EventAggregate, ValidatorId};
 use futures::compat::Future01CompatExt;
 use futures::future::{ok, try_join_all, FutureExt, TryFutureExt};
 use futures::Future;
 use futures_legacy::Future as LegacyFuture;
+use reqwest::header::AUTHORIZATION;
 use reqwest::r#async::{Client, Response};
 use reqwest::Error;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::iter::once;
 
 #[derive(Clone)]
@@ -76,6 +79,103 @@ impl SentryApi {
 
         await!(future.compat())
     }
+
+    /// Fetches the event aggregates of the Channel created after `after`, the oldest first
+    pub async fn event_aggregates(
+        self,
+        channel: ChannelId,
+        after: Option<DateTime<Utc>>,
+        auth_token: String,
+    ) -> Result<Vec<EventAggregate>, reqwest::Error> {
+        let mut url = format!("{}/channel/{}/events-aggregates", self.sentry_url, channel);
+        if let Some(after) = after {
+            url.push_str(&format!("?after={}", after.timestamp_millis()));
+        }
+
+        let future = self
+            .client
+            .get(url.as_str())
+            .header(AUTHORIZATION, format!("Bearer {}", auth_token))
+            .send()
+            .and_then(|mut res: Response| res.json::<EventAggregatesResponse>());
+
+        await!(future.compat()).map(|response| response.events)
+    }
+
+    /// Submits the messages of our validator to the Sentry at `validator_url`,
+    /// the `auth_token` authenticates us as the sender
+    pub async fn propagate<S: State>(
+        self,
+        validator_url: String,
+        channel: ChannelId,
+        messages: Vec<Message<S>>,
+        auth_token: String,
+    ) -> Result<(), reqwest::Error> {
+        let url = format!("{}/channel/{}/validator-messages", validator_url, channel);
+
+        let future = self
+            .client
+            .post(url.as_str())
+            .header(AUTHORIZATION, format!("Bearer {}", auth_token))
+            .json(&ValidatorMessagesRequest { messages })
+            .send()
+            .and_then(|res: Response| res.error_for_status());
+
+        await!(future.compat()).map(|_| ())
+    }
+
+    /// Fetches the latest messages of the Channel sent by the `from` validator, the newest first.
+    /// All the message types are fetched when `types` is empty.
+    pub async fn validator_messages<S: State>(
+        self,
+        channel: ChannelId,
+        from: ValidatorId,
+        types: Vec<String>,
+        limit: u32,
+    ) -> Result<Vec<Message<S>>, reqwest::Error> {
+        let mut url = format!(
+            "{}/channel/{}/validator-messages/{}",
+            self.sentry_url, channel, from
+        );
+        if !types.is_empty() {
+            url.push_str(&format!("/{}", types.join("+")));
+        }
+        url.push_str(&format!("?limit={}", limit));
+
+        let future = self
+            .client
+            .get(url.as_str())
+            .send()
+            .and_then(|mut res: Response| res.json::<ValidatorMessagesResponse<S>>());
+
+        await!(future.compat()).map(|response| {
+            response
+                .validator_messages
+                .into_iter()
+                .map(|validator_message| validator_message.msg)
+                .collect()
+        })
+    }
+
+    /// Fetches the latest `NewState` of the Channel leader, which the follower approved
+    pub async fn last_approved<S: State>(
+        self,
+        channel: ChannelId,
+    ) -> Result<Option<NewState<S>>, reqwest::Error> {
+        let url = format!("{}/channel/{}/last-approved", self.sentry_url, channel);
+
+        let future = self
+            .client
+            .get(url.as_str())
+            .send()
+            .and_then(|mut res: Response| res.json::<LastApprovedResponse<S>>());
+
+        await!(future.compat()).map(|response| {
+            response
+                .last_approved
+                .map(|last_approved| last_approved.new_state)
+        })
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -84,3 +184,42 @@ struct ChannelAllResponse {
     pub channels: Vec<Channel>,
     pub total_pages: u64,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EventAggregatesResponse {
+    pub events: Vec<EventAggregate>,
+}
+
+#[derive(Serialize)]
+#[serde(bound = "S: State")]
+struct ValidatorMessagesRequest<S: State> {
+    pub messages: Vec<Message<S>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", bound = "S: State")]
+struct ValidatorMessagesResponse<S: State> {
+    pub validator_messages: Vec<ValidatorMessageResponse<S>>,
+}
+
+/// The `channelId`, `from` & `received` of the message are known from the request
+#[derive(Deserialize)]
+#[serde(bound = "S: State")]
+struct ValidatorMessageResponse<S: State> {
+    pub msg: Message<S>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", bound = "S: State")]
+struct LastApprovedResponse<S: State> {
+    /// `null` if the follower hasn't approved any `NewState` yet
+    pub last_approved: Option<LastApproved<S>>,
+}
+
+/// Only the `NewState` of the `newState` & `approveState` pair is needed
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", bound = "S: State")]
+struct LastApproved<S: State> {
+    pub new_state: NewState<S>,
+}
@@ -2,11 +2,15 @@
 #![deny(rust_2018_idioms)]
 #![deny(clippy::all)]
 
+use std::collections::HashMap;
 use std::time::Duration;
 
+use adapter::dummy::DummyParticipant;
 use adapter::Adapter;
 use lazy_static::lazy_static;
 
+const DEFAULT_HEALTH_THRESHOLD_PROMILLES: u64 = 950;
+
 lazy_static! {
     static ref CONFIG: Config = {
         dotenv::dotenv().ok();
@@ -21,6 +25,10 @@ lazy_static! {
             .parse()
             .unwrap();
 
+        let health_threshold_promilles = std::env::var("VALIDATOR_HEALTH_THRESHOLD_PROMILLES")
+            .map(|threshold| threshold.parse().unwrap())
+            .unwrap_or(DEFAULT_HEALTH_THRESHOLD_PROMILLES);
+
         Config {
             validation_tick_timeout: Duration::from_millis(validation_tick_timeout),
             ticks_wait_time: Duration::from_millis(ticks_wait_time),
@@ -28,18 +36,21 @@ lazy_static! {
                 .unwrap()
                 .parse()
                 .unwrap(),
+            health_threshold_promilles,
         }
     };
 }
 
 fn main() {
     use adapter::dummy::DummyAdapter;
+    use adapter::ethereum::EthereumAdapter;
     use adapter::ConfigBuilder;
-    use clap::{App, Arg, SubCommand};
-    use std::collections::HashMap;
+    use clap::{App, AppSettings, Arg, SubCommand};
+    use std::path::Path;
 
     let matches = App::new("Validator worker")
         .version("0.2")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
         .arg(
             Arg::with_name("single-tick")
                 .short("s")
@@ -55,36 +66,60 @@ fn main() {
                         .index(1),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("ethereum")
+                .about("Runs the validator with the Ethereum adapter")
+                .arg(
+                    Arg::with_name("keystore")
+                        .long("keystore")
+                        .value_name("FILE")
+                        .help("The JSON keystore of the validator key, decrypted with VALIDATOR_KEYSTORE_PASSWORD")
+                        .required(true)
+                        .takes_value(true),
+                ),
+        )
         .get_matches();
 
     let is_single_tick = matches.is_present("single-tick");
 
-    let adapter = match matches.subcommand_matches("dummy") {
-        Some(dummy_matches) => {
+    match matches.subcommand() {
+        ("dummy", Some(dummy_matches)) => {
             let identity = dummy_matches.value_of("IDENTITY").unwrap();
 
-            DummyAdapter {
+            let adapter = DummyAdapter {
                 config: ConfigBuilder::new(identity).build(),
-                participants: HashMap::default(),
-            }
+                participants: dummy_participants(),
+            };
+
+            run(is_single_tick, adapter);
         }
-        None => panic!("We don't have any other adapters implemented yet!"),
-    };
+        ("ethereum", Some(ethereum_matches)) => {
+            let keystore = Path::new(ethereum_matches.value_of("keystore").unwrap());
+            let password = std::env::var("VALIDATOR_KEYSTORE_PASSWORD")
+                .expect("VALIDATOR_KEYSTORE_PASSWORD should be set");
+
+            let adapter = EthereumAdapter::from_keystore(keystore, &password)
+                .unwrap_or_else(|error| panic!("Loading the keystore failed: {}", error));
 
-    run(is_single_tick, adapter);
+            run(is_single_tick, adapter);
+        }
+        _ => unreachable!("A subcommand is required"),
+    }
 }
 
-fn run(is_single_tick: bool, adapter: impl Adapter) {
+fn run<A: Adapter + Send + Sync + 'static>(is_single_tick: bool, adapter: A) {
     use futures::future::{FutureExt, TryFutureExt};
     use reqwest::r#async::Client;
 
     use std::sync::Arc;
-    use validator::application::validator::{Follower, Leader};
+    use validator::application::validator::{Follower, Leader, Undelivered};
     use validator::application::worker::{InfiniteWorker, TickWorker};
+    use validator::application::MessagePropagator;
     use validator::domain::worker::Worker;
-    use validator::infrastructure::persistence::channel::{
-        ApiChannelRepository, MemoryChannelRepository,
-    };
+    use validator::domain::MessageRepository;
+    use validator::infrastructure::persistence::channel::ApiChannelRepository;
+    use validator::infrastructure::persistence::event_aggregate::ApiEventAggregateRepository;
+    use validator::infrastructure::persistence::validator::ApiMessageRepository;
     use validator::infrastructure::sentry::SentryApi;
 
     let sentry = SentryApi {
@@ -92,14 +127,45 @@ fn run(is_single_tick: bool, adapter: impl Adapter) {
         sentry_url: CONFIG.sentry_url.clone(),
     };
 
-    let _channel_repository = Arc::new(ApiChannelRepository { sentry });
-    let channel_repository = Arc::new(MemoryChannelRepository::new(&[]));
+    let identity = adapter.config().identity.to_string();
+    let adapter = Arc::new(adapter);
+
+    let event_aggregate_repository = Arc::new(ApiEventAggregateRepository {
+        sentry: sentry.clone(),
+        adapter: adapter.clone(),
+    });
+    let message_repository: Arc<dyn MessageRepository<A::State>> = Arc::new(ApiMessageRepository {
+        sentry: sentry.clone(),
+        adapter: adapter.clone(),
+    });
+    let channel_repository = Arc::new(ApiChannelRepository { sentry });
+
+    let propagator = Arc::new(MessagePropagator {
+        message_repository: message_repository.clone(),
+    });
+
+    let leader = Leader {
+        adapter: adapter.clone(),
+        message_repository: message_repository.clone(),
+        event_aggregate_repository: event_aggregate_repository.clone(),
+        propagator: propagator.clone(),
+        undelivered: Arc::new(Undelivered::default()),
+    };
+    let follower = Follower {
+        adapter,
+        message_repository: message_repository.clone(),
+        event_aggregate_repository,
+        propagator,
+        health_threshold_promilles: CONFIG.health_threshold_promilles,
+        undelivered: Arc::new(Undelivered::default()),
+    };
 
     let tick_worker = TickWorker {
-        leader: Leader {},
-        follower: Follower {},
+        leader: Arc::new(leader),
+        follower: Arc::new(follower),
         channel_repository,
-        identity: adapter.config().identity.to_string(),
+        message_repository,
+        identity,
         validation_tick_timeout: CONFIG.validation_tick_timeout,
     };
 
@@ -129,8 +195,24 @@ fn run(is_single_tick: bool, adapter: impl Adapter) {
     }
 }
 
+/// The auth. tokens of the dummy validators, the same ones are accepted by the dummy Sentry
+fn dummy_participants() -> HashMap<&'static str, DummyParticipant> {
+    ["awesomeLeader", "awesomeFollower"]
+        .iter()
+        .map(|&identity| {
+            let participant = DummyParticipant {
+                identity: identity.to_string(),
+                token: format!("AUTH_{}", identity),
+            };
+
+            (identity, participant)
+        })
+        .collect()
+}
+
 struct Config {
     pub validation_tick_timeout: Duration,
     pub ticks_wait_time: Duration,
     pub sentry_url: String,
+    pub health_threshold_promilles: u64,
 }